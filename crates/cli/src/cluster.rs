@@ -0,0 +1,554 @@
+//! `vc coordinator` and `vc worker`: one search spread over several machines.
+//!
+//! Workers connect over TCP and speak a line protocol. A worker opens with
+//! `hello <version>` and the coordinator answers `job <words>` (the search options, each
+//! hex-encoded so spaces survive) or `error <reason>`. Workers then send `attempts <total>`
+//! every [`HEARTBEAT`] and `hit <address> <private key>` for each match, until the
+//! coordinator sends `stop` or the connection drops.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use vanity_core::difficulty::format_count;
+use vanity_core::{
+    Address, AddressFormat, CancellationToken, CoreError, Difficulty, Match, MatchLimits,
+    MatchSieve, PatternSet, PrivateKey, SearchOptions,
+};
+use vanity_ui::app::{address_format, compile_patterns};
+use vanity_wallet::{
+    BitcoinVanityGenerator, CosmosVanityGenerator, EthereumVanityGenerator, SolanaVanityGenerator,
+    TonVanityGenerator,
+};
+
+use crate::{
+    address_bits_of, check_args, estimates, generator, parse_hex, search_difficulty, zero_bytes_of,
+    Args, Chain, Contract,
+};
+
+/// How often workers report their attempt count.
+const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// How often the coordinator prints the combined rate.
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Workers must run the same version, so both sides read the job's options alike.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A search as workers receive it: the options of a plain `vc` search, parsed like a command
+/// line on both sides. `vc serve` takes its jobs in the same form.
+pub(crate) struct Job {
+    pub(crate) words: Vec<String>,
+    pub(crate) args: Args,
+    pub(crate) format: AddressFormat,
+    pub(crate) patterns: PatternSet,
+}
+
+impl Job {
+    pub(crate) fn parse(words: Vec<String>) -> Result<Self, String> {
+        let argv = std::iter::once("vc".to_string()).chain(words.iter().cloned());
+        let args = Args::try_parse_from(argv).map_err(|e| e.to_string().trim().to_string())?;
+        if args.command.is_some() || args.score.is_some() || args.generate_batch.is_some() {
+            return Err(
+                "a job is a pattern search, not a command, --score or --generate-batch".to_string(),
+            );
+        }
+        if Contract::from_args(&args).is_some() || args.split_key.is_some() {
+            return Err(
+                "contract and split-key searches cannot be distributed or served".to_string(),
+            );
+        }
+        if args.prefix_file.is_some() || args.suffix_file.is_some() {
+            return Err(
+                "jobs cannot read word-list files; pass the words with --prefix and --suffix"
+                    .to_string(),
+            );
+        }
+        check_args(&args)?;
+        let (zero_bytes, address_bits) = (zero_bytes_of(&args), address_bits_of(&args));
+        if args.prefix.trim().is_empty()
+            && args.suffix.trim().is_empty()
+            && args.contains.is_empty()
+            && zero_bytes.is_none()
+            && address_bits.is_empty()
+        {
+            return Err(
+                "a job needs --prefix, --suffix, --contains or an address rule".to_string(),
+            );
+        }
+
+        let format = address_format(
+            args.chain.clone().into(),
+            args.network.clone().into(),
+            args.btc_type.clone().into(),
+            &args.hrp,
+        );
+        let patterns = compile_patterns(
+            &format,
+            &args.prefix,
+            &args.suffix,
+            args.case_sensitive,
+            &args.contains,
+            &args.exclude,
+        )
+        .map_err(CoreError::from)
+        .and_then(|patterns| patterns.validate(&format).map(|()| patterns))
+        .map_err(|e| e.to_string())?;
+        Ok(Self {
+            words,
+            args,
+            format,
+            patterns,
+        })
+    }
+
+    pub(crate) fn limits(&self) -> MatchLimits {
+        MatchLimits {
+            count: self.args.count as usize,
+            per_pattern: self.args.per_pattern.map(|cap| cap as usize),
+        }
+    }
+
+    pub(crate) fn difficulty(&self) -> Difficulty {
+        search_difficulty(
+            &self.format,
+            &self.patterns,
+            zero_bytes_of(&self.args),
+            &address_bits_of(&self.args),
+        )
+    }
+
+    /// The `job` line sent to workers.
+    fn line(&self) -> String {
+        let words: Vec<String> = self.words.iter().map(hex::encode).collect();
+        format!("job {}", words.join(" "))
+    }
+
+    /// Re-derives the address of a worker's key and checks it against the job, so a faulty or
+    /// dishonest worker cannot end the search.
+    fn verify(&self, address: &str, private_key: &str) -> Result<Match, String> {
+        let (private_key, derived) = match self.args.chain {
+            Chain::Ethereum => {
+                let key = parse_hex::<32>(private_key)?;
+                let address =
+                    EthereumVanityGenerator::derive_address(&key).ok_or("not a secp256k1 key")?;
+                (PrivateKey::Ethereum(key), Address::Ethereum(address))
+            }
+            Chain::Bitcoin => {
+                let key = bitcoin::PrivateKey::from_wif(private_key).map_err(|e| e.to_string())?;
+                let secp = bitcoin::secp256k1::Secp256k1::new();
+                let address = BitcoinVanityGenerator::derive_address(
+                    &secp,
+                    self.args.network.clone().into(),
+                    self.args.btc_type.clone().into(),
+                    key.inner.public_key(&secp),
+                );
+                (
+                    PrivateKey::Bitcoin(key.to_string()),
+                    Address::Bitcoin(address),
+                )
+            }
+            Chain::Solana => {
+                let keypair: [u8; 64] = bs58::decode(private_key)
+                    .into_vec()
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or("not a Base58 Solana keypair")?;
+                let seed: [u8; 32] = keypair[..32].try_into().expect("32 bytes");
+                let public_key = ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key();
+                if keypair[32..] != public_key.as_bytes()[..] {
+                    return Err("the keypair's public key does not belong to its seed".to_string());
+                }
+                let address = SolanaVanityGenerator::derive_address(public_key.as_bytes());
+                (PrivateKey::Solana(keypair), Address::Solana(address))
+            }
+            Chain::Ton => {
+                let seed = parse_hex::<32>(private_key)?;
+                let public_key = ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key();
+                let version = self.args.ton_version.clone().into();
+                // Bounceable addresses start with 'E'
+                let address = if address.starts_with('E') {
+                    TonVanityGenerator::derive_bounceable_address(version, public_key.as_bytes())
+                } else {
+                    TonVanityGenerator::derive_address(version, public_key.as_bytes())
+                };
+                (PrivateKey::Ton(seed), Address::Ton(address))
+            }
+            Chain::Cosmos => {
+                let key = parse_hex::<32>(private_key)?;
+                let address = CosmosVanityGenerator::derive_address(&self.args.hrp, &key)
+                    .map_err(|e| e.to_string())?;
+                (PrivateKey::Cosmos(key), Address::Cosmos(address))
+            }
+        };
+        if derived.to_string() != address {
+            return Err(format!("the key derives {}, not {}", derived, address));
+        }
+        if let Address::Ethereum(bytes) = &derived {
+            let zero_bytes = zero_bytes_of(&self.args);
+            if zero_bytes.is_some_and(|zero_bytes| !zero_bytes.matches(bytes))
+                || !address_bits_of(&self.args)
+                    .iter()
+                    .all(|bits| bits.matches(bytes))
+            {
+                return Err(format!("{} breaks the job's address rules", derived));
+            }
+        }
+        let pattern = self
+            .patterns
+            .find_address(&derived)
+            .ok_or_else(|| format!("{} matches no pattern of the job", derived))?;
+        Ok(Match {
+            private_key,
+            address: derived,
+            pattern,
+        })
+    }
+}
+
+/// What the coordinator hears from its connections.
+enum Event {
+    Joined {
+        id: usize,
+        peer: SocketAddr,
+        stream: TcpStream,
+    },
+    Attempts {
+        id: usize,
+        total: u64,
+    },
+    Hit {
+        id: usize,
+        address: String,
+        private_key: String,
+    },
+    Left {
+        id: usize,
+    },
+}
+
+/// A worker as the coordinator tracks it.
+struct Worker {
+    peer: SocketAddr,
+    stream: TcpStream,
+    attempts: u64,
+    connected: bool,
+}
+
+/// `vc coordinator`: accepts workers, hands each the job, verifies their results and stops
+/// them all once the job's `--count` is reached. The job's `--timeout` and `--max-attempts`
+/// apply to the whole cluster.
+pub(crate) fn run_coordinator(listen: &str, words: Vec<String>) {
+    let job = Job::parse(words).unwrap_or_else(|e| {
+        eprintln!("Error: invalid job: {}", e);
+        std::process::exit(1);
+    });
+    let listener = TcpListener::bind(listen).unwrap_or_else(|e| {
+        eprintln!("Error: cannot listen on {}: {}", listen, e);
+        std::process::exit(1);
+    });
+    let local = listener
+        .local_addr()
+        .map_or_else(|_| listen.to_string(), |addr| addr.to_string());
+    println!("Coordinator listening on {}", local);
+    println!("Job: {}", job.words.join(" "));
+    let difficulty = job.difficulty();
+    if !difficulty.is_impossible() {
+        println!("Difficulty: {} (expected attempts)", difficulty.odds());
+    }
+
+    let (tx, rx) = mpsc::channel::<Event>();
+    let job_line = job.line();
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().enumerate() {
+            let Ok(stream) = stream else { continue };
+            let (tx, job_line) = (tx.clone(), job_line.clone());
+            thread::spawn(move || serve_worker(id + 1, stream, &job_line, &tx));
+        }
+    });
+
+    let started = Instant::now();
+    let deadline = job.args.timeout.map(|t| started + Duration::from_secs(t));
+    let labels = job.patterns.len() > 1;
+    let mut sieve = MatchSieve::new(job.limits(), job.patterns.len());
+    let mut workers: HashMap<usize, Worker> = HashMap::new();
+    let mut last_status = (started, 0u64);
+    let stop = |workers: &mut HashMap<usize, Worker>| {
+        for worker in workers.values_mut().filter(|worker| worker.connected) {
+            let _ = writeln!(worker.stream, "stop");
+        }
+    };
+
+    loop {
+        match rx.recv_timeout(HEARTBEAT) {
+            Ok(Event::Joined { id, peer, stream }) => {
+                println!("Worker {} joined from {}", id, peer);
+                workers.insert(
+                    id,
+                    Worker {
+                        peer,
+                        stream,
+                        attempts: 0,
+                        connected: true,
+                    },
+                );
+            }
+            Ok(Event::Attempts { id, total }) => {
+                if let Some(worker) = workers.get_mut(&id) {
+                    worker.attempts = total;
+                }
+            }
+            Ok(Event::Hit {
+                id,
+                address,
+                private_key,
+            }) => match job.verify(&address, &private_key) {
+                Ok(found) => {
+                    if sieve.admit(&found) {
+                        println!(
+                            "\nAddress: {}\nPrivate Key: {}",
+                            found.address, found.private_key
+                        );
+                        if let Some(pattern) = job.patterns.get(found.pattern).filter(|_| labels) {
+                            println!("Pattern: {}", pattern);
+                        }
+                        println!("Verified: found by worker {}", id);
+                    }
+                    if sieve.is_done() {
+                        stop(&mut workers);
+                        let total: u64 = workers.values().map(|worker| worker.attempts).sum();
+                        println!(
+                            "\nDone after {} keys in {:.1}s; stopped {} workers.",
+                            format_count(total as f64),
+                            started.elapsed().as_secs_f64(),
+                            workers.values().filter(|worker| worker.connected).count()
+                        );
+                        return;
+                    }
+                }
+                Err(e) => eprintln!("Warning: rejected a result from worker {}: {}", id, e),
+            },
+            Ok(Event::Left { id }) => {
+                if let Some(worker) = workers.get_mut(&id) {
+                    worker.connected = false;
+                    println!("Worker {} ({}) left", id, worker.peer);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        let total: u64 = workers.values().map(|worker| worker.attempts).sum();
+        let limit_reached = deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || job.args.max_attempts.is_some_and(|max| total >= max);
+        if limit_reached {
+            stop(&mut workers);
+            match sieve.accepted() {
+                0 => {
+                    eprintln!("Error: Search limit reached without a match.");
+                    std::process::exit(1);
+                }
+                found => {
+                    eprintln!(
+                        "Warning: Search limit reached after {} of {} matches.",
+                        found, job.args.count
+                    );
+                    return;
+                }
+            }
+        }
+
+        let (since, counted) = last_status;
+        if since.elapsed() >= STATUS_INTERVAL {
+            let rate = total.saturating_sub(counted) as f64 / since.elapsed().as_secs_f64();
+            let connected = workers.values().filter(|worker| worker.connected).count();
+            let mut status = format!(
+                "Workers: {}, {} keys at {} keys/s",
+                connected,
+                format_count(total as f64),
+                format_count(rate)
+            );
+            if !difficulty.is_impossible() && rate > 0.0 {
+                status.push_str(&format!("; {}", estimates(&difficulty, rate)));
+            }
+            println!("{}", status);
+            last_status = (Instant::now(), total);
+        }
+    }
+}
+
+/// Greets one worker, sends it the job and forwards what it reports.
+fn serve_worker(id: usize, stream: TcpStream, job_line: &str, tx: &mpsc::Sender<Event>) {
+    let (Ok(peer), Ok(reader)) = (stream.peer_addr(), stream.try_clone()) else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).lines();
+    let mut stream = stream;
+    match lines.next() {
+        Some(Ok(hello)) if hello == format!("hello {}", VERSION) => {}
+        Some(Ok(hello)) => {
+            let _ = writeln!(
+                stream,
+                "error the coordinator runs vc {}, not '{}'",
+                VERSION, hello
+            );
+            return;
+        }
+        _ => return,
+    }
+    if writeln!(stream, "{}", job_line).is_err() {
+        return;
+    }
+    if tx.send(Event::Joined { id, peer, stream }).is_err() {
+        return;
+    }
+
+    for line in lines {
+        let Ok(line) = line else { break };
+        let mut parts = line.split(' ');
+        let event = match (parts.next(), parts.next(), parts.next()) {
+            (Some("attempts"), Some(total), None) => match total.parse() {
+                Ok(total) => Event::Attempts { id, total },
+                Err(_) => continue,
+            },
+            (Some("hit"), Some(address), Some(private_key)) => Event::Hit {
+                id,
+                address: address.to_string(),
+                private_key: private_key.to_string(),
+            },
+            _ => continue,
+        };
+        if tx.send(event).is_err() {
+            return;
+        }
+    }
+    let _ = tx.send(Event::Left { id });
+}
+
+/// What a worker's main loop hears from its search and its coordinator.
+enum WorkerEvent {
+    Found(Match),
+    /// The search ended on its own: enough matches, or an error.
+    Finished(Result<(), CoreError>),
+    Stop,
+    Disconnected,
+}
+
+/// `vc worker`: runs the coordinator's job on all cores, sending heartbeats and matches,
+/// until told to stop.
+pub(crate) fn run_worker(connect: &str) {
+    let mut stream = TcpStream::connect(connect).unwrap_or_else(|e| {
+        eprintln!("Error: cannot connect to {}: {}", connect, e);
+        std::process::exit(1);
+    });
+    let reader = stream.try_clone().expect("TCP stream clone");
+    let mut lines = BufReader::new(reader).lines();
+    let greeting = writeln!(stream, "hello {}", VERSION)
+        .ok()
+        .and_then(|()| lines.next())
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    let job = match greeting.split_once(' ') {
+        Some(("job", words)) => words
+            .split(' ')
+            .map(|word| {
+                hex::decode(word)
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .ok_or_else(|| "malformed job".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(Job::parse),
+        Some(("error", reason)) => Err(reason.to_string()),
+        _ => Err("the coordinator sent no job".to_string()),
+    };
+    let job = job.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    println!("Connected to {}", connect);
+    println!("Job: {}", job.words.join(" "));
+
+    let (tx, rx) = mpsc::channel::<WorkerEvent>();
+    let attempts = Arc::new(AtomicU64::new(0));
+    let cancel = CancellationToken::new();
+
+    let coordinator_tx = tx.clone();
+    thread::spawn(move || {
+        for line in lines {
+            match line.as_deref() {
+                Ok("stop") => {
+                    let _ = coordinator_tx.send(WorkerEvent::Stop);
+                    return;
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        let _ = coordinator_tx.send(WorkerEvent::Disconnected);
+    });
+
+    // The coordinator enforces the job's limits across workers; this one only stops once it
+    // alone has found enough
+    let options = SearchOptions {
+        progress: Some(attempts.clone()),
+        cancel: cancel.clone(),
+        ..SearchOptions::default()
+    };
+    let search = thread::spawn(move || {
+        let mut sieve = MatchSieve::new(job.limits(), job.patterns.len());
+        let generator = generator(&job.args, job.patterns.clone());
+        let outcome = generator.search_each(&options, &mut |found| {
+            if sieve.admit(&found) {
+                let _ = tx.send(WorkerEvent::Found(found));
+            }
+            !sieve.is_done()
+        });
+        let _ = tx.send(WorkerEvent::Finished(outcome));
+    });
+
+    let mut last_beat = Instant::now();
+    let outcome = loop {
+        let event = rx.recv_timeout(HEARTBEAT);
+        let sent = match event {
+            Ok(WorkerEvent::Found(found)) => {
+                println!("Found {}", found.address);
+                writeln!(stream, "hit {} {}", found.address, found.private_key)
+            }
+            Ok(WorkerEvent::Finished(Err(e))) => break Err(format!("Search failed: {}", e)),
+            Ok(WorkerEvent::Finished(Ok(()))) | Err(mpsc::RecvTimeoutError::Timeout) => Ok(()),
+            Ok(WorkerEvent::Stop) => break Ok(()),
+            Ok(WorkerEvent::Disconnected) | Err(mpsc::RecvTimeoutError::Disconnected) => {
+                break Err("lost the connection to the coordinator".to_string())
+            }
+        };
+        let sent = sent.and_then(|()| {
+            if last_beat.elapsed() < HEARTBEAT {
+                return Ok(());
+            }
+            last_beat = Instant::now();
+            writeln!(stream, "attempts {}", attempts.load(Ordering::Relaxed))
+        });
+        if sent.is_err() {
+            break Err("lost the connection to the coordinator".to_string());
+        }
+    };
+
+    cancel.cancel();
+    let _ = search.join();
+    match outcome {
+        Ok(()) => println!(
+            "Stopped by the coordinator after {} keys.",
+            format_count(attempts.load(Ordering::Relaxed) as f64)
+        ),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,17 +1,31 @@
-use clap::{Parser, ValueEnum};
-use std::sync::atomic::AtomicU64;
-use std::sync::Arc;
+mod cluster;
+mod serve;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{sync::mpsc, thread};
-use vanity_core::VanityGenerator;
+use vanity_core::difficulty::{format_count, format_duration, CONFIDENCE_LEVELS, SLOWEST_RATE};
+use vanity_core::{
+    Address, AddressBits, AddressFormat, CancellationToken, CoreError, Difficulty, KeyKind,
+    Leaderboard, Match, MatchLimits, MatchSieve, PatternSet, PrivateKey, Score, SearchOptions,
+    VanityGenerator, ZeroBytes,
+};
 use vanity_ui::{
     app::{
-        BitcoinType as UiBtcType, Chain as UiChain, Network as UiNetwork,
-        TonVersion as UiTonVersion,
+        address_format, compile_patterns, zero_byte_summary, App, BitcoinType as UiBtcType,
+        Chain as UiChain, FoundKey, Network as UiNetwork, SearchEvent, TonVersion as UiTonVersion,
     },
     run_tui,
 };
 use vanity_wallet::{
-    BitcoinAddressType, BitcoinVanityGenerator, EthereumVanityGenerator, SolanaVanityGenerator,
+    combine_ed25519_split_key, combine_split_key, verify_expanded_key, AccountDeployment,
+    BitcoinAddressType, BitcoinVanityGenerator, Create2VanityGenerator, Create3VanityGenerator,
+    EthereumVanityGenerator, SafeDeployment, SafeVanityGenerator, SaltGuard, SolanaVanityGenerator,
+    CREATEX,
 };
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -132,15 +146,18 @@ fn convert_btc_type(t: UiBtcType) -> BitcoinAddressType {
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Blockchain to generate address for
-    #[arg(long, value_enum, default_value_t = Chain::Ethereum)]
+    #[arg(long, value_enum, default_value_t = Chain::Ethereum, global = true)]
     chain: Chain,
     /// Network (mainnet, testnet, regtest)
-    #[arg(long, value_enum, default_value_t = Network::Mainnet)]
+    #[arg(long, value_enum, default_value_t = Network::Mainnet, global = true)]
     network: Network,
 
     /// Bitcoin address type (only used if chain is bitcoin)
-    #[arg(long, value_enum, default_value_t = BtcType::Segwit)]
+    #[arg(long, value_enum, default_value_t = BtcType::Segwit, global = true)]
     btc_type: BtcType,
 
     /// TON wallet version (only used if chain is ton)
@@ -148,17 +165,138 @@ struct Args {
     ton_version: TonVersion,
 
     /// Human-Readable Part (HRP) for Cosmos addresses (e.g., cosmos, osmo, juno)
-    #[arg(long, default_value = "cosmos")]
+    #[arg(long, default_value = "cosmos", global = true)]
     hrp: String,
 
-    /// Prefix must start with this string (e.g., "0xDEAD")
+    /// Prefix must start with this pattern (e.g., "0xDEAD", "0x[0-9]{4}"); separate several with spaces to match any
     #[arg(short, long, default_value = "")]
     prefix: String,
 
-    /// Suffix must end with this string (e.g., "BEEF")
+    /// Suffix must end with this pattern (e.g., "BEEF", "?00"); several work like prefixes, but each is paired with every prefix, so only one of the two may list several
     #[arg(short, long, default_value = "")]
     suffix: String,
 
+    /// Read more prefixes from a file, one per line (blank lines and # comments are skipped)
+    #[arg(long, value_name = "FILE")]
+    prefix_file: Option<PathBuf>,
+
+    /// Read more suffixes from a file, one per line
+    #[arg(long, value_name = "FILE")]
+    suffix_file: Option<PathBuf>,
+
+    /// Address must contain this pattern anywhere (repeatable)
+    #[arg(long)]
+    contains: Vec<String>,
+
+    /// Address must not contain this pattern anywhere (repeatable)
+    #[arg(long)]
+    exclude: Vec<String>,
+
+    /// Ethereum address must start with at least N zero bytes (cheaper calldata)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..=20), conflicts_with = "zero_bytes")]
+    leading_zero_bytes: Option<u64>,
+
+    /// Ethereum address must have at least N zero bytes anywhere
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..=20))]
+    zero_bytes: Option<u64>,
+
+    /// Mine a CREATE2 salt for a contract deployed by this factory instead of a key (Ethereum)
+    #[arg(long, value_name = "ADDR", value_parser = parse_hex::<20>, requires = "init_code_hash")]
+    deployer: Option<[u8; 20]>,
+
+    /// keccak256 of the contract's init code, for --deployer
+    #[arg(long, value_name = "HASH", value_parser = parse_hex::<32>, requires = "deployer")]
+    init_code_hash: Option<[u8; 32]>,
+
+    /// Fixed leading salt bytes the factory requires, e.g. the caller address
+    #[arg(long, value_name = "HEX", value_parser = parse_hex_bytes, requires = "deployer")]
+    salt_prefix: Option<Box<[u8]>>,
+
+    /// Match the contract a fresh key's account deploys at this nonce (0 for its first)
+    #[arg(long, value_name = "N", conflicts_with_all = ["deployer", "create3", "safe_factory", "account_factory"])]
+    deploy_nonce: Option<u64>,
+
+    /// Mine a CreateX CREATE3 salt: the contract address depends only on the factory and salt
+    #[arg(long, conflicts_with_all = ["deployer", "safe_factory", "account_factory"])]
+    create3: bool,
+
+    /// CREATE3 factory if not the canonical CreateX
+    #[arg(long, value_name = "ADDR", value_parser = parse_hex::<20>, requires = "create3")]
+    factory: Option<[u8; 20]>,
+
+    /// Guard the CREATE3 salt so only this sender can deploy it
+    #[arg(long, value_name = "ADDR", value_parser = parse_hex::<20>, requires = "create3")]
+    guard_sender: Option<[u8; 20]>,
+
+    /// Guard the CREATE3 salt to this chain id (the address then differs per chain)
+    #[arg(long, value_name = "ID", requires = "create3")]
+    guard_chain_id: Option<u64>,
+
+    /// Mine a Gnosis Safe saltNonce for a proxy deployed by this SafeProxyFactory
+    #[arg(
+        long,
+        value_name = "ADDR",
+        value_parser = parse_hex::<20>,
+        requires_all = ["safe_singleton", "safe_initializer", "proxy_creation_code"],
+        conflicts_with_all = ["deployer", "account_factory"]
+    )]
+    safe_factory: Option<[u8; 20]>,
+
+    /// Safe implementation the proxy delegates to, for --safe-factory
+    #[arg(long, value_name = "ADDR", value_parser = parse_hex::<20>, requires = "safe_factory")]
+    safe_singleton: Option<[u8; 20]>,
+
+    /// Calldata of the Safe's setup call (owners, threshold, ...), for --safe-factory
+    #[arg(long, value_name = "HEX", value_parser = parse_hex_bytes, requires = "safe_factory")]
+    safe_initializer: Option<Box<[u8]>>,
+
+    /// The factory's proxyCreationCode(), for --safe-factory
+    #[arg(long, value_name = "HEX", value_parser = parse_hex_bytes, requires = "safe_factory")]
+    proxy_creation_code: Option<Box<[u8]>>,
+
+    /// Mine the salt of an ERC-4337 account created by this factory's
+    /// createAccount(owner, salt)
+    #[arg(
+        long,
+        value_name = "ADDR",
+        value_parser = parse_hex::<20>,
+        requires_all = ["account_init_code", "owner"],
+        conflicts_with = "deployer"
+    )]
+    account_factory: Option<[u8; 20]>,
+
+    /// Hex init code the factory deploys, with {owner} where the owner's address goes
+    #[arg(long, value_name = "TEMPLATE", value_parser = parse_init_code_template, requires = "account_factory")]
+    account_init_code: Option<String>,
+
+    /// Owner of the ERC-4337 account, for --account-factory
+    #[arg(long, value_name = "ADDR", value_parser = parse_hex::<20>, requires = "account_factory")]
+    owner: Option<[u8; 20]>,
+
+    /// Ethereum address bits under MASK must equal VALUE, e.g. 0x3fff=0x40 for Uniswap v4
+    /// hook flags (repeatable)
+    #[arg(long, value_name = "MASK=VALUE", value_parser = parse_mask)]
+    mask: Vec<AddressBits>,
+
+    /// Ethereum address must be numerically below this one
+    #[arg(long, value_name = "ADDR", value_parser = parse_uint160)]
+    below: Option<[u8; 20]>,
+
+    /// Ethereum address must be numerically above this one
+    #[arg(long, value_name = "ADDR", value_parser = parse_uint160)]
+    above: Option<[u8; 20]>,
+
+    /// Split-key mode: search offsets for this public key (secp256k1 hex, or ed25519 hex or
+    /// Base58 for Solana and TON) and print only the partial key; `vc combine` adds it to your
+    /// secret
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        value_parser = parse_split_key,
+        conflicts_with_all = ["deployer", "create3", "deploy_nonce", "safe_factory", "account_factory"]
+    )]
+    split_key: Option<SplitKey>,
+
     /// Case-sensitive matching
     #[arg(long, default_value_t = false)]
     case_sensitive: bool,
@@ -167,40 +305,439 @@ struct Args {
     #[arg(long, default_value_t = false)]
     no_tui: bool,
 
+    /// Give up after this many seconds without a match
+    #[arg(long)]
+    timeout: Option<u64>,
+
+    /// Give up after checking this many candidate keys
+    #[arg(long)]
+    max_attempts: Option<u64>,
+
+    /// Keep searching until this many distinct matches are found
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    count: u64,
+
+    /// Report at most this many matches per pattern
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    per_pattern: Option<u64>,
+
+    /// Keep the best addresses by score instead of matching a pattern (leading-zeros,
+    /// leading:<char>, run, target:<text>); needs --timeout or --max-attempts
+    #[arg(long, value_name = "SCORE")]
+    score: Option<Score>,
+
+    /// Size of the --score leaderboard
+    #[arg(long, value_name = "K", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    top: u64,
+
     /// Generate a batch of N random keys (JSON Lines format) for fuzzing
     #[arg(long)]
     generate_batch: Option<u64>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Add a split-key search's partial key to your secret and re-derive the address
+    Combine {
+        /// Your secret key (hex, or WIF for Bitcoin); for Solana and TON a seed, an expanded
+        /// key or a Solana keypair (hex or Base58)
+        #[arg(long, value_name = "KEY")]
+        secret: String,
+
+        /// Partial key the split-key search printed; omit to print the public key to hand out
+        #[arg(long, value_name = "KEY", value_parser = parse_secret)]
+        partial: Option<[u8; 32]>,
+
+        /// Address the search reported; exit non-zero if the combined key does not match it
+        #[arg(long, value_name = "ADDR")]
+        address: Option<String>,
+    },
+    /// Hand a search to `vc worker`s over TCP, verify their results and stop them when done
+    Coordinator {
+        /// Address to accept workers on, e.g. 0.0.0.0:7878 (port 0 picks a free one)
+        #[arg(long, value_name = "ADDR")]
+        listen: String,
+
+        /// The search, as options of a plain `vc` search, after `--`
+        #[arg(last = true, required = true, value_name = "SEARCH")]
+        job: Vec<String>,
+    },
+    /// Search for a `vc coordinator` until it says stop
+    Worker {
+        /// Coordinator address, e.g. 192.168.1.10:7878
+        #[arg(long, value_name = "ADDR")]
+        connect: String,
+    },
+    /// Accept searches as jobs over an HTTP/JSON API
+    Serve {
+        /// Address to serve on, e.g. 127.0.0.1:8080 (port 0 picks a free one)
+        #[arg(long, value_name = "ADDR")]
+        listen: String,
+
+        /// Jobs that may wait for a free slot; further submissions are refused
+        #[arg(long, default_value_t = 16)]
+        queue: usize,
+
+        /// Jobs that search at once, splitting the cores between them
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+    },
+}
+
+/// Public key a split-key search offsets; its curve decides the chains it works for.
+#[derive(Clone, Copy, Debug)]
+enum SplitKey {
+    /// Ethereum, Bitcoin and Cosmos.
+    Secp256k1(k256::PublicKey),
+    /// Solana and TON.
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl SplitKey {
+    fn secp256k1(self) -> Option<k256::PublicKey> {
+        match self {
+            SplitKey::Secp256k1(public_key) => Some(public_key),
+            SplitKey::Ed25519(_) => None,
+        }
+    }
+
+    fn ed25519(self) -> Option<ed25519_dalek::VerifyingKey> {
+        match self {
+            SplitKey::Ed25519(public_key) => Some(public_key),
+            SplitKey::Secp256k1(_) => None,
+        }
+    }
+
+    fn supports(&self, chain: &Chain) -> bool {
+        match self {
+            SplitKey::Secp256k1(_) => {
+                matches!(chain, Chain::Ethereum | Chain::Bitcoin | Chain::Cosmos)
+            }
+            SplitKey::Ed25519(_) => matches!(chain, Chain::Solana | Chain::Ton),
+        }
+    }
+}
+
+impl std::fmt::Display for SplitKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitKey::Secp256k1(public_key) => {
+                write!(f, "{}", hex::encode(public_key.to_encoded_point(true)))
+            }
+            SplitKey::Ed25519(public_key) => write!(f, "{}", hex::encode(public_key.as_bytes())),
+        }
+    }
+}
+
+/// Adds the split-key offset to a generator when one is given; each has `with_split_key`.
+macro_rules! with_split_key {
+    ($generator:expr, $split_key:expr) => {{
+        let generator = $generator;
+        match $split_key {
+            Some(public_key) => generator.with_split_key(public_key),
+            None => generator,
+        }
+    }};
+}
+
+/// Adds the zero-byte and bit rules to any Ethereum-address generator; each has
+/// `with_zero_bytes` and `with_bits`.
+macro_rules! with_address_rules {
+    ($generator:expr, $zero_bytes:expr, $address_bits:expr) => {{
+        let mut generator = $generator;
+        if let Some(zero_bytes) = $zero_bytes {
+            generator = generator.with_zero_bytes(zero_bytes);
+        }
+        for bits in $address_bits {
+            generator = generator.with_bits(*bits);
+        }
+        generator
+    }};
+}
+
+/// Contract deployment whose address is matched instead of an account's.
+#[derive(Clone, Debug)]
+enum Contract {
+    /// `--deploy-nonce`: a key whose account deploys the contract with CREATE.
+    Create { nonce: u64 },
+    /// `--deployer` and `--init-code-hash`.
+    Create2 {
+        deployer: [u8; 20],
+        init_code_hash: [u8; 32],
+        salt_prefix: Vec<u8>,
+    },
+    /// `--create3`, through CreateX.
+    Create3 { factory: [u8; 20], guard: SaltGuard },
+    /// `--safe-factory` and the Safe's parameters.
+    Safe(SafeDeployment),
+    /// `--account-factory`, `--account-init-code` and `--owner`.
+    Account(AccountDeployment),
+}
+
+impl Contract {
+    fn from_args(args: &Args) -> Option<Self> {
+        if args.create3 {
+            let guard = match (args.guard_sender, args.guard_chain_id) {
+                (None, None) => SaltGuard::None,
+                (Some(sender), None) => SaltGuard::Sender(sender),
+                (None, Some(chain_id)) => SaltGuard::ChainId(chain_id),
+                (Some(sender), Some(chain_id)) => SaltGuard::SenderAndChainId(sender, chain_id),
+            };
+            return Some(Contract::Create3 {
+                factory: args.factory.unwrap_or(CREATEX),
+                guard,
+            });
+        }
+        if let Some(nonce) = args.deploy_nonce {
+            return Some(Contract::Create { nonce });
+        }
+        if let Some(factory) = args.safe_factory {
+            return Some(Contract::Safe(SafeDeployment {
+                factory,
+                singleton: args.safe_singleton?,
+                proxy_creation_code: args.proxy_creation_code.clone()?.into(),
+                initializer: args.safe_initializer.clone()?.into(),
+            }));
+        }
+        if let Some(factory) = args.account_factory {
+            // The template was checked while parsing
+            let template = args.account_init_code.as_deref()?;
+            return AccountDeployment::from_template(factory, args.owner?, template)
+                .ok()
+                .map(Contract::Account);
+        }
+        Some(Contract::Create2 {
+            deployer: args.deployer?,
+            init_code_hash: args.init_code_hash?,
+            salt_prefix: args.salt_prefix.clone().unwrap_or_default().into(),
+        })
+    }
+
+    fn generator(
+        &self,
+        patterns: PatternSet,
+        zero_bytes: Option<ZeroBytes>,
+        address_bits: &[AddressBits],
+    ) -> Box<dyn VanityGenerator> {
+        match self {
+            Contract::Create { nonce } => Box::new(with_address_rules!(
+                EthereumVanityGenerator::from_patterns(patterns).with_deploy_nonce(*nonce),
+                zero_bytes,
+                address_bits
+            )),
+            Contract::Create2 {
+                deployer,
+                init_code_hash,
+                salt_prefix,
+            } => Box::new(with_address_rules!(
+                Create2VanityGenerator::from_patterns(patterns, *deployer, *init_code_hash)
+                    .with_salt_prefix(salt_prefix),
+                zero_bytes,
+                address_bits
+            )),
+            Contract::Create3 { factory, guard } => Box::new(with_address_rules!(
+                Create3VanityGenerator::from_patterns(patterns)
+                    .with_factory(*factory)
+                    .with_guard(*guard),
+                zero_bytes,
+                address_bits
+            )),
+            Contract::Safe(safe) => Box::new(with_address_rules!(
+                SafeVanityGenerator::from_patterns(patterns, safe),
+                zero_bytes,
+                address_bits
+            )),
+            // The factory passes the salt to CREATE2 unchanged
+            Contract::Account(account) => Box::new(with_address_rules!(
+                Create2VanityGenerator::from_patterns(
+                    patterns,
+                    account.factory,
+                    account.init_code_hash()
+                ),
+                zero_bytes,
+                address_bits
+            )),
+        }
+    }
+}
+
+impl Contract {
+    /// Whether results are salts rather than private keys.
+    fn mines_salt(&self) -> bool {
+        !matches!(self, Contract::Create { .. })
+    }
+}
+
+impl std::fmt::Display for Contract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Contract::Create { nonce } => {
+                write!(
+                    f,
+                    "keys whose account deploys the contract at nonce {}",
+                    nonce
+                )
+            }
+            Contract::Create2 { deployer, .. } => {
+                write!(f, "CREATE2 salts for deployer 0x{}", hex::encode(deployer))
+            }
+            Contract::Create3 { factory, guard } => {
+                write!(f, "CREATE3 salts for factory 0x{}", hex::encode(factory))?;
+                match guard {
+                    SaltGuard::None => write!(f, " (unguarded, same address on every chain)"),
+                    SaltGuard::Sender(sender) => {
+                        write!(f, " (only 0x{} may deploy)", hex::encode(sender))
+                    }
+                    SaltGuard::ChainId(chain_id) => write!(f, " (chain {} only)", chain_id),
+                    SaltGuard::SenderAndChainId(sender, chain_id) => write!(
+                        f,
+                        " (only 0x{} may deploy, chain {} only)",
+                        hex::encode(sender),
+                        chain_id
+                    ),
+                }
+            }
+            Contract::Safe(safe) => write!(
+                f,
+                "Safe saltNonce values for factory 0x{}",
+                hex::encode(safe.factory)
+            ),
+            Contract::Account(account) => write!(
+                f,
+                "ERC-4337 account salts for factory 0x{} and owner 0x{}",
+                hex::encode(account.factory),
+                hex::encode(account.owner)
+            ),
+        }
+    }
+}
+
+/// How long headless mode measures the key rate before printing time estimates.
+const RATE_SAMPLE: Duration = Duration::from_secs(1);
+
+/// How long the key rate is benchmarked before a search that might take years.
+const BENCHMARK: Duration = Duration::from_millis(500);
+
 fn main() {
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::Combine {
+            secret,
+            partial,
+            address,
+        }) => {
+            match args.chain {
+                Chain::Solana | Chain::Ton => {
+                    run_ed25519_combine(&args, secret, partial.as_ref(), address.as_deref())
+                }
+                _ => run_combine(&args, secret, partial.as_ref(), address.as_deref()),
+            }
+            return;
+        }
+        Some(Command::Coordinator { listen, job }) => {
+            cluster::run_coordinator(listen, job.clone());
+            return;
+        }
+        Some(Command::Worker { connect }) => {
+            cluster::run_worker(connect);
+            return;
+        }
+        Some(Command::Serve {
+            listen,
+            queue,
+            concurrency,
+        }) => {
+            serve::run_serve(listen, *queue, *concurrency);
+            return;
+        }
+        None => {}
+    }
+
+    // Chain-specific options are checked before any mode runs, so none of them drops one
+    if let Err(e) = check_args(&args) {
+        eprintln!("Error: {}.", e);
+        std::process::exit(1);
+    }
+
     // Check for batch generation
     if let Some(count) = args.generate_batch {
         run_batch_generation(count, &args);
         return;
     }
 
+    if let Some(score) = args.score.clone() {
+        run_scoring(score, &args);
+        return;
+    }
+
+    // Word lists extend the space-separated prefixes and suffixes
+    let prefixes = with_wordlist(&args.prefix, args.prefix_file.as_deref());
+    let suffixes = with_wordlist(&args.suffix, args.suffix_file.as_deref());
+    let zero_bytes = zero_bytes_of(&args);
+    let address_bits = address_bits_of(&args);
+    let contract = Contract::from_args(&args);
+
     // Determine start mode
-    let start_immediately = !args.prefix.is_empty() || !args.suffix.is_empty();
+    let start_immediately = !prefixes.trim().is_empty()
+        || !suffixes.trim().is_empty()
+        || !args.contains.is_empty()
+        || zero_bytes.is_some()
+        || !address_bits.is_empty();
 
-    // Validate if provided
-    let prefix = if let Some(stripped) = args.prefix.strip_prefix("0x") {
-        stripped.to_string()
-    } else {
-        args.prefix.clone()
-    };
+    // Reject patterns no address can match before starting any search
+    if start_immediately {
+        let format = address_format(
+            args.chain.clone().into(),
+            args.network.clone().into(),
+            args.btc_type.clone().into(),
+            &args.hrp,
+        );
+        let patterns = compile_patterns(
+            &format,
+            &prefixes,
+            &suffixes,
+            args.case_sensitive,
+            &args.contains,
+            &args.exclude,
+        );
+        let checked = patterns
+            .map_err(CoreError::from)
+            .and_then(|patterns| patterns.validate(&format));
+        if let Err(e) = checked {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
 
     // Shared state
     let attempts = Arc::new(AtomicU64::new(0));
-    // Channel sends (Address, PrivateKey) strings
-    let (tx, rx) = mpsc::channel::<(String, String)>();
+    // Channel sends the search outcome (found Address/PrivateKey strings, or why it stopped)
+    let (tx, rx) = mpsc::channel::<SearchEvent>();
     let attempts_clone = attempts.clone();
 
+    // Cancelled on exit so running workers stop instead of spinning until the process dies
+    let cancel = CancellationToken::new();
+    let workers: Arc<Mutex<Vec<thread::JoinHandle<()>>>> = Arc::default();
+    let cancel_clone = cancel.clone();
+    let workers_clone = workers.clone();
+    let timeout = args.timeout.map(Duration::from_secs);
+    let max_attempts = args.max_attempts;
+    let count = args.count as usize;
+    let limits = MatchLimits {
+        count,
+        per_pattern: args.per_pattern.map(|cap| cap as usize),
+    };
+
     // Capture configuration
     let cli_chain = args.chain.clone();
     let cli_network = args.network.clone();
     let cli_btc_type = args.btc_type.clone();
+    let contains = args.contains.clone();
+    let excludes = args.exclude.clone();
+    let search_rules = SearchRules::from_args(&args);
+    let benchmark_rules = search_rules.clone();
+    let split_key = args.split_key;
 
     let on_search_start = move |p_prefix: String,
                                 p_suffix: String,
@@ -210,67 +747,75 @@ fn main() {
                                 p_btc_type: UiBtcType,
                                 p_ton_version: UiTonVersion,
                                 p_hrp: String| {
-        let p_prefix = if let Some(stripped) = p_prefix.strip_prefix("0x") {
-            stripped.to_string()
-        } else {
-            p_prefix
-        };
-
-        let my_attempts = attempts_clone.clone();
         let my_tx = tx.clone();
+        let rules = search_rules.clone();
         // capture cli_ton_version only needed if not passed, but we pass it now.
+        let options = SearchOptions {
+            progress: Some(attempts_clone.clone()),
+            cancel: cancel_clone.clone(),
+            deadline: timeout.map(|t| Instant::now() + t),
+            max_attempts,
+        };
 
-        thread::spawn(move || {
-            let (pk, addr) = match p_chain {
-                UiChain::Ethereum => {
-                    let gen = EthereumVanityGenerator::new(&p_prefix, &p_suffix, p_case);
-                    gen.search(Some(my_attempts))
-                }
-                UiChain::Bitcoin => {
-                    // Map UI Network to Bitcoin Network
-                    let net = convert_network(p_network);
-
-                    // Map UI type to Wallet type
-                    let t = convert_btc_type(p_btc_type);
-                    let gen = BitcoinVanityGenerator::new(&p_prefix, &p_suffix, p_case, net, t);
-                    gen.search(Some(my_attempts))
-                }
-                UiChain::Solana => {
-                    let gen = SolanaVanityGenerator::new(&p_prefix, &p_suffix, p_case);
-                    gen.search(Some(my_attempts))
-                }
-                UiChain::Ton => {
-                    // Convert UiTonVersion to CoreVersion manually
-                    let core_version: vanity_core::TonWalletVersion = p_ton_version.into();
-
-                    let gen = vanity_wallet::TonVanityGenerator::new(
-                        &p_prefix,
-                        &p_suffix,
-                        p_case,
-                        core_version,
-                    );
-                    gen.search(Some(my_attempts))
-                }
-                UiChain::Cosmos => {
-                    use vanity_wallet::CosmosVanityGenerator;
-                    let gen = CosmosVanityGenerator::new(&p_hrp, &p_prefix, &p_suffix, p_case);
-                    gen.search(Some(my_attempts))
+        let format = address_format(p_chain, p_network, p_btc_type, &p_hrp);
+        let patterns =
+            compile_patterns(&format, &p_prefix, &p_suffix, p_case, &contains, &excludes);
+        let labels = patterns.as_ref().map(pattern_labels).unwrap_or_default();
+
+        let handle = thread::spawn(move || {
+            let outcome = patterns.map_err(CoreError::from).and_then(|patterns| {
+                let mut sieve = MatchSieve::new(limits, patterns.len());
+                let generator = rules.generator(
+                    p_chain,
+                    p_network,
+                    p_btc_type,
+                    p_ton_version,
+                    &p_hrp,
+                    patterns,
+                );
+
+                // Send each new match as strings while the search runs
+                generator.search_each(&options, &mut |found| {
+                    if sieve.admit(&found) {
+                        let _ = my_tx.send(SearchEvent::Found(found_key(&found, &labels)));
+                    }
+                    !sieve.is_done()
+                })?;
+                Ok((sieve.is_done(), sieve.accepted()))
+            });
+
+            let event = match outcome {
+                Ok((true, _)) => SearchEvent::Completed,
+                Ok((false, 0)) => {
+                    SearchEvent::Stopped("Search limit reached without a match.".to_string())
                 }
+                Ok((false, found)) => SearchEvent::Stopped(format!(
+                    "Search limit reached after {} of {} matches.",
+                    found, count
+                )),
+                Err(e) => SearchEvent::Stopped(format!("Search failed: {}", e)),
             };
-
-            // Send tuple (Address, PrivateKey) as strings
-            let _ = my_tx.send((addr.to_string(), pk.to_string()));
+            let _ = my_tx.send(event);
         });
+        workers_clone
+            .lock()
+            .expect("worker list poisoned")
+            .push(handle);
     };
 
-    if args.no_tui {
+    // Contract and split-key results need more than the TUI's address and key, so they run
+    // headless
+    if args.no_tui || contract.is_some() || split_key.is_some() {
         if !start_immediately {
-            eprintln!("Error: --no-tui requires --prefix or --suffix.");
+            eprintln!(
+                "Error: --no-tui requires --prefix, --suffix, --contains, zero bytes, --mask, \
+                 --below or --above."
+            );
             std::process::exit(1);
         }
         println!(
             "Searching for pattern defined by prefix='{}', suffix='{}'...",
-            prefix, args.suffix
+            prefixes, suffixes
         );
 
         // Convert CLI arguments to UI modules
@@ -279,36 +824,175 @@ fn main() {
         let ui_btc_type: UiBtcType = cli_btc_type.into();
         let ui_ton_version: UiTonVersion = args.ton_version.clone().into();
 
-        // Spawn search thread directly
-        on_search_start(
-            prefix,
-            args.suffix,
+        let format = address_format(ui_chain, ui_network, ui_btc_type, &args.hrp);
+        let patterns = compile_patterns(
+            &format,
+            &prefixes,
+            &suffixes,
             args.case_sensitive,
-            ui_chain,
-            ui_network,
-            ui_btc_type,
-            ui_ton_version,
-            args.hrp.clone(),
-        );
+            &args.contains,
+            &args.exclude,
+        )
+        .ok();
+        let difficulty = patterns
+            .as_ref()
+            .map(|patterns| search_difficulty(&format, patterns, zero_bytes, &address_bits));
+        if let Some(contract) = &contract {
+            println!("Mining {}", contract);
+        }
+        if let Some(public_key) = &split_key {
+            println!("Searching partial keys for public key {}", public_key);
+        }
+        if let Some(zero_bytes) = &zero_bytes {
+            println!("Requiring {}", zero_bytes);
+        }
+        for bits in &address_bits {
+            println!("Requiring {}", bits);
+        }
+        if let Some(difficulty) = difficulty.as_ref().filter(|d| !d.is_impossible()) {
+            println!("Difficulty: {} (expected attempts)", difficulty.odds());
+        }
+        if let Some(patterns) = patterns.as_ref().filter(|p| p.len() > 1) {
+            for pattern in patterns.patterns() {
+                println!("  {pattern}: {}", Difficulty::new(&format, pattern).odds());
+            }
+        }
+
+        // Searches that could take years are benchmarked first, so the warning comes before
+        // the search does; matches the benchmark finds are printed ahead of the search's
+        let mut estimated = false;
+        let mut benchmarked = Vec::new();
+        if let (Some(difficulty), Some(patterns)) = (&difficulty, &patterns) {
+            if !difficulty.is_impossible() && difficulty.is_impractical(SLOWEST_RATE) {
+                let (rate, hits) = measure_rate(generator(&args, patterns.clone()).as_ref());
+                print_estimates(difficulty, rate);
+                estimated = true;
+                let mut sieve = MatchSieve::new(limits, patterns.len());
+                let labels = pattern_labels(patterns);
+                benchmarked = hits
+                    .iter()
+                    .filter(|hit| sieve.admit(hit))
+                    .map(|hit| SearchEvent::Found(found_key(hit, &labels)))
+                    .collect();
+            }
+        }
+
+        // Spawn search thread directly
+        let started = Instant::now();
+        if benchmarked.len() < count {
+            on_search_start(
+                prefixes,
+                suffixes,
+                args.case_sensitive,
+                ui_chain,
+                ui_network,
+                ui_btc_type,
+                ui_ton_version,
+                args.hrp.clone(),
+            );
+        }
+        // The search thread holds the only sender now, so a thread that dies without an event
+        // closes the channel
+        drop(on_search_start);
 
-        // Simple loop waiting for result
-        loop {
-            if let Ok(res) = rx.try_recv() {
-                println!("\nAddress: {}\nPrivate Key: {}", res.0, res.1);
-                run_verification(&res.1, &format!("{:?}", args.chain).to_lowercase());
-                break;
+        // Print matches as they arrive, with time estimates once the rate is known
+        let sample_at = started + RATE_SAMPLE;
+        let mut found = 0;
+        let mut benchmarked = benchmarked.into_iter();
+        while found < count {
+            let event = if let Some(event) = benchmarked.next() {
+                Some(event)
+            } else if estimated {
+                rx.recv().ok()
+            } else {
+                match rx.recv_timeout(sample_at.saturating_duration_since(Instant::now())) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let rate = attempts.load(Ordering::Relaxed) as f64
+                            / started.elapsed().as_secs_f64();
+                        if let Some(difficulty) = &difficulty {
+                            print_estimates(difficulty, rate);
+                        }
+                        estimated = true;
+                        continue;
+                    }
+                    event => event.ok(),
+                }
+            };
+            match event {
+                Some(SearchEvent::Found(FoundKey {
+                    address: addr,
+                    private_key: pk,
+                    kind,
+                    pattern,
+                })) => {
+                    found += 1;
+                    match &contract {
+                        Some(Contract::Safe(safe)) => {
+                            let salt_nonce = salt_nonce_of(&pk).unwrap_or_default();
+                            println!(
+                                "\nSafe: {}\nsaltNonce: {}\ncreateProxyWithNonce({}, 0x{}, {})",
+                                addr,
+                                salt_nonce,
+                                Address::Ethereum(safe.singleton),
+                                hex::encode(&safe.initializer),
+                                salt_nonce
+                            )
+                        }
+                        Some(Contract::Account(account)) => println!(
+                            "\nAccount: {}\nSalt: {}\ninitCode: 0x{}",
+                            addr,
+                            pk,
+                            parse_hex::<32>(&pk)
+                                .map(|salt| hex::encode(account.user_op_init_code(&salt)))
+                                .unwrap_or_default()
+                        ),
+                        Some(contract) if contract.mines_salt() => {
+                            println!("\nContract: {}\nSalt: {}", addr, pk)
+                        }
+                        Some(_) => println!(
+                            "\nDeployer: {}\nContract: {}\nPrivate Key: {}",
+                            deployer_of(&pk).unwrap_or_default(),
+                            addr,
+                            pk
+                        ),
+                        None => println!("\nAddress: {}\n{}: {}", addr, kind.label(), pk),
+                    }
+                    if let Some(pattern) = pattern {
+                        println!("Pattern: {}", pattern);
+                    }
+                    if let Some(summary) = zero_bytes.and_then(|_| zero_byte_summary(&addr)) {
+                        println!("Zero bytes: {}", summary);
+                    }
+                    // Partial keys and salts do not derive the address on their own
+                    if kind == KeyKind::Secret {
+                        run_verification(&pk, &format!("{:?}", args.chain).to_lowercase());
+                    }
+                }
+                Some(SearchEvent::Stopped(reason)) if found == 0 => {
+                    eprintln!("Error: {}", reason);
+                    std::process::exit(1);
+                }
+                // Partial results were printed; say why the rest are missing
+                Some(SearchEvent::Stopped(reason)) => {
+                    eprintln!("Warning: {}", reason);
+                    break;
+                }
+                Some(SearchEvent::Completed) => break,
+                None => {
+                    eprintln!("Error: the search stopped without reporting a result.");
+                    std::process::exit(1);
+                }
             }
-            std::thread::sleep(std::time::Duration::from_millis(100));
         }
     } else {
         // Run TUI on main thread
         let initial_prefix = if start_immediately {
-            prefix
+            prefixes
         } else {
             String::new()
         };
         let initial_suffix = if start_immediately {
-            args.suffix
+            suffixes
         } else {
             String::new()
         };
@@ -345,22 +1029,188 @@ fn main() {
             initial_ui_btc_type,
             initial_ui_ton_version,
             args.hrp.clone(),
+            args.contains.clone(),
+            args.exclude.clone(),
+            zero_bytes,
+            address_bits,
+            count,
             on_search_start,
+            |app: &App| {
+                app.patterns().map_or((0.0, Vec::new()), |patterns| {
+                    let labels = pattern_labels(&patterns);
+                    let generator = benchmark_rules.generator(
+                        app.chain,
+                        app.network,
+                        app.btc_type,
+                        app.ton_version,
+                        &app.hrp,
+                        patterns,
+                    );
+                    let (rate, hits) = measure_rate(generator.as_ref());
+                    let found = hits.iter().map(|hit| found_key(hit, &labels)).collect();
+                    (rate, found)
+                })
+            },
         ) {
-            Ok(res) => res,
+            Ok(res) => Some(res),
             Err(e) => {
                 eprintln!("TUI Error: {}", e);
                 None
             }
         };
 
-        if let Some((_, pk, chain)) = result {
+        // Stop any search still running (e.g. the user quit mid-search)
+        cancel.cancel();
+        join_workers(&workers);
+
+        if let Some((found, chain)) = result {
             // TUI now returns the selected chain, so we use it for verification.
-            run_verification(&pk, &format!("{:?}", chain).to_lowercase());
+            for key in found {
+                run_verification(&key.private_key, &format!("{:?}", chain).to_lowercase());
+            }
         }
     }
 }
 
+/// The `--leading-zero-bytes` or `--zero-bytes` rule, if any.
+fn zero_bytes_of(args: &Args) -> Option<ZeroBytes> {
+    match (args.leading_zero_bytes, args.zero_bytes) {
+        (Some(n), _) => Some(ZeroBytes::Leading(n as usize)),
+        (_, Some(n)) => Some(ZeroBytes::Anywhere(n as usize)),
+        _ => None,
+    }
+}
+
+/// The `--mask`, `--below` and `--above` rules.
+fn address_bits_of(args: &Args) -> Vec<AddressBits> {
+    (args.mask.iter().copied())
+        .chain(args.below.map(AddressBits::Below))
+        .chain(args.above.map(AddressBits::Above))
+        .collect()
+}
+
+/// Appends the patterns of a word list file (one per line, skipping blank lines and `#`
+/// comments) to a space-separated list, exiting on read errors.
+fn with_wordlist(list: &str, file: Option<&Path>) -> String {
+    let Some(path) = file else {
+        return list.to_string();
+    };
+    let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("Error: cannot read {}: {}", path.display(), e);
+        std::process::exit(1);
+    });
+    let words = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    std::iter::once(list.trim())
+        .filter(|list| !list.is_empty())
+        .chain(words)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Odds of a key matching any of `patterns` and every address rule.
+fn search_difficulty(
+    format: &AddressFormat,
+    patterns: &PatternSet,
+    zero_bytes: Option<ZeroBytes>,
+    address_bits: &[AddressBits],
+) -> Difficulty {
+    let difficulty = Difficulty::any(format, patterns);
+    let difficulty = match &zero_bytes {
+        Some(zero_bytes) => difficulty.and(Difficulty::zero_bytes(zero_bytes)),
+        None => difficulty,
+    };
+    address_bits.iter().fold(difficulty, |difficulty, bits| {
+        difficulty.and(Difficulty::address_bits(bits))
+    })
+}
+
+/// Prints the time to reach each confidence level at `rate`, warning when the search is
+/// likely to take years.
+fn print_estimates(difficulty: &Difficulty, rate: f64) {
+    if difficulty.is_impossible() || rate <= 0.0 {
+        return;
+    }
+    println!(
+        "At {} keys/s: {}",
+        format_count(rate),
+        estimates(difficulty, rate)
+    );
+    if difficulty.is_impractical(rate) {
+        eprintln!(
+            "Warning: this search is likely to take years at this speed; consider a shorter pattern."
+        );
+    }
+}
+
+/// Keys per second `generator` searches on all cores, measured over [`BENCHMARK`], and the
+/// matches it found meanwhile.
+fn measure_rate(generator: &dyn VanityGenerator) -> (f64, Vec<Match>) {
+    let attempts = Arc::new(AtomicU64::new(0));
+    let started = Instant::now();
+    let options = SearchOptions {
+        deadline: Some(started + BENCHMARK),
+        ..SearchOptions::with_progress(attempts.clone())
+    };
+    let mut hits = Vec::new();
+    let _ = generator.search_each(&options, &mut |hit| {
+        hits.push(hit);
+        true
+    });
+    let rate = attempts.load(Ordering::Relaxed) as f64 / started.elapsed().as_secs_f64();
+    (rate, hits)
+}
+
+/// Names of the patterns results are labelled with: none when there is only one.
+fn pattern_labels(patterns: &PatternSet) -> Vec<String> {
+    if patterns.len() > 1 {
+        patterns
+            .patterns()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// `found` as the UI reports it, naming its pattern from `labels`.
+fn found_key(found: &Match, labels: &[String]) -> FoundKey {
+    FoundKey {
+        address: found.address.to_string(),
+        private_key: found.private_key.to_string(),
+        kind: found.private_key.kind(),
+        pattern: labels.get(found.pattern).cloned(),
+    }
+}
+
+/// Time to reach each confidence level at `rate`, e.g. `50% within 3m, 90% within 9m`.
+fn estimates(difficulty: &Difficulty, rate: f64) -> String {
+    CONFIDENCE_LEVELS
+        .iter()
+        .map(|&confidence| {
+            let eta = difficulty
+                .time_for(confidence, rate)
+                .map_or_else(|| "forever".to_string(), format_duration);
+            format!("{:.0}% within {}", confidence * 100.0, eta)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn join_workers(workers: &Mutex<Vec<thread::JoinHandle<()>>>) {
+    let handles: Vec<_> = workers
+        .lock()
+        .expect("worker list poisoned")
+        .drain(..)
+        .collect();
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
 fn run_verification(pk: &str, chain: &str) {
     // Check if python3 is available
     use std::process::Command;
@@ -409,29 +1259,485 @@ fn run_verification(pk: &str, chain: &str) {
     }
 }
 
+/// Keeps the best-scoring keys within the time/attempt budget and prints them, best first,
+/// as JSON Lines.
+fn run_scoring(score: Score, args: &Args) {
+    if args.timeout.is_none() && args.max_attempts.is_none() {
+        eprintln!("Error: --score needs a budget: --timeout or --max-attempts.");
+        std::process::exit(1);
+    }
+    if !args.prefix.is_empty()
+        || !args.suffix.is_empty()
+        || args.prefix_file.is_some()
+        || args.suffix_file.is_some()
+        || !args.contains.is_empty()
+        || !args.exclude.is_empty()
+    {
+        eprintln!(
+            "Error: --score replaces patterns; drop --prefix, --suffix, --prefix-file, \
+             --suffix-file, --contains and --exclude."
+        );
+        std::process::exit(1);
+    }
+    // Targets are written like patterns: Ethereum's 0x is optional, case follows the flag
+    let score = match score {
+        Score::Target { text, .. } => Score::Target {
+            text: match args.chain {
+                Chain::Ethereum => text.strip_prefix("0x").unwrap_or(&text).to_string(),
+                _ => text,
+            },
+            case_sensitive: args.case_sensitive,
+        },
+        score => score,
+    };
+
+    let options = SearchOptions {
+        deadline: args
+            .timeout
+            .map(|t| Instant::now() + Duration::from_secs(t)),
+        max_attempts: args.max_attempts,
+        ..SearchOptions::default()
+    };
+    let mut board = Leaderboard::new(args.top as usize);
+    eprintln!("Scoring addresses by {} (top {})...", score, args.top);
+    if let Err(e) =
+        generator(args, PatternSet::default()).search_scored(&options, &score, &mut board)
+    {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    let contract = Contract::from_args(args);
+    for (rank, entry) in board.entries().iter().enumerate() {
+        // Deploy-nonce entries also name the account that deploys the scored contract, Safe
+        // entries the decimal saltNonce to deploy with and account entries their initCode
+        let extra = match (&contract, &entry.private_key) {
+            (Some(Contract::Create { .. }), PrivateKey::Ethereum(secret)) => {
+                EthereumVanityGenerator::derive_address(secret)
+                    .map(|account| format!(", \"deployer\": \"{}\"", Address::Ethereum(account)))
+            }
+            (Some(Contract::Safe(_)), PrivateKey::Salt(_)) => {
+                salt_nonce_of(&entry.private_key.to_string())
+                    .map(|nonce| format!(", \"salt_nonce\": \"{}\"", nonce))
+            }
+            (Some(Contract::Account(account)), PrivateKey::Salt(salt)) => Some(format!(
+                ", \"init_code\": \"0x{}\"",
+                hex::encode(account.user_op_init_code(salt))
+            )),
+            _ => None,
+        };
+        println!(
+            "{{\"rank\": {}, \"score\": {}, \"addr\": \"{}\", \"{}\": \"{}\"{}}}",
+            rank + 1,
+            entry.score,
+            entry.address,
+            json_key(&entry.private_key),
+            entry.private_key,
+            extra.unwrap_or_default()
+        );
+    }
+}
+
 fn run_batch_generation(count: u64, args: &Args) {
-    // Create generator for batch processing using a trait object to avoid code duplication
-    let gen: Box<dyn VanityGenerator> = match args.chain {
-        Chain::Ethereum => Box::new(EthereumVanityGenerator::new("", "", false)),
-        Chain::Bitcoin => {
-            let net = args.network.clone().into();
-            let t = args.btc_type.clone().into();
-            Box::new(BitcoinVanityGenerator::new("", "", false, net, t))
-        }
-        Chain::Solana => Box::new(SolanaVanityGenerator::new("", "", false)), // No prefix/suffix for batch random
-        Chain::Ton => Box::new(vanity_wallet::TonVanityGenerator::new(
-            "",
-            "",
-            false,
+    let gen = generator(args, PatternSet::default());
+    for _ in 0..count {
+        let (pk, addr) = gen.generate();
+        println!(
+            "{{\"{}\": \"{}\", \"addr\": \"{}\"}}",
+            json_key(&pk),
+            pk,
+            addr
+        );
+    }
+}
+
+/// JSON field a key is printed under in batch and scoring output: `pk` only for keys that
+/// control the address, `partial` for split-key offsets and `salt` for contract salts.
+fn json_key(key: &PrivateKey) -> &'static str {
+    match key.kind() {
+        KeyKind::Secret => "pk",
+        KeyKind::Partial => "partial",
+        KeyKind::Salt => "salt",
+    }
+}
+
+/// Rejects address rules, contract modes and split keys the selected chain has no use for,
+/// which the generators would otherwise drop.
+fn check_args(args: &Args) -> Result<(), String> {
+    let ethereum = matches!(args.chain, Chain::Ethereum);
+    if zero_bytes_of(args).is_some() && !ethereum {
+        return Err("--leading-zero-bytes and --zero-bytes only apply to Ethereum".to_string());
+    }
+    let address_bits = address_bits_of(args);
+    if !address_bits.is_empty() && !ethereum {
+        return Err("--mask, --below and --above only apply to Ethereum".to_string());
+    }
+    for bits in &address_bits {
+        bits.validate().map_err(|e| e.to_string())?;
+    }
+    if Contract::from_args(args).is_some() && !ethereum {
+        return Err("--deploy-nonce, --deployer, --create3, --safe-factory and \
+                    --account-factory only apply to Ethereum"
+            .to_string());
+    }
+    match args.split_key.filter(|k| !k.supports(&args.chain)) {
+        Some(SplitKey::Secp256k1(_)) => {
+            Err("a secp256k1 --split-key only applies to Ethereum, Bitcoin and Cosmos".to_string())
+        }
+        Some(SplitKey::Ed25519(_)) => {
+            Err("an ed25519 --split-key only applies to Solana and TON".to_string())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Generator for the selected chain and `patterns`, with the address rules and split key of
+/// `args`. Batch generation and scoring pass an empty set.
+fn generator(args: &Args, patterns: PatternSet) -> Box<dyn VanityGenerator> {
+    SearchRules::from_args(args).generator(
+        args.chain.clone().into(),
+        args.network.clone().into(),
+        args.btc_type.clone().into(),
+        args.ton_version.clone().into(),
+        &args.hrp,
+        patterns,
+    )
+}
+
+/// What a search takes from the command line besides its chain and patterns, which the TUI
+/// can change.
+#[derive(Clone, Debug)]
+struct SearchRules {
+    contract: Option<Contract>,
+    zero_bytes: Option<ZeroBytes>,
+    address_bits: Vec<AddressBits>,
+    split_key: Option<SplitKey>,
+}
+
+impl SearchRules {
+    fn from_args(args: &Args) -> Self {
+        Self {
+            contract: Contract::from_args(args),
+            zero_bytes: zero_bytes_of(args),
+            address_bits: address_bits_of(args),
+            split_key: args.split_key,
+        }
+    }
+
+    /// Generator for `patterns` on the picked chain; contract modes and address rules only
+    /// apply to Ethereum, and the split key only to the chains of its curve.
+    fn generator(
+        &self,
+        chain: UiChain,
+        network: UiNetwork,
+        btc_type: UiBtcType,
+        ton_version: UiTonVersion,
+        hrp: &str,
+        patterns: PatternSet,
+    ) -> Box<dyn VanityGenerator> {
+        let (zero_bytes, address_bits) = (self.zero_bytes, &self.address_bits);
+        match chain {
+            UiChain::Ethereum => match &self.contract {
+                Some(contract) => contract.generator(patterns, zero_bytes, address_bits),
+                None => Box::new(with_split_key!(
+                    with_address_rules!(
+                        EthereumVanityGenerator::from_patterns(patterns),
+                        zero_bytes,
+                        address_bits
+                    ),
+                    self.split_key.and_then(SplitKey::secp256k1)
+                )),
+            },
+            UiChain::Bitcoin => Box::new(with_split_key!(
+                BitcoinVanityGenerator::from_patterns(
+                    patterns,
+                    convert_network(network),
+                    convert_btc_type(btc_type)
+                ),
+                self.split_key.and_then(SplitKey::secp256k1)
+            )),
+            UiChain::Solana => Box::new(with_split_key!(
+                SolanaVanityGenerator::from_patterns(patterns),
+                self.split_key.and_then(SplitKey::ed25519)
+            )),
+            UiChain::Ton => Box::new(with_split_key!(
+                vanity_wallet::TonVanityGenerator::from_patterns(patterns, ton_version.into()),
+                self.split_key.and_then(SplitKey::ed25519)
+            )),
+            UiChain::Cosmos => Box::new(with_split_key!(
+                vanity_wallet::CosmosVanityGenerator::from_patterns(hrp, patterns),
+                self.split_key.and_then(SplitKey::secp256k1)
+            )),
+        }
+    }
+}
+
+/// `vc combine`: the final key of a split-key search and its address, or without a partial
+/// key, the public key to hand to whoever runs the search.
+fn run_combine(args: &Args, secret: &str, partial: Option<&[u8; 32]>, expected: Option<&str>) {
+    let secret = parse_secret(secret).unwrap_or_else(|e| {
+        eprintln!("Error: invalid --secret: {}", e);
+        std::process::exit(1);
+    });
+    let key = match partial {
+        Some(partial) => combine_split_key(&secret, partial),
+        None => Ok(secret),
+    };
+    let derived = key.and_then(|key| {
+        let secret_key =
+            k256::SecretKey::from_slice(&key).map_err(|e| CoreError::CryptoError(e.to_string()))?;
+        let (private_key, address) = match args.chain {
+            Chain::Ethereum => (
+                PrivateKey::Ethereum(key),
+                EthereumVanityGenerator::derive_address(&key).map(Address::Ethereum),
+            ),
+            Chain::Bitcoin => {
+                let secp = bitcoin::secp256k1::Secp256k1::new();
+                let secret_key = bitcoin::secp256k1::SecretKey::from_slice(&key)
+                    .map_err(|e| CoreError::CryptoError(e.to_string()))?;
+                let network = args.network.clone().into();
+                let address = BitcoinVanityGenerator::derive_address(
+                    &secp,
+                    network,
+                    args.btc_type.clone().into(),
+                    secret_key.public_key(&secp),
+                );
+                (
+                    PrivateKey::Bitcoin(bitcoin::PrivateKey::new(secret_key, network).to_string()),
+                    Some(Address::Bitcoin(address)),
+                )
+            }
+            Chain::Cosmos => (
+                PrivateKey::Cosmos(key),
+                Some(Address::Cosmos(
+                    vanity_wallet::CosmosVanityGenerator::derive_address(&args.hrp, &key)?,
+                )),
+            ),
+            Chain::Solana | Chain::Ton => unreachable!("ed25519 keys are combined separately"),
+        };
+        let address = address.ok_or_else(|| CoreError::CryptoError("invalid key".to_string()))?;
+        Ok((secret_key.public_key(), private_key, address))
+    });
+    let (public_key, private_key, address) = match derived {
+        Ok(derived) => derived,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if partial.is_none() {
+        println!(
+            "Public Key: {}",
+            hex::encode(public_key.to_encoded_point(true))
+        );
+        println!("Address: {}", address);
+        return;
+    }
+    println!("Address: {}\nPrivate Key: {}", address, private_key);
+    check_combined_address(&address, expected);
+}
+
+/// `vc combine` for Solana and TON: adds the partial scalar to the secret's, which leaves an
+/// expanded key rather than a seed, and proves it by signing with it.
+fn run_ed25519_combine(
+    args: &Args,
+    secret: &str,
+    partial: Option<&[u8; 32]>,
+    expected: Option<&str>,
+) {
+    let derive = |public_key: &ed25519_dalek::VerifyingKey| match args.chain {
+        Chain::Ton => Address::Ton(vanity_wallet::TonVanityGenerator::derive_address(
             args.ton_version.clone().into(),
+            public_key.as_bytes(),
         )),
-        Chain::Cosmos => Box::new(vanity_wallet::CosmosVanityGenerator::new(
-            &args.hrp, "", "", false,
-        )),
+        _ => Address::Solana(SolanaVanityGenerator::derive_address(public_key.as_bytes())),
     };
+    let secret = parse_ed25519_secret(secret).unwrap_or_else(|e| {
+        eprintln!("Error: invalid --secret: {}", e);
+        std::process::exit(1);
+    });
 
-    for _ in 0..count {
-        let (pk, addr) = gen.generate();
-        println!("{{\"pk\": \"{}\", \"addr\": \"{}\"}}", pk, addr);
+    let Some(partial) = partial else {
+        let public_key = combine_ed25519_split_key(&secret, &[0; 32])
+            .and_then(|expanded| verify_expanded_key(&expanded));
+        match public_key {
+            Ok(public_key) => {
+                println!("Public Key: {}", hex::encode(public_key.as_bytes()));
+                println!("Address: {}", derive(&public_key));
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    };
+
+    let combined = combine_ed25519_split_key(&secret, partial).and_then(|expanded| {
+        verify_expanded_key(&expanded).map(|public_key| (expanded, public_key))
+    });
+    let (expanded, public_key) = match combined {
+        Ok(combined) => combined,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let address = derive(&public_key);
+    println!(
+        "Address: {}\nPrivate Key: {}\nPublic Key: {}",
+        address,
+        PrivateKey::Ed25519Expanded(expanded),
+        hex::encode(public_key.as_bytes())
+    );
+    println!("Signature check passed: the expanded key signs for this public key.");
+    check_combined_address(&address, expected);
+}
+
+/// Exits non-zero unless a combined key's address is the one the search reported.
+fn check_combined_address(address: &Address, expected: Option<&str>) {
+    let Some(expected) = expected else {
+        return;
+    };
+    // EIP-55 case is presentation only
+    let matches = match address {
+        Address::Ethereum(_) => address.to_string().eq_ignore_ascii_case(expected),
+        _ => address.to_string() == expected,
+    };
+    if !matches {
+        eprintln!(
+            "Error: the combined key derives {}, not {}; check the secret and the partial key.",
+            address, expected
+        );
+        std::process::exit(1);
+    }
+    println!("Address matches the search result.");
+}
+
+/// Parses a split-key public key: 32 bytes (hex, or Base58 as Solana shows it) are ed25519,
+/// 33 or 65 bytes of hex a secp256k1 key, compressed or not.
+fn parse_split_key(s: &str) -> Result<SplitKey, String> {
+    let bytes = parse_hex_bytes(s)
+        .map(Vec::from)
+        .or_else(|e| bs58::decode(s).into_vec().map_err(|_| e))?;
+    match bytes.len() {
+        32 => {
+            let bytes: [u8; 32] = bytes.try_into().expect("32 bytes");
+            ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                .map(SplitKey::Ed25519)
+                .map_err(|_| "not an ed25519 public key".to_string())
+        }
+        _ => k256::PublicKey::from_sec1_bytes(&bytes)
+            .map(SplitKey::Secp256k1)
+            .map_err(|_| "not a secp256k1 or ed25519 public key".to_string()),
+    }
+}
+
+/// Parses an ed25519 secret for `vc combine`: a 32-byte seed in hex, 64 bytes of hex that
+/// are either a keypair (the seed followed by its public key) or an expanded key, or a Base58
+/// Solana keypair. Keypairs are reduced to their seed.
+fn parse_ed25519_secret(s: &str) -> Result<Vec<u8>, String> {
+    match parse_hex_bytes(s) {
+        Ok(bytes) if bytes.len() == 64 && is_ed25519_keypair(&bytes) => Ok(bytes[..32].to_vec()),
+        Ok(bytes) if matches!(bytes.len(), 32 | 64) => Ok(bytes.into_vec()),
+        Ok(bytes) => Err(format!(
+            "expected a 32-byte seed or a 64-byte expanded key, got {} bytes",
+            bytes.len()
+        )),
+        Err(e) => match bs58::decode(s).into_vec() {
+            Ok(keypair) if keypair.len() == 64 => Ok(keypair[..32].to_vec()),
+            _ => Err(e),
+        },
+    }
+}
+
+/// Whether 64 bytes are a seed followed by its public key, as Solana keypairs are stored. An
+/// expanded key's second half is a hash prefix, which is practically never that public key.
+fn is_ed25519_keypair(bytes: &[u8]) -> bool {
+    let (seed, public_key) = bytes.split_at(32);
+    let seed: [u8; 32] = seed.try_into().expect("32 bytes");
+    ed25519_dalek::SigningKey::from_bytes(&seed)
+        .verifying_key()
+        .as_bytes()
+        == public_key
+}
+
+/// Parses a 32-byte secret as hex, or as a Bitcoin WIF.
+fn parse_secret(s: &str) -> Result<[u8; 32], String> {
+    match bitcoin::PrivateKey::from_wif(s) {
+        Ok(key) => Ok(key.inner.secret_bytes()),
+        Err(_) => parse_hex::<32>(s),
     }
 }
+
+/// Parses a `0x`-optional hex number of up to 160 bits, e.g. `0x3fff` or a full address.
+fn parse_uint160(s: &str) -> Result<[u8; 20], String> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    if digits.len() > 40 {
+        return Err(format!("{} hex digits is more than 160 bits", digits.len()));
+    }
+    parse_hex::<20>(&format!("{:0>40}", digits))
+}
+
+/// Parses `MASK=VALUE`, two numbers as for [`parse_uint160`].
+fn parse_mask(s: &str) -> Result<AddressBits, String> {
+    let (mask, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected MASK=VALUE, got '{}'", s))?;
+    Ok(AddressBits::Mask {
+        mask: parse_uint160(mask)?,
+        value: parse_uint160(value)?,
+    })
+}
+
+/// Checks an `--account-init-code` template by filling in a zero owner.
+fn parse_init_code_template(s: &str) -> Result<String, String> {
+    AccountDeployment::from_template([0; 20], [0; 20], s)
+        .map(|_| s.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Decimal `saltNonce` of a printed salt (a big-endian uint256).
+fn salt_nonce_of(pk: &str) -> Option<String> {
+    let mut number = parse_hex::<32>(pk).ok()?;
+    let mut digits = Vec::new();
+    // Long division by ten, least significant digit first
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u16;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | *byte as u16;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).ok()
+}
+
+/// Checksummed account address of a printed Ethereum private key.
+fn deployer_of(pk: &str) -> Option<String> {
+    let secret = parse_hex::<32>(pk).ok()?;
+    EthereumVanityGenerator::derive_address(&secret)
+        .map(|account| Address::Ethereum(account).to_string())
+}
+
+/// Parses a `0x`-optional hex string of exactly `N` bytes.
+fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], String> {
+    let bytes = parse_hex_bytes(s)?;
+    bytes
+        .into_vec()
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected {} bytes, got {}", N, bytes.len()))
+}
+
+/// Parses a `0x`-optional hex string.
+fn parse_hex_bytes(s: &str) -> Result<Box<[u8]>, String> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s))
+        .map(Vec::into_boxed_slice)
+        .map_err(|e| format!("invalid hex: {}", e))
+}
@@ -0,0 +1,577 @@
+//! `vc serve`: vanity searches as an HTTP/JSON API, for tools that would otherwise shell out
+//! to `vc`.
+//!
+//! `POST /jobs` takes `{"args": [...]}`, the options of a plain `vc` search as
+//! `vc coordinator` accepts them, and answers with the job's `id` and a `token`. Every other
+//! request about the job must carry `Authorization: Bearer <token>`:
+//!
+//! - `GET /jobs/<id>`: state, attempts, key rate and estimated time to the next match.
+//! - `GET /jobs/<id>/results`: the matches found so far, with their private keys.
+//! - `DELETE /jobs/<id>`: cancels a queued or running job, or forgets a finished one.
+//!
+//! Finished jobs not deleted are forgotten [`FINISHED_JOB_TTL`] after they end.
+//!
+//! At most `--concurrency` jobs search at once, each on its own share of the cores; up to
+//! `--queue` more wait their turn, and submissions beyond that are refused with 503. Each
+//! connection carries one request.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::{rngs::OsRng, RngCore};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use vanity_core::{CancellationToken, CoreError, Match, MatchSieve, SearchOptions};
+
+use crate::cluster::Job;
+use crate::generator;
+
+/// Clients get this long to send their request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest request head (request line and headers) accepted.
+const MAX_HEAD: usize = 8 * 1024;
+
+/// Largest request body accepted.
+const MAX_BODY: usize = 64 * 1024;
+
+/// How long a finished job and its matches are kept for the client to collect.
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Where a job is in its life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Queued,
+    Running,
+    /// Found `--count` matches.
+    Done,
+    /// Hit `--timeout` or `--max-attempts` first; any matches found are still returned.
+    LimitReached,
+    Cancelled,
+    Failed,
+}
+
+impl State {
+    fn name(self) -> &'static str {
+        match self {
+            State::Queued => "queued",
+            State::Running => "running",
+            State::Done => "done",
+            State::LimitReached => "limit_reached",
+            State::Cancelled => "cancelled",
+            State::Failed => "failed",
+        }
+    }
+}
+
+/// A submitted job and what it has found so far.
+struct Entry {
+    job: Job,
+    /// Hex-encoded secret of the client that submitted the job.
+    token: String,
+    attempts: Arc<AtomicU64>,
+    cancel: CancellationToken,
+    progress: Mutex<Progress>,
+}
+
+struct Progress {
+    state: State,
+    started: Option<Instant>,
+    finished: Option<Instant>,
+    matches: Vec<Match>,
+    error: Option<String>,
+}
+
+struct Server {
+    jobs: Mutex<HashMap<u64, Arc<Entry>>>,
+    next_id: AtomicU64,
+    /// How many jobs may wait for a free slot.
+    queue: usize,
+    /// One permit per job allowed to search at once.
+    slots: Arc<Semaphore>,
+    /// One thread pool per slot, taken by a job while it holds a permit.
+    pools: Mutex<Vec<rayon::ThreadPool>>,
+}
+
+/// An HTTP status and JSON body.
+type Response = (u16, Value);
+
+fn error(status: u16, message: impl Into<String>) -> Response {
+    (status, json!({ "error": message.into() }))
+}
+
+/// `vc serve`: accepts jobs over HTTP until interrupted, then cancels the ones left.
+pub(crate) fn run_serve(listen: &str, queue: usize, concurrency: usize) {
+    if concurrency == 0 {
+        eprintln!("Error: --concurrency must be at least 1");
+        std::process::exit(1);
+    }
+    let runtime = tokio::runtime::Runtime::new().unwrap_or_else(|e| {
+        eprintln!("Error: cannot start the server: {}", e);
+        std::process::exit(1);
+    });
+    runtime.block_on(async {
+        let listener = TcpListener::bind(listen).await.unwrap_or_else(|e| {
+            eprintln!("Error: cannot listen on {}: {}", listen, e);
+            std::process::exit(1);
+        });
+        let local = listener.local_addr();
+        println!(
+            "Serving on http://{}",
+            local
+                .as_ref()
+                .map_or_else(|_| listen.to_string(), |addr| addr.to_string())
+        );
+        if local.is_ok_and(|addr| !addr.ip().is_loopback()) {
+            eprintln!(
+                "Warning: {} is reachable from other machines, and private keys are sent \
+                 over plain HTTP; put the server behind TLS or listen on 127.0.0.1",
+                listen
+            );
+        }
+        println!(
+            "Running up to {} jobs at once, with {} more queued",
+            concurrency, queue
+        );
+
+        let server = Arc::new(Server {
+            jobs: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            queue,
+            slots: Arc::new(Semaphore::new(concurrency)),
+            pools: Mutex::new(thread_pools(concurrency)),
+        });
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else { continue };
+                    tokio::spawn(handle_connection(server.clone(), stream));
+                }
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+        println!("Shutting down");
+        for entry in server.jobs.lock().expect("job list poisoned").values() {
+            entry.cancel.cancel();
+        }
+    });
+}
+
+/// Splits the cores evenly between `concurrency` pools, so that running jobs do not queue
+/// behind each other on one pool.
+fn thread_pools(concurrency: usize) -> Vec<rayon::ThreadPool> {
+    let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+    let threads = (cores / concurrency).max(1);
+    (0..concurrency)
+        .map(|slot| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(move |thread| format!("vc-job{}-{}", slot, thread))
+                .build()
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: cannot start the search threads: {}", e);
+                    std::process::exit(1);
+                })
+        })
+        .collect()
+}
+
+async fn handle_connection(server: Arc<Server>, mut stream: TcpStream) {
+    let (status, body) =
+        match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(Ok(request)) => route(&server, request),
+            Ok(Err(response)) => response,
+            Err(_) => error(408, "request timed out"),
+        };
+    let body = body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        status,
+        reason(status),
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    /// The bearer token of the `Authorization` header.
+    token: Option<String>,
+    body: Vec<u8>,
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD {
+            return Err(error(413, "request head too large"));
+        }
+        let mut chunk = [0u8; 1024];
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return Err(error(400, "incomplete request")),
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    };
+    let mut body = buffer.split_off(head_end + 4);
+    let head = std::str::from_utf8(&buffer[..head_end])
+        .map_err(|_| error(400, "request head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(error(400, "malformed request line"));
+    };
+
+    let mut length = 0;
+    let mut token = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(error(400, "malformed header"));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            length = value
+                .parse()
+                .map_err(|_| error(400, "malformed Content-Length"))?;
+        } else if name.eq_ignore_ascii_case("authorization") {
+            token = value
+                .strip_prefix("Bearer ")
+                .map(|token| token.trim().to_string());
+        }
+    }
+    if length > MAX_BODY {
+        return Err(error(413, "request body too large"));
+    }
+
+    if body.len() < length {
+        let read = body.len();
+        body.resize(length, 0);
+        stream
+            .read_exact(&mut body[read..])
+            .await
+            .map_err(|_| error(400, "incomplete request body"))?;
+    }
+    body.truncate(length);
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        token,
+        body,
+    })
+}
+
+fn route(server: &Arc<Server>, request: Request) -> Response {
+    server.forget_expired();
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["jobs"]) => submit(server, &request.body),
+        (_, ["jobs"]) => error(405, "use POST to submit a job"),
+        (method, ["jobs", id, rest @ ..]) if rest.len() <= 1 => {
+            let entry = match authorize(server, id, request.token.as_deref()) {
+                Ok(entry) => entry,
+                Err(response) => return response,
+            };
+            match (method, rest) {
+                ("GET", []) => (200, status(&entry)),
+                ("DELETE", []) => cancel(server, id, &entry),
+                ("GET", ["results"]) => (200, results(&entry)),
+                (_, []) => error(405, "use GET for a job's status or DELETE to cancel it"),
+                (_, ["results"]) => error(405, "use GET to fetch results"),
+                _ => error(404, "no such endpoint"),
+            }
+        }
+        _ => error(404, "no such endpoint"),
+    }
+}
+
+impl Server {
+    /// Drops jobs that finished more than [`FINISHED_JOB_TTL`] ago.
+    fn forget_expired(&self) {
+        self.jobs
+            .lock()
+            .expect("job list poisoned")
+            .retain(|_, entry| {
+                entry
+                    .progress()
+                    .finished
+                    .is_none_or(|finished| finished.elapsed() < FINISHED_JOB_TTL)
+            });
+    }
+}
+
+/// Looks up job `id`, which only the client holding its token may see.
+fn authorize(server: &Server, id: &str, token: Option<&str>) -> Result<Arc<Entry>, Response> {
+    let entry = id
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| {
+            server
+                .jobs
+                .lock()
+                .expect("job list poisoned")
+                .get(&id)
+                .cloned()
+        })
+        .ok_or_else(|| error(404, format!("no job {}", id)))?;
+    match token {
+        None => Err(error(
+            401,
+            "send the job's token as Authorization: Bearer <token>",
+        )),
+        Some(token) if !same_token(token, &entry.token) => {
+            Err(error(403, "the token does not match the job's"))
+        }
+        Some(_) => Ok(entry),
+    }
+}
+
+/// Compares tokens without stopping at the first differing byte.
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn submit(server: &Arc<Server>, body: &[u8]) -> Response {
+    let words = serde_json::from_slice::<Value>(body).ok().and_then(|body| {
+        body.get("args")?
+            .as_array()?
+            .iter()
+            .map(|word| word.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+    });
+    let Some(words) = words else {
+        return error(
+            400,
+            r#"expected {"args": [...]} with the options of a vc search"#,
+        );
+    };
+    let job = match Job::parse(words) {
+        Ok(job) => job,
+        Err(e) => return error(400, format!("invalid job: {}", e)),
+    };
+
+    let mut token = [0u8; 32];
+    OsRng.fill_bytes(&mut token);
+    let entry = Arc::new(Entry {
+        job,
+        token: hex::encode(token),
+        attempts: Arc::new(AtomicU64::new(0)),
+        cancel: CancellationToken::new(),
+        progress: Mutex::new(Progress {
+            state: State::Queued,
+            started: None,
+            finished: None,
+            matches: Vec::new(),
+            error: None,
+        }),
+    });
+
+    let id = {
+        let mut jobs = server.jobs.lock().expect("job list poisoned");
+        let waiting = jobs
+            .values()
+            .filter(|entry| entry.progress().state == State::Queued)
+            .count();
+        // A job that can start right away does not take a queue place
+        if waiting >= server.queue + server.slots.available_permits() {
+            return error(503, "the job queue is full; try again later");
+        }
+        let id = server.next_id.fetch_add(1, Ordering::Relaxed);
+        jobs.insert(id, entry.clone());
+        id
+    };
+    println!("Job {} queued: {}", id, entry.job.words.join(" "));
+    tokio::spawn(run_job(server.clone(), id, entry.clone()));
+    (
+        202,
+        json!({ "id": id, "token": entry.token, "state": "queued" }),
+    )
+}
+
+/// Waits for a free slot, then searches on that slot's thread pool until the job ends.
+async fn run_job(server: Arc<Server>, id: u64, entry: Arc<Entry>) {
+    let Ok(_permit) = server.slots.clone().acquire_owned().await else {
+        return;
+    };
+    {
+        let mut progress = entry.progress();
+        if progress.state != State::Queued {
+            return;
+        }
+        progress.state = State::Running;
+        progress.started = Some(Instant::now());
+    }
+    println!("Job {} running", id);
+
+    // Every permit holder finds a pool left, as there are as many pools as permits
+    let pool = server
+        .pools
+        .lock()
+        .expect("pool list poisoned")
+        .pop()
+        .expect("a pool for every slot");
+    let search = entry.clone();
+    let (pool, outcome) = tokio::task::spawn_blocking(move || {
+        // Catch a panic here rather than in the task, so the pool comes back either way
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| pool.install(|| search.search())))
+            .map_err(|_| "the search panicked".to_string())
+            .and_then(|outcome| outcome.map_err(|e| e.to_string()));
+        (pool, outcome)
+    })
+    .await
+    .expect("the search task catches panics");
+    server.pools.lock().expect("pool list poisoned").push(pool);
+
+    let mut progress = entry.progress();
+    progress.finished = Some(Instant::now());
+    progress.state = match outcome {
+        _ if entry.cancel.is_cancelled() => State::Cancelled,
+        Err(e) => {
+            progress.error = Some(e);
+            State::Failed
+        }
+        Ok(()) if progress.matches.len() >= entry.job.args.count as usize => State::Done,
+        Ok(()) => State::LimitReached,
+    };
+    println!(
+        "Job {} {} with {} matches",
+        id,
+        progress.state.name(),
+        progress.matches.len()
+    );
+}
+
+impl Entry {
+    fn progress(&self) -> std::sync::MutexGuard<'_, Progress> {
+        self.progress.lock().expect("job progress poisoned")
+    }
+
+    /// Runs the job's search on all cores, collecting matches as they are found.
+    fn search(&self) -> Result<(), CoreError> {
+        let args = &self.job.args;
+        let options = SearchOptions {
+            progress: Some(self.attempts.clone()),
+            cancel: self.cancel.clone(),
+            deadline: args
+                .timeout
+                .map(|timeout| Instant::now() + Duration::from_secs(timeout)),
+            max_attempts: args.max_attempts,
+        };
+        let mut sieve = MatchSieve::new(self.job.limits(), self.job.patterns.len());
+        generator(args, self.job.patterns.clone()).search_each(&options, &mut |found| {
+            if sieve.admit(&found) {
+                self.progress().matches.push(found);
+            }
+            !sieve.is_done()
+        })
+    }
+}
+
+fn status(entry: &Entry) -> Value {
+    let progress = entry.progress();
+    let attempts = entry.attempts.load(Ordering::Relaxed);
+    let elapsed = progress.started.map(|started| {
+        progress
+            .finished
+            .unwrap_or_else(Instant::now)
+            .duration_since(started)
+            .as_secs_f64()
+    });
+    let rate = elapsed
+        .filter(|&elapsed| elapsed > 0.0)
+        .map_or(0.0, |elapsed| attempts as f64 / elapsed);
+    let difficulty = entry.job.difficulty();
+    // Searches are memoryless, so the median wait for the next match never shrinks
+    let eta = (progress.state == State::Running && rate > 0.0)
+        .then(|| difficulty.time_for(0.5, rate))
+        .flatten()
+        .map(|eta| eta.as_secs_f64());
+    json!({
+        "state": progress.state.name(),
+        "job": entry.job.words,
+        "attempts": attempts,
+        "rate": rate,
+        "elapsed_seconds": elapsed,
+        "expected_attempts": (!difficulty.is_impossible()).then(|| difficulty.expected_attempts()),
+        "expected_attempts_exact": difficulty.is_exact(),
+        "eta_seconds": eta,
+        "matches": progress.matches.len(),
+        "count": entry.job.args.count,
+        "error": progress.error,
+    })
+}
+
+fn results(entry: &Entry) -> Value {
+    let progress = entry.progress();
+    let labels = entry.job.patterns.len() > 1;
+    let matches: Vec<Value> = progress
+        .matches
+        .iter()
+        .map(|found| {
+            let mut result = json!({
+                "address": found.address.to_string(),
+                "private_key": found.private_key.to_string(),
+            });
+            if let Some(pattern) = entry.job.patterns.get(found.pattern).filter(|_| labels) {
+                result["pattern"] = json!(pattern.to_string());
+            }
+            result
+        })
+        .collect();
+    json!({ "state": progress.state.name(), "results": matches })
+}
+
+fn cancel(server: &Server, id: &str, entry: &Entry) -> Response {
+    let mut progress = entry.progress();
+    match progress.state {
+        State::Queued => {
+            progress.state = State::Cancelled;
+            progress.finished = Some(Instant::now());
+            println!("Job {} cancelled", id);
+        }
+        // The search stops within a batch; `run_job` then records the cancellation
+        State::Running => entry.cancel.cancel(),
+        finished => {
+            drop(progress);
+            if let Ok(id) = id.parse::<u64>() {
+                server.jobs.lock().expect("job list poisoned").remove(&id);
+            }
+            return (200, json!({ "state": finished.name(), "forgotten": true }));
+        }
+    }
+    (
+        200,
+        json!({ "state": progress.state.name(), "forgotten": false }),
+    )
+}
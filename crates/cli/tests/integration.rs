@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use predicates::prelude::PredicateBooleanExt;
 
 #[test]
 fn test_help_snapshot() {
@@ -59,3 +60,820 @@ fn test_headless_execution() {
         .assert()
         .success();
 }
+
+#[test]
+fn test_headless_attempt_limit() {
+    // A 10-nibble prefix is out of reach in 1000 attempts, so the search must give up
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let mut cmd = Command::new(bin_path);
+    cmd.arg("--prefix")
+        .arg("ffffffffff")
+        .arg("--no-tui")
+        .arg("--max-attempts")
+        .arg("1000")
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Search limit reached"));
+}
+
+#[test]
+fn test_headless_invalid_pattern() {
+    // 'l' is not a Base58 digit: the pattern is rejected up front instead of searching forever
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let mut cmd = Command::new(bin_path);
+    cmd.args([
+        "--chain",
+        "solana",
+        "--prefix",
+        "Sol",
+        "--case-sensitive",
+        "--no-tui",
+    ])
+    .timeout(std::time::Duration::from_secs(5))
+    .assert()
+    .failure()
+    .stderr(predicates::str::contains(
+        "Invalid pattern: prefix: 'l' at position 2 is not in the Base58 alphabet",
+    ));
+}
+
+#[test]
+fn test_headless_wordlist() {
+    // Prefixes from the command line and a file; the result names the one it matched
+    let words = std::env::temp_dir().join(format!("vc-words-{}.txt", std::process::id()));
+    std::fs::write(&words, "# brand words\na0\n\nB1\n").unwrap();
+
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let mut cmd = Command::new(bin_path);
+    cmd.args(["--prefix", "0xc2", "--prefix-file"])
+        .arg(&words)
+        .arg("--no-tui")
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("prefix='0xc2 a0 B1'"))
+        .stdout(predicates::str::contains("  B1…: 1 in 256"))
+        .stdout(predicates::str::contains("Pattern: "));
+    std::fs::remove_file(&words).unwrap();
+}
+
+#[test]
+fn test_headless_count() {
+    // Matches stream until the count is reached; per-pattern caps end the run early
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let output = Command::new(bin_path)
+        .args(["--prefix", "a", "--count", "3", "--no-tui"])
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let addresses: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("Address: "))
+        .collect();
+    assert_eq!(addresses.len(), 3, "{stdout}");
+    assert!(addresses
+        .iter()
+        .all(|a| a.to_lowercase().starts_with("0xa")));
+    assert!(addresses[0] != addresses[1] && addresses[1] != addresses[2]);
+
+    let output = Command::new(bin_path)
+        .args(["--prefix", "a b", "--count", "10", "--per-pattern", "1"])
+        .arg("--no-tui")
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.matches("Pattern: ").count(), 2, "{stdout}");
+    assert!(stdout.contains("Pattern: a…") && stdout.contains("Pattern: b…"));
+}
+
+#[test]
+fn test_score_leaderboard() {
+    // The best addresses within the budget come out as JSON Lines, best first
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let output = Command::new(bin_path)
+        .args([
+            "--score",
+            "leading-zeros",
+            "--top",
+            "3",
+            "--max-attempts",
+            "5000",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{stdout}");
+    for (rank, line) in lines.iter().enumerate() {
+        assert!(line.starts_with(&format!("{{\"rank\": {}, \"score\": ", rank + 1)));
+        assert!(line.contains("\"addr\": \"0x") && line.contains("\"pk\": \"0x"));
+    }
+
+    // Without a budget the search would never end
+    Command::new(bin_path)
+        .args(["--score", "run"])
+        .timeout(std::time::Duration::from_secs(5))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--score needs a budget"));
+
+    // Pattern options would be ignored, so they are refused
+    for pattern in [["--exclude", "0"], ["--prefix-file", "words.txt"]] {
+        Command::new(bin_path)
+            .args(["--score", "run", "--max-attempts", "10"])
+            .args(pattern)
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("--score replaces patterns"));
+    }
+}
+
+#[test]
+fn test_headless_zero_bytes() {
+    // Zero-byte counts are estimated up front and reported with the result
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args(["--leading-zero-bytes", "1", "--no-tui"])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Difficulty: 1 in 256"))
+        .stdout(predicates::str::contains("Zero bytes: "));
+
+    Command::new(bin_path)
+        .args(["--chain", "solana", "--zero-bytes", "2", "--no-tui"])
+        .timeout(std::time::Duration::from_secs(5))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("only apply to Ethereum"));
+    Command::new(bin_path)
+        .args([
+            "--chain",
+            "solana",
+            "--zero-bytes",
+            "2",
+            "--generate-batch",
+            "1",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("only apply to Ethereum"));
+}
+
+#[test]
+fn test_create2_salt_mining() {
+    // CREATE2 mining prints the contract address and salt, keeping the salt prefix
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args([
+            "--deployer",
+            "0x4e59b44847b379578588920ca78fbf26c0b4956c",
+            "--init-code-hash",
+            "0x4242424242424242424242424242424242424242424242424242424242424242",
+            "--salt-prefix",
+            "0xdeadbeef",
+            "--prefix",
+            "0xab",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match("(?i)Contract: 0xab").unwrap())
+        .stdout(predicates::str::contains("Salt: 0xdeadbeef"));
+
+    Command::new(bin_path)
+        .args(["--deployer", "0x4e59", "--init-code-hash", "0x42"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("expected 20 bytes"));
+}
+
+#[test]
+fn test_create3_salt_mining() {
+    // Sender-guarded salts start with the sender and a zero chain-id flag
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args([
+            "--create3",
+            "--guard-sender",
+            "0x00000000000000000000000000000000deadbeef",
+            "--prefix",
+            "0xab",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Mining CREATE3 salts for factory 0xba5ed099",
+        ))
+        .stdout(predicates::str::is_match("(?i)Contract: 0xab").unwrap())
+        .stdout(predicates::str::contains(
+            "Salt: 0x00000000000000000000000000000000deadbeef00",
+        ));
+
+    Command::new(bin_path)
+        .args(["--guard-chain-id", "1", "--prefix", "ab"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--create3"));
+
+    // Salts are not keys, and batch and scoring output say so
+    for mode in [
+        &["--generate-batch", "1"][..],
+        &["--score", "run", "--max-attempts", "10"],
+    ] {
+        Command::new(bin_path)
+            .arg("--create3")
+            .args(mode)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("\"salt\": \"0x"))
+            .stdout(predicates::str::contains("\"pk\"").not());
+    }
+}
+
+#[test]
+fn test_safe_salt_nonce_mining() {
+    // Safe mode prints the address, the nonce and the factory call to make
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args([
+            "--safe-factory",
+            "0x4e1dcf7ad4e460cfd30791ccc4f9c8a4f820ec67",
+            "--safe-singleton",
+            "0x29fcb43b46531bca003ddc8fcb67ffe91900c762",
+            "--safe-initializer",
+            "0xb63e800d",
+            "--proxy-creation-code",
+            "0x608060405234801561001057600080fd5b50",
+            "--prefix",
+            "0x5a",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Mining Safe saltNonce values for factory 0x4e1dcf7a",
+        ))
+        .stdout(predicates::str::is_match("(?i)Safe: 0x5a").unwrap())
+        .stdout(predicates::str::is_match(r"saltNonce: \d+\n").unwrap())
+        .stdout(predicates::str::is_match(
+            r"createProxyWithNonce\(0x29fcB43b46531BcA003ddC8FCB67FFE91900C762, 0xb63e800d, \d+\)",
+        ).unwrap());
+
+    Command::new(bin_path)
+        .args([
+            "--safe-factory",
+            "0x4e1dcf7ad4e460cfd30791ccc4f9c8a4f820ec67",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--safe-singleton"));
+}
+
+#[test]
+fn test_erc4337_account_mining() {
+    // Account mode prints the salt and the initCode calling createAccount(owner, salt)
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args([
+            "--account-factory",
+            "0x9406cc6185a346906296840746125a0e44976454",
+            "--account-init-code",
+            "0x60806040{owner}",
+            "--owner",
+            "0x00000000000000000000000000000000deadbeef",
+            "--prefix",
+            "0xab",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Mining ERC-4337 account salts for factory 0x9406cc61",
+        ))
+        .stdout(predicates::str::is_match("(?i)Account: 0xab").unwrap())
+        .stdout(predicates::str::contains("Salt: 0x"))
+        .stdout(
+            predicates::str::is_match(
+                "initCode: 0x9406cc6185a346906296840746125a0e449764545fbfb9cf\
+             00000000000000000000000000000000000000000000000000000000deadbeef[0-9a-f]{64}\n",
+            )
+            .unwrap(),
+        );
+
+    Command::new(bin_path)
+        .args([
+            "--account-factory",
+            "0x9406cc6185a346906296840746125a0e44976454",
+            "--account-init-code",
+            "0x60806040",
+            "--owner",
+            "0x00000000000000000000000000000000deadbeef",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("{owner}"));
+}
+
+#[test]
+fn test_split_key_and_combine() {
+    // The requester hands out the public key of their secret; the worker never sees it
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let secret = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    Command::new(bin_path)
+        .args(["combine", "--secret", secret])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Public Key: 0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ))
+        .stdout(predicates::str::contains(
+            "Address: 0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+        ));
+
+    let output = Command::new(bin_path)
+        .args([
+            "--chain",
+            "bitcoin",
+            "--split-key",
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "--prefix",
+            "bc1qq",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap()
+            .to_string()
+    };
+    let (address, partial) = (field("Address: "), field("Partial Key: "));
+    assert!(!stdout.contains("Private Key"));
+
+    Command::new(bin_path)
+        .args(["combine", "--chain", "bitcoin", "--secret", secret])
+        .args(["--partial", &partial, "--address", &address])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Address matches"));
+
+    // A wrong secret derives another address
+    Command::new(bin_path)
+        .args([
+            "combine",
+            "--chain",
+            "bitcoin",
+            "--secret",
+            &secret.replace("01", "02"),
+        ])
+        .args(["--partial", &partial, "--address", &address])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("check the secret"));
+
+    // Batch and scoring output name partial keys as such
+    for mode in [
+        &["--generate-batch", "1"][..],
+        &["--score", "run", "--max-attempts", "10"],
+    ] {
+        Command::new(bin_path)
+            .args([
+                "--split-key",
+                "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            ])
+            .args(mode)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("\"partial\": \"0x"))
+            .stdout(predicates::str::contains("\"pk\"").not());
+    }
+}
+
+#[test]
+fn test_ed25519_split_key_and_combine() {
+    // RFC 8032 test 1; the combined key has no seed, so it is printed expanded
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let seed = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    Command::new(bin_path)
+        .args(["combine", "--chain", "solana", "--secret", seed])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Public Key: d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        ))
+        .stdout(predicates::str::contains(
+            "Address: FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z",
+        ));
+
+    let output = Command::new(bin_path)
+        .args(["--chain", "solana", "--prefix", "Ab"])
+        .args([
+            "--split-key",
+            "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z",
+        ])
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap()
+            .to_string()
+    };
+    let (address, partial) = (field("Address: "), field("Partial Key: "));
+
+    let output = Command::new(bin_path)
+        .args(["combine", "--chain", "solana", "--secret", seed])
+        .args(["--partial", &partial, "--address", &address])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Signature check passed"));
+    assert!(stdout.contains("Address matches"));
+    let expanded = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Private Key: "))
+        .unwrap();
+    assert_eq!(expanded.len(), 128);
+
+    // The expanded key combines like the seed it came from
+    Command::new(bin_path)
+        .args(["combine", "--chain", "solana", "--secret", expanded])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!("Address: {}", address)));
+
+    // 64 bytes of hex ending in the seed's public key are a keypair, not an expanded key
+    let keypair = format!("{seed}d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+    Command::new(bin_path)
+        .args(["combine", "--chain", "solana", "--secret", &keypair])
+        .args(["--partial", &partial, "--address", &address])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Address matches"));
+
+    Command::new(bin_path)
+        .args(["--chain", "ethereum", "--prefix", "ab"])
+        .args([
+            "--split-key",
+            "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("only applies to Solana and TON"));
+
+    // Scoring and batch generation check the key before running, rather than dropping it
+    for mode in [
+        &["--score", "run", "--max-attempts", "10"][..],
+        &["--generate-batch", "1"],
+    ] {
+        Command::new(bin_path)
+            .args(["--chain", "bitcoin"])
+            .args([
+                "--split-key",
+                "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z",
+            ])
+            .args(mode)
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("only applies to Solana and TON"));
+    }
+}
+
+#[test]
+fn test_deploy_nonce_mode() {
+    // Nonce mode reports the deploying account and the matched contract
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args(["--deploy-nonce", "0", "--prefix", "0xab"])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Deployer: 0x"))
+        .stdout(predicates::str::is_match("(?i)Contract: 0xab").unwrap())
+        .stdout(predicates::str::contains("Private Key: 0x"));
+
+    Command::new(bin_path)
+        .args(["--deploy-nonce", "0", "--create3", "--prefix", "ab"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_address_bits() {
+    // Two bit rules are combined as independent, so the odds are an estimate; values outside
+    // the mask are rejected
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    Command::new(bin_path)
+        .args([
+            "--mask",
+            "0xff=0x40",
+            "--below",
+            "0x8000000000000000000000000000000000000000",
+        ])
+        .args(["--no-tui"])
+        .timeout(std::time::Duration::from_secs(10))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Requiring bits 0xff = 0x40"))
+        .stdout(predicates::str::contains("Difficulty: about 1 in 512"))
+        .stdout(predicates::str::is_match("(?i)Address: 0x[0-7][0-9a-f]{37}40\n").unwrap());
+
+    Command::new(bin_path)
+        .args(["--mask", "0x0f=0x10", "--no-tui"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("outside its mask"));
+
+    // Bit rules are among the patterns headless mode asks for
+    Command::new(bin_path)
+        .args(["--no-tui"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--mask, --below or --above"));
+}
+
+/// Starts `vc` with `args` and returns it with the address its first line announces after
+/// `announcement`.
+fn start_listening(args: &[&str], announcement: &str) -> (std::process::Child, String) {
+    use std::io::Read;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_vc"))
+        .args(args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Byte by byte, so the rest of the output stays in the pipe
+    let stdout = child.stdout.as_mut().unwrap();
+    let mut first_line = Vec::new();
+    let mut byte = [0u8];
+    while stdout.read_exact(&mut byte).is_ok() && byte[0] != b'\n' {
+        first_line.push(byte[0]);
+    }
+    let address = String::from_utf8(first_line)
+        .unwrap()
+        .strip_prefix(announcement)
+        .unwrap()
+        .to_string();
+    (child, address)
+}
+
+fn start_coordinator(job: &[&str]) -> (std::process::Child, String) {
+    let mut args = vec!["coordinator", "--listen", "127.0.0.1:0", "--"];
+    args.extend_from_slice(job);
+    start_listening(&args, "Coordinator listening on ")
+}
+
+#[test]
+fn test_coordinator_and_workers() {
+    // Several workers share one search; the coordinator verifies hits and stops them all.
+    // The pattern takes long enough for every worker to join before the first hit.
+    let bin_path = env!("CARGO_BIN_EXE_vc");
+    let (coordinator, address) = start_coordinator(&["--prefix", "abcd", "--count", "2"]);
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let address = address.clone();
+            std::thread::spawn(move || {
+                std::process::Command::new(bin_path)
+                    .args(["worker", "--connect", &address])
+                    .output()
+            })
+        })
+        .collect();
+
+    let output = coordinator.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.matches("Verified: found by worker").count(),
+        2,
+        "{stdout}"
+    );
+    assert!(stdout.contains("Difficulty: 1 in 6553"));
+    for worker in workers {
+        let worker = worker.join().unwrap().unwrap();
+        assert!(worker.status.success());
+        let stdout = String::from_utf8(worker.stdout).unwrap();
+        assert!(stdout.contains("Job: --prefix abcd --count 2"));
+        assert!(stdout.contains("Stopped by the coordinator"));
+    }
+}
+
+#[test]
+fn test_coordinator_rejects_bad_results() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+
+    let (coordinator, address) = start_coordinator(&["--prefix", "abcdef", "--timeout", "2"]);
+
+    // A worker of another version gets no job
+    let mut stream = TcpStream::connect(&address).unwrap();
+    writeln!(stream, "hello 0.0.0").unwrap();
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply).unwrap();
+    assert!(reply.starts_with("error"), "{reply}");
+
+    // A hit whose key does not derive the address is rejected
+    let mut stream = TcpStream::connect(&address).unwrap();
+    writeln!(stream, "hello {}", env!("CARGO_PKG_VERSION")).unwrap();
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply).unwrap();
+    assert!(reply.starts_with("job "), "{reply}");
+    let key = format!("0x{:064x}", 1);
+    writeln!(
+        stream,
+        "hit 0xabcdef0000000000000000000000000000000000 {key}"
+    )
+    .unwrap();
+    drop(stream);
+
+    let output = coordinator.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("rejected a result from worker 2"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"));
+    assert!(stderr.contains("Search limit reached without a match"));
+
+    // Jobs are plain pattern searches
+    Command::new(env!("CARGO_BIN_EXE_vc"))
+        .args(["coordinator", "--listen", "127.0.0.1:0", "--"])
+        .args(["--prefix", "ab", "--create3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be distributed"));
+}
+
+/// Sends one HTTP request to `vc serve` and returns the status code and JSON body.
+fn http(
+    address: &str,
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+    body: &str,
+) -> (u16, serde_json::Value) {
+    use std::io::{Read, Write};
+
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    let authorization = token.map_or_else(String::new, |token| {
+        format!("Authorization: Bearer {token}\r\n")
+    });
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {address}\r\n{authorization}\
+         Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn test_serve_jobs() {
+    let (mut server, address) = start_listening(
+        &[
+            "serve",
+            "--listen",
+            "127.0.0.1:0",
+            "--queue",
+            "1",
+            "--concurrency",
+            "1",
+        ],
+        "Serving on http://",
+    );
+
+    let (status, job) = http(
+        &address,
+        "POST",
+        "/jobs",
+        None,
+        r#"{"args": ["--prefix", "ab", "--count", "2"]}"#,
+    );
+    assert_eq!(status, 202, "{job}");
+    let path = format!("/jobs/{}", job["id"]);
+    let token = job["token"].as_str().unwrap();
+
+    // Only the submitter's token gets answers
+    assert_eq!(http(&address, "GET", &path, None, "").0, 401);
+    assert_eq!(http(&address, "GET", &path, Some("00"), "").0, 403);
+    let results = format!("{path}/results");
+    assert_eq!(http(&address, "GET", &results, Some("00"), "").0, 403);
+
+    let started = std::time::Instant::now();
+    let status = loop {
+        let (code, status) = http(&address, "GET", &path, Some(token), "");
+        assert_eq!(code, 200);
+        if status["state"] != "queued" && status["state"] != "running" {
+            break status;
+        }
+        assert!(started.elapsed().as_secs() < 60, "{status}");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    assert_eq!(status["state"], "done", "{status}");
+    assert_eq!(status["matches"], 2);
+    assert!(status["attempts"].as_u64().unwrap() > 0);
+
+    let (code, found) = http(&address, "GET", &results, Some(token), "");
+    assert_eq!(code, 200);
+    let found = found["results"].as_array().unwrap();
+    assert_eq!(found.len(), 2);
+    for result in found {
+        let address = result["address"].as_str().unwrap();
+        assert!(address.to_lowercase().starts_with("0xab"), "{address}");
+        assert!(result["private_key"].as_str().unwrap().starts_with("0x"));
+    }
+
+    // One job runs and one waits; the queue refuses a third
+    let long = r#"{"args": ["--prefix", "ffffffffff"]}"#;
+    let (_, running) = http(&address, "POST", "/jobs", None, long);
+    let (_, queued) = http(&address, "POST", "/jobs", None, long);
+    let (status, full) = http(&address, "POST", "/jobs", None, long);
+    assert_eq!(status, 503, "{full}");
+    for job in [&running, &queued] {
+        let path = format!("/jobs/{}", job["id"]);
+        let token = job["token"].as_str();
+        assert_eq!(http(&address, "DELETE", &path, token, "").0, 200);
+    }
+    let path = format!("/jobs/{}", queued["id"]);
+    let (_, status) = http(&address, "GET", &path, queued["token"].as_str(), "");
+    assert_eq!(status["state"], "cancelled");
+
+    // Finished jobs are forgotten on request
+    let path = format!("/jobs/{}", job["id"]);
+    assert_eq!(http(&address, "DELETE", &path, Some(token), "").0, 200);
+    assert_eq!(http(&address, "GET", &path, Some(token), "").0, 404);
+
+    let (status, invalid) = http(
+        &address,
+        "POST",
+        "/jobs",
+        None,
+        r#"{"args": ["--create3"]}"#,
+    );
+    assert_eq!(status, 400);
+    assert!(invalid["error"].as_str().unwrap().contains("invalid job"));
+    assert_eq!(http(&address, "POST", "/jobs", None, "[]").0, 400);
+    assert_eq!(http(&address, "GET", "/elsewhere", None, "").0, 404);
+
+    server.kill().unwrap();
+    let _ = server.wait();
+}
+
+#[test]
+fn test_serve_concurrent_jobs() {
+    let (mut server, address) = start_listening(
+        &["serve", "--listen", "127.0.0.1:0", "--concurrency", "2"],
+        "Serving on http://",
+    );
+
+    // Each running job searches on its own pool, so neither waits for the other
+    let long = r#"{"args": ["--prefix", "ffffffffff"]}"#;
+    let jobs: Vec<_> = (0..2)
+        .map(|_| http(&address, "POST", "/jobs", None, long).1)
+        .collect();
+    let started = std::time::Instant::now();
+    for job in &jobs {
+        let path = format!("/jobs/{}", job["id"]);
+        loop {
+            let (_, status) = http(&address, "GET", &path, job["token"].as_str(), "");
+            if status["state"] == "running" && status["attempts"].as_u64().unwrap() > 0 {
+                break;
+            }
+            assert!(started.elapsed().as_secs() < 60, "{status}");
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+    }
+    // The first job is still searching while the second makes progress
+    let path = format!("/jobs/{}", jobs[0]["id"]);
+    let (_, first) = http(&address, "GET", &path, jobs[0]["token"].as_str(), "");
+    assert_eq!(first["state"], "running", "{first}");
+
+    server.kill().unwrap();
+    let _ = server.wait();
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::error::CoreError;
+
+/// A rule on a raw 20-byte Ethereum address read as a 160-bit big-endian number.
+///
+/// Uniswap v4 hooks encode their permissions in the low address bits, and pools order their
+/// two tokens by address, so some contracts must sit in a given bit pattern or numeric range.
+/// Rules are checked on the `[u8; 20]` of [`Address::Ethereum`](crate::Address::Ethereum).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressBits {
+    /// The bits set in `mask` must equal those of `value`.
+    Mask { mask: [u8; 20], value: [u8; 20] },
+    /// Numerically below this address.
+    Below([u8; 20]),
+    /// Numerically above this address.
+    Above([u8; 20]),
+}
+
+impl AddressBits {
+    /// Rejects values outside their mask and bounds no address can meet.
+    pub fn validate(&self) -> Result<(), CoreError> {
+        let invalid = match self {
+            AddressBits::Mask { mask, value }
+                if value.iter().zip(mask).any(|(v, m)| v & !m != 0) =>
+            {
+                "the value sets bits outside its mask"
+            }
+            AddressBits::Below(bound) if *bound == [0; 20] => "no address is below zero",
+            AddressBits::Above(bound) if *bound == [0xff; 20] => {
+                "no address is above the largest one"
+            }
+            _ => return Ok(()),
+        };
+        Err(CoreError::InvalidPattern(format!("{self}: {invalid}")))
+    }
+
+    #[inline]
+    pub fn matches(&self, address: &[u8; 20]) -> bool {
+        match self {
+            AddressBits::Mask { mask, value } => address
+                .iter()
+                .zip(mask)
+                .zip(value)
+                .all(|((a, m), v)| a & m == *v),
+            // Big-endian byte arrays compare like the numbers they encode
+            AddressBits::Below(bound) => address < bound,
+            AddressBits::Above(bound) => address > bound,
+        }
+    }
+
+    /// Chance that a uniformly random address satisfies the rule.
+    pub fn probability(&self) -> f64 {
+        match self {
+            AddressBits::Mask { mask, .. } => {
+                let bits: u32 = mask.iter().map(|m| m.count_ones()).sum();
+                0.5f64.powi(bits as i32)
+            }
+            // Addresses below `bound` number `bound` of 2^160
+            AddressBits::Below(bound) => fraction(bound),
+            AddressBits::Above(bound) => fraction(&bound.map(|b| !b)),
+        }
+    }
+}
+
+impl fmt::Display for AddressBits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressBits::Mask { mask, value } => {
+                write!(f, "bits {} = {}", short_hex(mask), short_hex(value))
+            }
+            AddressBits::Below(bound) => write!(f, "below {}", short_hex(bound)),
+            AddressBits::Above(bound) => write!(f, "above {}", short_hex(bound)),
+        }
+    }
+}
+
+/// `number / 2^160` for a 160-bit big-endian number.
+fn fraction(number: &[u8; 20]) -> f64 {
+    number
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &byte| (acc + byte as f64) / 256.0)
+}
+
+/// `0x`-prefixed hex without leading zeros, e.g. `0x3fff` for a mask of the low 14 bits.
+fn short_hex(number: &[u8; 20]) -> String {
+    let digits = hex::encode(number);
+    let trimmed = digits.trim_start_matches('0');
+    format!("0x{}", if trimmed.is_empty() { "0" } else { trimmed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(low: u64) -> [u8; 20] {
+        let mut bytes = [0u8; 20];
+        bytes[12..].copy_from_slice(&low.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_matches() {
+        // Uniswap v4 hook flags live in the low 14 bits
+        let hook = AddressBits::Mask {
+            mask: number(0x3fff),
+            value: number(0x0480),
+        };
+        let mut address = [0xab; 20];
+        address[18] = 0xc4;
+        address[19] = 0x80;
+        assert!(hook.matches(&address));
+        address[19] = 0x81;
+        assert!(!hook.matches(&address));
+
+        let mut high = [0u8; 20];
+        high[0] = 0x80;
+        assert!(AddressBits::Below(high).matches(&number(u64::MAX)));
+        assert!(!AddressBits::Below(high).matches(&high));
+        assert!(AddressBits::Above(number(5)).matches(&number(6)));
+        assert!(!AddressBits::Above(number(5)).matches(&number(5)));
+    }
+
+    #[test]
+    fn test_probability() {
+        let mask = AddressBits::Mask {
+            mask: number(0x3fff),
+            value: number(0),
+        };
+        assert_eq!(mask.probability(), 1.0 / 16384.0);
+        let mut half = [0u8; 20];
+        half[0] = 0x80;
+        assert_eq!(AddressBits::Below(half).probability(), 0.5);
+        assert!((AddressBits::Above(half).probability() - 0.5).abs() < 1e-12);
+        assert_eq!(AddressBits::Below([0; 20]).probability(), 0.0);
+    }
+
+    #[test]
+    fn test_validate_and_display() {
+        let mask = AddressBits::Mask {
+            mask: number(0x0f),
+            value: number(0x10),
+        };
+        assert!(mask.validate().is_err());
+        assert!(AddressBits::Below([0; 20]).validate().is_err());
+        assert!(AddressBits::Above([0xff; 20]).validate().is_err());
+        assert!(AddressBits::Above([0; 20]).validate().is_ok());
+
+        let hook = AddressBits::Mask {
+            mask: number(0x3fff),
+            value: number(0),
+        };
+        assert_eq!(hook.to_string(), "bits 0x3fff = 0x0");
+        assert_eq!(AddressBits::Below(number(0x100)).to_string(), "below 0x100");
+    }
+}
@@ -0,0 +1,456 @@
+use std::cmp::{max, min};
+
+use crate::pattern::CharClass;
+
+/// Bitcoin Base58 alphabet (also used by Solana); the index of a character is its digit value.
+pub const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Cap on the variants expanded for case-insensitive letters and character classes.
+/// Characters past the cap are left unconstrained; the string check on hits still covers them.
+const MAX_VARIANTS: usize = 1024;
+
+/// Largest power of 58 used by [`Base58Suffix`] (58^10 < 2^64).
+const MAX_SUFFIX_DIGITS: usize = 10;
+
+/// Payloads of a fixed byte length whose Base58 encoding starts with a prefix.
+///
+/// Base58 encodes a big-endian payload as one `'1'` per leading zero byte followed by the
+/// digits of the payload as a number, so a prefix corresponds to a handful of numeric ranges
+/// (one per possible encoding length). Hot loops compare raw bytes against these ranges and
+/// only encode the candidates that fall inside.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Base58Prefix {
+    /// Sorted, disjoint, inclusive `[lo, hi]` bounds, each `len` bytes long.
+    ranges: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Leading pattern characters covered by the ranges (fewer than the pattern past the cap).
+    chars: usize,
+}
+
+impl Base58Prefix {
+    /// Compiles the prefix classes for payloads of `len` bytes (32 for Solana, 25 for P2PKH).
+    ///
+    /// Classes are expanded into every digit sequence they allow. A class with no character in
+    /// the alphabet makes the prefix unsatisfiable.
+    pub fn new(prefix: &[CharClass], len: usize) -> Self {
+        let columns: Vec<Vec<u8>> = prefix.iter().map(|&class| digits(class)).collect();
+
+        let variants = expand(&columns);
+        let chars = variants.first().map_or(0, Vec::len);
+        let mut ranges = Vec::new();
+        for digits in variants {
+            push_ranges(&digits, len, &mut ranges);
+        }
+        ranges.sort();
+
+        let mut merged: Vec<(Vec<u8>, Vec<u8>)> = Vec::with_capacity(ranges.len());
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= *last_hi => *last_hi = max(last_hi.clone(), hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+
+        Self {
+            ranges: merged,
+            chars,
+        }
+    }
+
+    /// Whether any payload of this length can match.
+    pub fn is_satisfiable(&self) -> bool {
+        !self.ranges.is_empty()
+    }
+
+    /// Whether every payload matches (empty prefix).
+    pub fn is_unconstrained(&self) -> bool {
+        match self.ranges.as_slice() {
+            [(lo, hi)] => lo.iter().all(|&b| b == 0) && hi.iter().all(|&b| b == 0xff),
+            _ => false,
+        }
+    }
+
+    /// Number of disjoint ranges.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of leading pattern characters the ranges account for.
+    pub(crate) fn compiled_chars(&self) -> usize {
+        self.chars
+    }
+
+    /// Fraction of the payloads in the inclusive range `[lo, hi]` that match.
+    pub fn density(&self, lo: &[u8], hi: &[u8]) -> f64 {
+        let covered: f64 = self
+            .ranges
+            .iter()
+            .filter_map(|(start, end)| {
+                let start = max(start.as_slice(), lo);
+                let end = min(end.as_slice(), hi);
+                (start <= end).then(|| span(start, end))
+            })
+            .sum();
+        covered / span(lo, hi)
+    }
+
+    /// Checks a `len`-byte payload.
+    #[inline]
+    pub fn contains(&self, bytes: &[u8]) -> bool {
+        let i = self
+            .ranges
+            .partition_point(|(lo, _)| lo.as_slice() <= bytes);
+        i > 0 && bytes <= self.ranges[i - 1].1.as_slice()
+    }
+
+    /// Whether any payload in the inclusive range `[lo, hi]` matches.
+    ///
+    /// Used when the tail of the payload is not known yet, e.g. a P2PKH checksum:
+    /// `lo`/`hi` are the payload with the unknown bytes set to `0x00`/`0xff`.
+    #[inline]
+    pub fn intersects(&self, lo: &[u8], hi: &[u8]) -> bool {
+        let i = self.ranges.partition_point(|(_, end)| end.as_slice() < lo);
+        i < self.ranges.len() && self.ranges[i].0.as_slice() <= hi
+    }
+}
+
+/// Necessary condition for a Base58 suffix, checked on the payload as a number.
+///
+/// The last `k` digits of an encoding are the payload modulo `58^k`, as long as the payload
+/// has at least `k` digits. Only the last ten characters are used so the modulus fits a `u64`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Base58Suffix {
+    modulus: u64,
+    /// Sorted residues of the accepted suffix variants.
+    residues: Vec<u64>,
+}
+
+impl Base58Suffix {
+    pub fn new(suffix: &[CharClass]) -> Self {
+        let skipped = suffix.len().saturating_sub(MAX_SUFFIX_DIGITS);
+        let columns: Vec<Vec<u8>> = suffix[skipped..]
+            .iter()
+            .map(|&class| digits(class))
+            .collect();
+
+        // Keep the last characters whose variant count stays within the cap
+        let mut variants = 1usize;
+        let mut keep = 0;
+        for column in columns.iter().rev() {
+            variants = variants.saturating_mul(column.len().max(1));
+            if variants > MAX_VARIANTS {
+                break;
+            }
+            keep += 1;
+        }
+        let columns = if columns.iter().any(Vec::is_empty) {
+            columns
+        } else {
+            columns[columns.len() - keep..].to_vec()
+        };
+
+        let modulus = 58u64.pow(columns.len() as u32);
+        let mut residues: Vec<u64> = expand(&columns)
+            .iter()
+            .map(|digits| digits.iter().fold(0, |acc, &d| acc * 58 + d as u64))
+            .collect();
+        residues.sort_unstable();
+        residues.dedup();
+
+        Self { modulus, residues }
+    }
+
+    /// Whether every payload passes (empty suffix).
+    pub fn is_unconstrained(&self) -> bool {
+        self.modulus == 1
+    }
+
+    /// Checks a payload; payloads too small to have all suffix digits always pass.
+    #[inline]
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        if self.is_unconstrained() {
+            return true;
+        }
+        // Anything wider than 64 bits is above 58^10 and has every suffix digit
+        if bytes.len() <= 8 || bytes[..bytes.len() - 8].iter().all(|&b| b == 0) {
+            return true;
+        }
+        self.residues.binary_search(&self.residue(bytes)).is_ok()
+    }
+
+    fn residue(&self, bytes: &[u8]) -> u64 {
+        let modulus = self.modulus as u128;
+        let (head, tail) = bytes.split_at(bytes.len() % 8);
+        let mut r = head.iter().fold(0u128, |acc, &b| acc << 8 | b as u128) % modulus;
+        for chunk in tail.chunks_exact(8) {
+            let word = u64::from_be_bytes(chunk.try_into().expect("8-byte chunk"));
+            r = (r << 64 | word as u128) % modulus;
+        }
+        r as u64
+    }
+}
+
+/// Digit values a class allows.
+pub(crate) fn digits(class: CharClass) -> Vec<u8> {
+    (0..ALPHABET.len() as u8)
+        .filter(|&d| class.contains(ALPHABET[d as usize] as char))
+        .collect()
+}
+
+/// Cartesian product of the per-character alternatives, keeping the leading characters
+/// whose variant count stays within [`MAX_VARIANTS`].
+fn expand(columns: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    if columns.iter().any(Vec::is_empty) {
+        return Vec::new();
+    }
+    let mut variants = vec![Vec::new()];
+    for column in columns {
+        if variants.len() * column.len() > MAX_VARIANTS {
+            break;
+        }
+        variants = variants
+            .iter()
+            .flat_map(|head| {
+                column.iter().map(move |&d| {
+                    let mut digits: Vec<u8> = head.clone();
+                    digits.push(d);
+                    digits
+                })
+            })
+            .collect();
+    }
+    variants
+}
+
+/// Appends the ranges of `len`-byte payloads whose encoding starts with `digits`.
+fn push_ranges(digits: &[u8], len: usize, out: &mut Vec<(Vec<u8>, Vec<u8>)>) {
+    let zeros = digits.iter().take_while(|&&d| d == 0).count();
+    if zeros > len {
+        return;
+    }
+    let rest = &digits[zeros..];
+    // Two spare bytes so multiples of 58 just above the payload range do not overflow
+    let width = len + 2;
+    let truncate = |n: Vec<u8>| n[2..].to_vec();
+
+    // Below 256^(len - zeros): at least `zeros` leading zero bytes
+    let mut upper = power_of_256(len - zeros, width);
+    sub_one(&mut upper);
+    if rest.is_empty() {
+        out.push((vec![0; len], truncate(upper)));
+        return;
+    }
+    if zeros == len {
+        return;
+    }
+    // At or above 256^(len - zeros - 1): exactly `zeros` leading zero bytes
+    let lower = power_of_256(len - zeros - 1, width);
+
+    let mut max_digits = 0;
+    let mut scale = vec![0; width];
+    add_small(&mut scale, 1);
+    while scale <= upper {
+        mul_small(&mut scale, 58);
+        max_digits += 1;
+    }
+    if rest.len() > max_digits {
+        return;
+    }
+
+    // Numbers whose digits start with `rest` lie in [value·58^k, (value+1)·58^k) for k >= 0
+    let mut value = vec![0; width];
+    for &d in rest {
+        mul_small(&mut value, 58);
+        add_small(&mut value, d as u32);
+    }
+    let mut next = value.clone();
+    add_small(&mut next, 1);
+
+    while value <= upper {
+        let lo = max(&value, &lower).clone();
+        let mut hi = next.clone();
+        sub_one(&mut hi);
+        let hi = min(hi, upper.clone());
+        if lo <= hi {
+            out.push((truncate(lo), truncate(hi)));
+        }
+        mul_small(&mut value, 58);
+        mul_small(&mut next, 58);
+    }
+}
+
+/// Number of integers in `[lo, hi]` (equal-length big-endian bytes), as a float.
+fn span(lo: &[u8], hi: &[u8]) -> f64 {
+    let mut diff = hi.to_vec();
+    let mut borrow = 0;
+    for (d, &l) in diff.iter_mut().zip(lo).rev() {
+        let v = *d as i16 - l as i16 - borrow;
+        *d = v.rem_euclid(256) as u8;
+        borrow = (v < 0) as i16;
+    }
+    diff.iter().fold(0.0, |acc, &b| acc * 256.0 + b as f64) + 1.0
+}
+
+fn power_of_256(exp: usize, width: usize) -> Vec<u8> {
+    let mut n = vec![0; width];
+    n[width - 1 - exp] = 1;
+    n
+}
+
+fn mul_small(n: &mut [u8], m: u32) {
+    let mut carry = 0;
+    for byte in n.iter_mut().rev() {
+        let v = *byte as u32 * m + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+}
+
+fn add_small(n: &mut [u8], a: u32) {
+    let mut carry = a;
+    for byte in n.iter_mut().rev() {
+        if carry == 0 {
+            break;
+        }
+        let v = *byte as u32 + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+}
+
+fn sub_one(n: &mut [u8]) {
+    for byte in n.iter_mut().rev() {
+        let (v, borrow) = byte.overflowing_sub(1);
+        *byte = v;
+        if !borrow {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Pattern;
+    use proptest::prelude::*;
+
+    fn compile_prefix(text: &str, len: usize, case_sensitive: bool) -> Base58Prefix {
+        let pattern = Pattern::new(text, "", case_sensitive).unwrap();
+        Base58Prefix::new(&pattern.prefix_classes(), len)
+    }
+
+    fn compile_suffix(text: &str, case_sensitive: bool) -> Base58Suffix {
+        let pattern = Pattern::new("", text, case_sensitive).unwrap();
+        Base58Suffix::new(&pattern.suffix_classes())
+    }
+
+    fn starts_with(bytes: &[u8], prefix: &str, case_sensitive: bool) -> bool {
+        let encoded = bs58::encode(bytes).into_string();
+        Pattern::new(prefix, "", case_sensitive)
+            .unwrap()
+            .matches(&encoded)
+    }
+
+    #[test]
+    fn test_range_boundaries() {
+        let prefix = compile_prefix("Sun", 32, true);
+        assert!(prefix.is_satisfiable());
+        for (lo, hi) in &prefix.ranges {
+            // Both ends of each range encode to the prefix, the bytes just outside do not
+            assert!(starts_with(lo, "Sun", true));
+            assert!(starts_with(hi, "Sun", true));
+
+            let mut below = lo.clone();
+            sub_one(&mut below);
+            assert!(!starts_with(&below, "Sun", true));
+            let mut above = hi.clone();
+            add_small(&mut above, 1);
+            assert!(!starts_with(&above, "Sun", true));
+        }
+    }
+
+    #[test]
+    fn test_leading_ones() {
+        // "1" requires exactly one leading zero byte when followed by other digits
+        let prefix = compile_prefix("1A", 25, true);
+        let mut bytes = [0xffu8; 25];
+        bytes[0] = 0;
+        assert_eq!(prefix.contains(&bytes), starts_with(&bytes, "1A", true));
+        bytes[1] = 0;
+        assert!(!prefix.contains(&bytes));
+
+        // Only '1's: at least that many leading zero bytes
+        let ones = compile_prefix("11", 25, true);
+        assert!(ones.contains(&[0; 25]));
+        let mut bytes = [0u8; 25];
+        bytes[2] = 1;
+        assert!(ones.contains(&bytes));
+        bytes[1] = 1;
+        assert!(!ones.contains(&bytes));
+    }
+
+    #[test]
+    fn test_unsatisfiable_and_unconstrained() {
+        assert!(!compile_prefix("0", 32, true).is_satisfiable());
+        assert!(!compile_prefix("l", 32, true).is_satisfiable());
+        // 'l' is invalid but 'L' is not
+        assert!(compile_prefix("l", 32, false).is_satisfiable());
+        // Longer than any 32-byte encoding
+        assert!(!compile_prefix(&"z".repeat(45), 32, true).is_satisfiable());
+        assert!(compile_prefix("", 32, true).is_unconstrained());
+        assert!(compile_suffix("", true).is_unconstrained());
+    }
+
+    #[test]
+    fn test_p2pkh_checksum_interval() {
+        // Version 0x00 + hash160 of the key for secret 1: 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH
+        let mut lo = [0u8; 25];
+        hex::decode_to_slice("751e76e8199196d454941c45d1b3a323f1433bd6", &mut lo[1..21]).unwrap();
+        let mut hi = lo;
+        hi[21..].fill(0xff);
+
+        assert!(compile_prefix("1BgGZ9", 25, true).intersects(&lo, &hi));
+        assert!(!compile_prefix("1BgGZ8", 25, true).intersects(&lo, &hi));
+        assert!(!compile_prefix("1C", 25, true).intersects(&lo, &hi));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_prefix_matches_encoding(
+            bytes in prop::collection::vec(any::<u8>(), 32),
+            zeros in 0usize..3,
+            take in 1usize..5,
+            case_sensitive in any::<bool>(),
+        ) {
+            let mut bytes = bytes;
+            bytes[..zeros].fill(0);
+            let encoded = bs58::encode(&bytes).into_string();
+
+            // Prefix taken from the encoding itself must match
+            let own = &encoded[..take.min(encoded.len())];
+            prop_assert!(compile_prefix(own, 32, case_sensitive).contains(&bytes));
+
+            // Arbitrary prefixes agree with the string check
+            for probe in ["1", "11", "A", "z", "Sun", "abc", "2", "[A-C]?", "?1", "[^2-9]"] {
+                prop_assert_eq!(
+                    compile_prefix(probe, 32, case_sensitive).contains(&bytes),
+                    starts_with(&bytes, probe, case_sensitive),
+                    "prefix {} encoding {}", probe, encoded
+                );
+            }
+        }
+
+        #[test]
+        fn prop_suffix_is_necessary(
+            bytes in prop::collection::vec(any::<u8>(), 32),
+            take in 1usize..14,
+            case_sensitive in any::<bool>(),
+        ) {
+            let encoded = bs58::encode(&bytes).into_string();
+            let own = &encoded[encoded.len() - take.min(encoded.len())..];
+            prop_assert!(compile_suffix(own, case_sensitive).matches(&bytes));
+
+            let suffix = compile_suffix("zz", true);
+            prop_assert_eq!(suffix.matches(&bytes), encoded.ends_with("zz"));
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::mask::ByteMask;
+use crate::pattern::{self, CharClass};
+
+/// URL-safe Base64 alphabet; the index of a character is its 6-bit value.
+pub const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Prefix and suffix of an unpadded Base64 string compiled to bit constraints on its bytes.
+///
+/// The encoded bytes are `head ‖ body ‖ checksum`: the head is fixed (TON tag and workchain),
+/// the body is what the search varies (the state-init hash) and the checksum is derived from
+/// both. Body constraints reject candidates before the checksum or the string are computed;
+/// checksum constraints are only checked for candidates whose body already matches.
+///
+/// Classes (including case-folded letters) constrain the bits their values share, so matching
+/// is a necessary condition there and hits must still be confirmed on the encoded string.
+#[derive(Clone, Debug)]
+pub struct Base64Pattern {
+    body: ByteMask,
+    checksum: ByteMask,
+    /// Chance that a uniformly random body (and its checksum) matches.
+    probability: f64,
+    satisfiable: bool,
+}
+
+impl Base64Pattern {
+    /// Compiles prefix/suffix classes for `head ‖ body_len bytes ‖ checksum_len bytes`.
+    /// The total length must be a multiple of three (no padding characters).
+    pub fn new(
+        head: &[u8],
+        body_len: usize,
+        checksum_len: usize,
+        prefix: &[CharClass],
+        suffix: &[CharClass],
+    ) -> Self {
+        let total_len = head.len() + body_len + checksum_len;
+        debug_assert_eq!(total_len % 3, 0, "padded Base64 is not supported");
+        let total_chars = total_len * 4 / 3;
+
+        let mut pattern = Self {
+            body: ByteMask::new(body_len),
+            checksum: ByteMask::new(checksum_len),
+            probability: 1.0,
+            satisfiable: true,
+        };
+        let Some(positions) = pattern::anchored(total_chars, prefix, suffix) else {
+            pattern.satisfiable = false;
+            return pattern;
+        };
+        for (index, class) in positions.into_iter().enumerate() {
+            if class == CharClass::ANY {
+                continue;
+            }
+            if pattern.require(head, index, class) {
+                pattern.probability *= pattern_odds(head, index, class);
+            } else {
+                pattern.satisfiable = false;
+            }
+        }
+        pattern
+    }
+
+    /// Chance that one candidate matches, counting every case variant of each character.
+    pub fn probability(&self) -> f64 {
+        if self.satisfiable {
+            self.probability
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether any body can match.
+    pub fn is_satisfiable(&self) -> bool {
+        self.satisfiable
+    }
+
+    /// Number of body and checksum bits fixed by the pattern.
+    pub fn constrained_bits(&self) -> u32 {
+        self.body.constrained_bits() + self.checksum.constrained_bits()
+    }
+
+    /// Whether [`matches_checksum`](Self::matches_checksum) has anything to check.
+    pub fn needs_checksum(&self) -> bool {
+        !self.checksum.is_empty()
+    }
+
+    #[inline]
+    pub fn matches_body(&self, body: &[u8]) -> bool {
+        self.body.matches(body)
+    }
+
+    #[inline]
+    pub fn matches_checksum(&self, checksum: &[u8]) -> bool {
+        self.checksum.matches(checksum)
+    }
+
+    /// Requires a character of `class` at `index`, keeping the bits shared by every value
+    /// that agrees with the fixed head.
+    fn require(&mut self, head: &[u8], index: usize, class: CharClass) -> bool {
+        let head_bits = head.len() * 8;
+        let body_bits = self.body.len() * 8;
+        let values = head_values(head, index, class);
+        let Some(&first) = values.first() else {
+            return false;
+        };
+
+        for k in 0..6 {
+            let bit = value_bit(first, k);
+            if values.iter().any(|&value| value_bit(value, k) != bit) {
+                continue;
+            }
+            let position = index * 6 + k;
+            let required = if position < head_bits {
+                true
+            } else if position < head_bits + body_bits {
+                self.body.require_bits(position - head_bits, 1, bit as u64)
+            } else {
+                self.checksum
+                    .require_bits(position - head_bits - body_bits, 1, bit as u64)
+            };
+            if !required {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 6-bit values a `class` character may stand for at `index`, given the fixed head bits.
+fn head_values(head: &[u8], index: usize, class: CharClass) -> Vec<u8> {
+    let head_bits = head.len() * 8;
+    let head_bit = |position: usize| head[position / 8] >> (7 - position % 8) & 1;
+
+    URL_SAFE_ALPHABET
+        .iter()
+        .enumerate()
+        .filter(|(_, &a)| class.contains(a as char))
+        .map(|(value, _)| value as u8)
+        .filter(|&value| {
+            (0..6).all(|k| {
+                let position = index * 6 + k;
+                position >= head_bits || head_bit(position) == value_bit(value, k)
+            })
+        })
+        .collect()
+}
+
+/// Chance that the free (non-head) bits of character `index` spell one of the class values.
+fn pattern_odds(head: &[u8], index: usize, class: CharClass) -> f64 {
+    let free_bits = (index * 6 + 6).saturating_sub(head.len() * 8).min(6);
+    head_values(head, index, class).len() as f64 / (1u32 << free_bits) as f64
+}
+
+#[inline]
+fn value_bit(value: u8, k: usize) -> u8 {
+    value >> (5 - k) & 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Pattern;
+
+    fn compile(prefix: &str, suffix: &str, case_sensitive: bool) -> Base64Pattern {
+        let pattern = Pattern::new(prefix, suffix, case_sensitive).unwrap();
+        Base64Pattern::new(
+            &HEAD,
+            32,
+            2,
+            &pattern.prefix_classes(),
+            &pattern.suffix_classes(),
+        )
+    }
+
+    // UQAf25uVNlQUbtYFPintHesYHmC_GDRUa63bqAwtMp8McURe (TON V5R1 test vector)
+    const HEAD: [u8; 2] = [0x51, 0x00];
+
+    fn decode(address: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let (mut acc, mut bits) = (0u32, 0);
+        for c in address.bytes() {
+            let value = URL_SAFE_ALPHABET.iter().position(|&a| a == c).unwrap();
+            acc = acc << 6 | value as u32;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((acc >> bits) as u8);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_prefix_suffix_and_checksum() {
+        let address = "UQAf25uVNlQUbtYFPintHesYHmC_GDRUa63bqAwtMp8McURe";
+        let bytes = decode(address);
+        let (body, checksum) = (&bytes[2..34], &bytes[34..]);
+
+        for (prefix, suffix, case_sensitive) in [
+            ("UQAf25", "", true),
+            ("uqaF25", "", false),
+            ("", "McURe", true),
+            ("UQ", "cure", false),
+        ] {
+            let pattern = compile(prefix, suffix, case_sensitive);
+            assert!(pattern.is_satisfiable());
+            assert!(pattern.matches_body(body), "{prefix}/{suffix}");
+            assert!(pattern.matches_checksum(checksum), "{prefix}/{suffix}");
+        }
+
+        // The last two characters only depend on the checksum
+        let pattern = compile("", "Rf", true);
+        assert!(pattern.needs_checksum());
+        assert!(pattern.matches_body(body));
+        assert!(!pattern.matches_checksum(checksum));
+
+        let pattern = compile("UQAg", "", true);
+        assert!(!pattern.needs_checksum());
+        assert!(!pattern.matches_body(body));
+    }
+
+    #[test]
+    fn test_fixed_head_rejects_impossible_prefixes() {
+        // Workchain 0 leaves four free bits after "UQ": only 'A'-'D' can follow
+        for prefix in ["UQA", "UQD", "uqd"] {
+            assert!(compile(prefix, "", false).is_satisfiable());
+        }
+        for (prefix, case_sensitive) in [("UQE", true), ("UQa", true), ("EQ", true), ("UQz", false)]
+        {
+            let pattern = compile(prefix, "", case_sensitive);
+            assert!(!pattern.is_satisfiable(), "{prefix}");
+        }
+        assert!(!compile("UQA!", "", true).is_satisfiable());
+        // Classes keep the values the head allows
+        assert_eq!(compile("UQ[A-D]", "", true).probability(), 1.0);
+        assert_eq!(compile("UQ[AE]", "", true).probability(), 0.25);
+        assert!(!compile("", &"A".repeat(49), true).is_satisfiable());
+    }
+}
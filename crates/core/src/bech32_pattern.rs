@@ -0,0 +1,370 @@
+use crate::mask::ByteMask;
+use crate::pattern::{self, CharClass};
+
+/// Bech32 data alphabet; the index of a character is its 5-bit value.
+pub const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const CHECKSUM_LEN: usize = 6;
+
+/// Checksum constant: BIP-173 Bech32 or BIP-350 Bech32m.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Bech32Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Bech32Variant::Bech32 => 1,
+            Bech32Variant::Bech32m => 0x2bc8_30a3,
+        }
+    }
+}
+
+/// Shape shared by every address of one kind: `hrp 1 [version] payload checksum`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bech32Layout {
+    pub hrp: String,
+    /// SegWit witness version, encoded as one character before the payload.
+    pub witness_version: Option<u8>,
+    /// Payload length in bytes (key hash or output key).
+    pub payload_len: usize,
+    pub variant: Bech32Variant,
+}
+
+impl Bech32Layout {
+    /// Cosmos SDK account address: the 20-byte key hash under a chain-specific HRP.
+    pub fn cosmos(hrp: &str) -> Self {
+        Self {
+            hrp: hrp.to_lowercase(),
+            witness_version: None,
+            payload_len: 20,
+            variant: Bech32Variant::Bech32,
+        }
+    }
+
+    /// SegWit v0 key hash (P2WPKH, BIP-173).
+    pub fn p2wpkh(hrp: &str) -> Self {
+        Self {
+            hrp: hrp.to_lowercase(),
+            witness_version: Some(0),
+            payload_len: 20,
+            variant: Bech32Variant::Bech32,
+        }
+    }
+
+    /// Taproot output key (P2TR, BIP-350).
+    pub fn p2tr(hrp: &str) -> Self {
+        Self {
+            hrp: hrp.to_lowercase(),
+            witness_version: Some(1),
+            payload_len: 32,
+            variant: Bech32Variant::Bech32m,
+        }
+    }
+
+    /// Characters every address starts with: HRP, separator and witness version.
+    pub fn head(&self) -> String {
+        let mut head = format!("{}1", self.hrp);
+        if let Some(version) = self.witness_version {
+            head.push(CHARSET[version as usize] as char);
+        }
+        head
+    }
+
+    /// Number of 5-bit characters carrying the payload (the last one may be zero-padded).
+    pub fn payload_chars(&self) -> usize {
+        (self.payload_len * 8).div_ceil(5)
+    }
+
+    /// Length of the full address string.
+    pub fn address_len(&self) -> usize {
+        self.head().len() + self.payload_chars() + CHECKSUM_LEN
+    }
+}
+
+/// Prefix and suffix of a Bech32 address compiled to constraints on the raw payload.
+///
+/// Every data character is a fixed 5-bit group of the payload, so prefix and suffix classes
+/// become a [`ByteMask`] on the bits their values share. Suffix characters that land in the
+/// checksum are kept separately and only checked (by computing the checksum) after the mask
+/// matches.
+///
+/// Classes whose values do not form a bit pattern (e.g. `[ac]`) make matching a necessary
+/// condition; hits must still be confirmed on the encoded string.
+#[derive(Clone, Debug)]
+pub struct Bech32Pattern {
+    payload: ByteMask,
+    /// Allowed checksum characters as (index among the six checksum characters, bit set of
+    /// allowed 5-bit values).
+    checksum: Vec<(usize, u32)>,
+    /// Chance that a uniformly random payload (and its checksum) matches.
+    probability: f64,
+    /// Checksum state after the HRP and witness version, shared by every candidate.
+    head_state: u32,
+    variant: Bech32Variant,
+    satisfiable: bool,
+}
+
+impl Bech32Pattern {
+    /// Compiles prefix and suffix classes matched against the whole address string, so a
+    /// prefix normally starts with [`Bech32Layout::head`].
+    ///
+    /// Addresses are lowercase: uppercase characters only match through case-folded classes.
+    pub fn new(layout: &Bech32Layout, prefix: &[CharClass], suffix: &[CharClass]) -> Self {
+        let mut pattern = Self {
+            payload: ByteMask::new(layout.payload_len),
+            checksum: Vec::new(),
+            probability: 1.0,
+            head_state: head_state(layout),
+            variant: layout.variant,
+            satisfiable: true,
+        };
+
+        let Some(positions) = pattern::anchored(layout.address_len(), prefix, suffix) else {
+            pattern.satisfiable = false;
+            return pattern;
+        };
+        for (position, class) in positions.into_iter().enumerate() {
+            if class != CharClass::ANY && !pattern.require(layout, position, class) {
+                pattern.satisfiable = false;
+            }
+        }
+        pattern
+    }
+
+    /// Whether any payload can match.
+    pub fn is_satisfiable(&self) -> bool {
+        self.satisfiable
+    }
+
+    /// Chance that one candidate matches; zero when the pattern can never match.
+    pub fn probability(&self) -> f64 {
+        if self.satisfiable {
+            self.probability
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether the pattern reaches into the checksum.
+    pub fn needs_checksum(&self) -> bool {
+        !self.checksum.is_empty()
+    }
+
+    /// Checks a payload; the checksum is only computed when the pattern reaches into it.
+    #[inline]
+    pub fn matches(&self, payload: &[u8]) -> bool {
+        self.payload.matches(payload)
+            && (self.checksum.is_empty() || self.checksum_matches(payload))
+    }
+
+    #[cold]
+    fn checksum_matches(&self, payload: &[u8]) -> bool {
+        let mut state = self.head_state;
+        let mut acc = 0u32;
+        let mut bits = 0;
+        for &byte in payload {
+            acc = acc << 8 | byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                state = polymod_step(state, (acc >> bits) as u8 & 31);
+            }
+        }
+        if bits > 0 {
+            state = polymod_step(state, (acc << (5 - bits)) as u8 & 31);
+        }
+        for _ in 0..CHECKSUM_LEN {
+            state = polymod_step(state, 0);
+        }
+        let checksum = state ^ self.variant.constant();
+
+        self.checksum.iter().all(|&(index, allowed)| {
+            let value = (checksum >> (5 * (CHECKSUM_LEN - 1 - index))) & 31;
+            allowed >> value & 1 == 1
+        })
+    }
+
+    /// Requires a character of `class` at `position` of the address string.
+    fn require(&mut self, layout: &Bech32Layout, position: usize, class: CharClass) -> bool {
+        let head = layout.head();
+        if position < head.len() {
+            return class.contains(head.as_bytes()[position] as char);
+        }
+        let values: Vec<u64> = (0..32)
+            .filter(|&value| class.contains(CHARSET[value as usize] as char))
+            .collect();
+
+        let index = position - head.len();
+        let payload_chars = layout.payload_chars();
+        if index >= payload_chars {
+            let allowed = values
+                .iter()
+                .fold(0u32, |allowed, &value| allowed | 1 << value);
+            self.probability *= values.len() as f64 / 32.0;
+            if allowed != u32::MAX {
+                self.checksum.push((index - payload_chars, allowed));
+            }
+            return allowed != 0;
+        }
+
+        // The last character may extend past the payload; its padding bits are zero
+        let offset = index * 5;
+        let width = (layout.payload_len * 8 - offset).min(5);
+        let padding = 5 - width;
+        let values: Vec<u64> = values
+            .into_iter()
+            .filter(|&value| value & ((1 << padding) - 1) == 0)
+            .map(|value| value >> padding)
+            .collect();
+        let Some(&first) = values.first() else {
+            return false;
+        };
+        self.probability *= values.len() as f64 / (1u32 << width) as f64;
+
+        // Keep the bits every allowed value shares
+        (0..width).all(|k| {
+            let bit = first >> (width - 1 - k) & 1;
+            values
+                .iter()
+                .any(|&value| value >> (width - 1 - k) & 1 != bit)
+                || self.payload.require_bits(offset + k, 1, bit)
+        })
+    }
+}
+
+/// Checksum state after the expanded HRP and the optional witness version.
+fn head_state(layout: &Bech32Layout) -> u32 {
+    let hrp = layout.hrp.as_bytes();
+    let mut state = 1;
+    for &c in hrp {
+        state = polymod_step(state, c >> 5);
+    }
+    state = polymod_step(state, 0);
+    for &c in hrp {
+        state = polymod_step(state, c & 31);
+    }
+    if let Some(version) = layout.witness_version {
+        state = polymod_step(state, version);
+    }
+    state
+}
+
+/// One step of the BIP-173 checksum polynomial.
+#[inline]
+fn polymod_step(state: u32, value: u8) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let top = state >> 25;
+    let mut state = (state & 0x1ff_ffff) << 5 ^ value as u32;
+    for (i, generator) in GENERATOR.iter().enumerate() {
+        if (top >> i) & 1 == 1 {
+            state ^= generator;
+        }
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Pattern;
+    use bech32::{Bech32, Hrp};
+
+    fn compile(
+        layout: &Bech32Layout,
+        prefix: &str,
+        suffix: &str,
+        case_sensitive: bool,
+    ) -> Bech32Pattern {
+        let pattern = Pattern::new(prefix, suffix, case_sensitive).unwrap();
+        Bech32Pattern::new(layout, &pattern.prefix_classes(), &pattern.suffix_classes())
+    }
+
+    fn hash_of_scalar_one() -> [u8; 20] {
+        let mut hash = [0u8; 20];
+        hex::decode_to_slice("751e76e8199196d454941c45d1b3a323f1433bd6", &mut hash).unwrap();
+        hash
+    }
+
+    #[test]
+    fn test_prefix_and_checksum_suffix() {
+        // bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 (BIP-173)
+        let layout = Bech32Layout::p2wpkh("bc");
+        assert_eq!(layout.head(), "bc1q");
+        assert_eq!(layout.address_len(), 42);
+        let hash = hash_of_scalar_one();
+
+        let matching = [
+            ("bc1qw508", ""),
+            ("BC1QW508D6", ""),
+            ("bc", "f3t4"),
+            ("", "kv8f3t4"),
+            ("", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+        ];
+        for (prefix, suffix) in matching {
+            let pattern = compile(&layout, prefix, suffix, false);
+            assert!(pattern.matches(&hash), "{prefix}/{suffix}");
+        }
+
+        let failing = [("bc1qw509", ""), ("", "f3t5"), ("", "xv8f3t4")];
+        for (prefix, suffix) in failing {
+            let pattern = compile(&layout, prefix, suffix, false);
+            assert!(pattern.is_satisfiable());
+            assert!(!pattern.matches(&hash), "{prefix}/{suffix}");
+        }
+
+        // Classes keep the bits their values share; checksum classes are sets of values
+        let pattern = compile(&layout, "bc1q[vw]5", "", true);
+        assert!(pattern.matches(&hash));
+        assert_eq!(pattern.probability(), 2.0 / 32.0 / 32.0);
+        assert!(compile(&layout, "", "f3[st]4", true).matches(&hash));
+        assert!(!compile(&layout, "", "f3[su]4", true).matches(&hash));
+    }
+
+    #[test]
+    fn test_unsatisfiable_patterns() {
+        let layout = Bech32Layout::p2wpkh("bc");
+        for (prefix, suffix, case_sensitive) in [
+            ("bc1p", "", false),  // wrong witness version
+            ("bc1qb", "", false), // 'b' is not in the alphabet
+            ("BC1Q", "", true),   // addresses are lowercase
+            ("", &"q".repeat(43), false),
+        ] {
+            let pattern = compile(&layout, prefix, suffix, case_sensitive);
+            assert!(!pattern.is_satisfiable(), "{prefix}/{suffix}");
+        }
+
+        // The last Taproot character carries one payload bit and four zero padding bits
+        let layout = Bech32Layout::p2tr("bc");
+        let last = layout.head().len() + layout.payload_chars() - 1;
+        let suffix_for = |c: char| format!("{c}{}", "q".repeat(layout.address_len() - last - 1));
+        assert!(compile(&layout, "", &suffix_for('s'), true).is_satisfiable());
+        assert!(!compile(&layout, "", &suffix_for('p'), true).is_satisfiable());
+    }
+
+    #[test]
+    fn test_checksum_agrees_with_encoder() {
+        let cosmos = Bech32Layout::cosmos("cosmos");
+        let taproot = Bech32Layout::p2tr("tb");
+        for seed in 0u8..32 {
+            let hash: Vec<u8> = (0..20).map(|i| seed.wrapping_mul(37) ^ i).collect();
+            let address = bech32::encode::<Bech32>(Hrp::parse("cosmos").unwrap(), &hash).unwrap();
+            let suffix = &address[address.len() - 6..];
+            let pattern = compile(&cosmos, &address[..10], suffix, true);
+            assert!(pattern.matches(&hash), "{address}");
+
+            let key: Vec<u8> = (0..32).map(|i| seed.wrapping_mul(91) ^ i).collect();
+            let address = bech32::segwit::encode_v1(Hrp::parse("tb").unwrap(), &key).unwrap();
+            let pattern = compile(&taproot, "", &address[address.len() - 8..], true);
+            assert!(pattern.matches(&key), "{address}");
+        }
+    }
+}
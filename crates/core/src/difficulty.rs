@@ -0,0 +1,577 @@
+use std::time::Duration;
+
+use crate::address_bits::AddressBits;
+use crate::base58::{self, Base58Prefix};
+use crate::base64_pattern::{Base64Pattern, URL_SAFE_ALPHABET};
+use crate::bech32_pattern::{Bech32Layout, Bech32Pattern, CHARSET};
+use crate::format::{ton_tag, AddressFormat};
+use crate::pattern::{self, CharClass, Pattern, Slot};
+use crate::pattern_set::PatternSet;
+use crate::zero_bytes::ZeroBytes;
+
+/// Confidence levels reported alongside the expected number of attempts.
+pub const CONFIDENCE_LEVELS: [f64; 3] = [0.5, 0.9, 0.99];
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 3600.0;
+
+/// Searches whose median time exceeds this are flagged before they start.
+pub const IMPRACTICAL_SEARCH: Duration = Duration::from_secs(SECONDS_PER_YEAR as u64);
+
+/// Keys per second any machine searches faster than. A search that is practical even at
+/// this rate needs no benchmark before it starts.
+pub const SLOWEST_RATE: f64 = 1_000.0;
+
+/// Chance that a single candidate key matches a pattern, and the effort that implies.
+///
+/// Probabilities are computed on the address encoding rather than as `1 / alphabet^len`:
+/// Base58 prefixes are measured as numeric ranges (the first character of a Solana or Legacy
+/// address is far from uniform), fixed heads such as `bc1q`, `UQ` or the Cosmos HRP cost
+/// nothing when matched and make the pattern impossible otherwise, case-insensitive letters
+/// count every variant, and EIP-55 case adds one bit per letter. Character classes count
+/// every value they allow.
+///
+/// Some parts are approximations, and [`is_exact`](Self::is_exact) says whether any was
+/// needed: Base58 suffixes and Base58 prefix characters past the compiled ranges, Bech32 and
+/// TON checksum characters are taken as uniform; `contains`/`exclude` substrings are
+/// estimated as independent windows over the address; several patterns or rules are taken
+/// as independent of each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    probability: f64,
+    exact: bool,
+}
+
+impl Difficulty {
+    pub fn new(format: &AddressFormat, pattern: &Pattern) -> Self {
+        let (prefix, suffix) = (pattern.prefix_classes(), pattern.suffix_classes());
+        let (anchored, exact) = match format {
+            AddressFormat::Ethereum => (ethereum_probability(&prefix, &suffix), true),
+            AddressFormat::BitcoinLegacy { version } => {
+                let mut lo = [0u8; 25];
+                lo[0] = *version;
+                let mut hi = [0xffu8; 25];
+                hi[0] = *version;
+                base58_probability(&prefix, &suffix, &lo, &hi)
+            }
+            AddressFormat::BitcoinSegWit { hrp } => {
+                bech32_probability(&Bech32Layout::p2wpkh(hrp), &prefix, &suffix)
+            }
+            AddressFormat::BitcoinTaproot { hrp } => {
+                bech32_probability(&Bech32Layout::p2tr(hrp), &prefix, &suffix)
+            }
+            AddressFormat::Solana => base58_probability(&prefix, &suffix, &[0; 32], &[0xff; 32]),
+            AddressFormat::Ton => {
+                let head = [ton_tag(pattern), 0x00];
+                let compiled = Base64Pattern::new(&head, 32, 2, &prefix, &suffix);
+                (compiled.probability(), !compiled.needs_checksum())
+            }
+            AddressFormat::Cosmos { hrp } => {
+                let layout = Bech32Layout::cosmos(hrp);
+                let mut full: Vec<CharClass> =
+                    layout.head().bytes().map(CharClass::single).collect();
+                full.extend(prefix);
+                bech32_probability(&layout, &full, &suffix)
+            }
+        };
+
+        // Substrings are treated as independent of the anchored part and of each other
+        let len = format.max_len();
+        let contains: f64 = pattern
+            .contained()
+            .iter()
+            .map(|slots| 1.0 - absence(format, slots, len))
+            .product();
+        let excludes: f64 = pattern
+            .excluded()
+            .iter()
+            .map(|slots| absence(format, slots, len))
+            .product();
+        Self {
+            probability: anchored * contains * excludes,
+            exact: exact && pattern.contained().is_empty() && pattern.excluded().is_empty(),
+        }
+    }
+
+    /// Chance that a candidate matches at least one pattern of `patterns`, treating the
+    /// patterns as independent (overlapping words make this a slight overestimate).
+    pub fn any(format: &AddressFormat, patterns: &PatternSet) -> Self {
+        let miss: f64 = patterns
+            .patterns()
+            .iter()
+            .map(|pattern| (-Self::new(format, pattern).probability).ln_1p())
+            .sum();
+        let exact = match patterns.patterns() {
+            [pattern] => Self::new(format, pattern).exact,
+            _ => false,
+        };
+        Self {
+            probability: -miss.exp_m1(),
+            exact,
+        }
+    }
+
+    /// Chance that an Ethereum address has the zero bytes `rule` asks for.
+    pub fn zero_bytes(rule: &ZeroBytes) -> Self {
+        Self {
+            probability: rule.probability(),
+            exact: true,
+        }
+    }
+
+    /// Chance that an Ethereum address meets a bit-mask or range `rule`.
+    pub fn address_bits(rule: &AddressBits) -> Self {
+        Self {
+            probability: rule.probability(),
+            exact: true,
+        }
+    }
+
+    /// Chance of meeting both requirements, treating them as independent (a prefix that
+    /// overlaps leading zero bytes makes this an underestimate).
+    pub fn and(self, other: Self) -> Self {
+        // Independence is only certain when one side always holds
+        let certain = self.probability >= 1.0 || other.probability >= 1.0;
+        Self {
+            probability: self.probability * other.probability,
+            exact: self.exact && other.exact && certain,
+        }
+    }
+
+    /// Chance that one candidate matches; zero when the pattern can never match.
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    /// Whether the probability is exact rather than an estimate (see [`Difficulty`]).
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /// The odds as shown to users: `1 in 65536`, or `about 1 in 4.2 billion` for estimates.
+    pub fn odds(&self) -> String {
+        let odds = format!("1 in {}", format_count(self.expected_attempts()));
+        if self.exact {
+            odds
+        } else {
+            format!("about {odds}")
+        }
+    }
+
+    pub fn is_impossible(&self) -> bool {
+        self.probability <= 0.0
+    }
+
+    /// Mean number of candidates until the first match.
+    pub fn expected_attempts(&self) -> f64 {
+        1.0 / self.probability
+    }
+
+    /// Number of candidates after which a match has been found with probability `confidence`.
+    pub fn attempts_for(&self, confidence: f64) -> f64 {
+        if self.probability >= 1.0 {
+            return 1.0;
+        }
+        // 1 - (1 - p)^n = confidence, with ln_1p keeping tiny probabilities exact
+        (-confidence).ln_1p() / (-self.probability).ln_1p()
+    }
+
+    /// Time to reach `confidence` at `rate` candidates per second.
+    /// `None` when the rate is unknown or the time does not fit a [`Duration`].
+    pub fn time_for(&self, confidence: f64, rate: f64) -> Option<Duration> {
+        if rate <= 0.0 {
+            return None;
+        }
+        Duration::try_from_secs_f64(self.attempts_for(confidence) / rate).ok()
+    }
+
+    /// Whether a search at `rate` is likely to run longer than [`IMPRACTICAL_SEARCH`].
+    pub fn is_impractical(&self, rate: f64) -> bool {
+        rate > 0.0
+            && self
+                .time_for(0.5, rate)
+                .is_none_or(|time| time >= IMPRACTICAL_SEARCH)
+    }
+}
+
+/// Human-readable count: `950`, `65536`, `3.1 billion`, `4.2e21`.
+pub fn format_count(n: f64) -> String {
+    const UNITS: [(f64, &str); 4] = [
+        (1e15, "quadrillion"),
+        (1e12, "trillion"),
+        (1e9, "billion"),
+        (1e6, "million"),
+    ];
+    if !n.is_finite() {
+        return "∞".to_string();
+    }
+    if n >= 1e18 {
+        return format!("{n:.1e}");
+    }
+    match UNITS.iter().find(|(unit, _)| n >= *unit) {
+        Some((unit, name)) => format!("{:.1} {name}", n / unit),
+        None => format!("{}", n.ceil()),
+    }
+}
+
+/// Human-readable duration: `< 1s`, `42s`, `3m 20s`, `5h 12m`, `3.1 days`, `2.4 years`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0 => "< 1s".to_string(),
+        1..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => {
+            let years = duration.as_secs_f64() / SECONDS_PER_YEAR;
+            if years < 1.0 {
+                format!("{:.1} days", secs as f64 / 86400.0)
+            } else if years < 1e3 {
+                format!("{years:.1} years")
+            } else {
+                format!("{} years", format_count(years))
+            }
+        }
+    }
+}
+
+/// Hex nibbles are uniform; a letter of one case also fixes one EIP-55 hash bit.
+fn ethereum_probability(prefix: &[CharClass], suffix: &[CharClass]) -> f64 {
+    match pattern::anchored(40, prefix, suffix) {
+        Some(positions) => positions.into_iter().map(hex_odds).product(),
+        None => 0.0,
+    }
+}
+
+/// Chance that one checksummed hex character falls in `class`.
+fn hex_odds(class: CharClass) -> f64 {
+    b"0123456789abcdef"
+        .iter()
+        .map(|&c| {
+            if c.is_ascii_digit() {
+                if class.contains(c as char) {
+                    1.0 / 16.0
+                } else {
+                    0.0
+                }
+            } else {
+                let cases = [c, c.to_ascii_uppercase()];
+                let allowed = cases.iter().filter(|&&c| class.contains(c as char)).count();
+                allowed as f64 / 32.0
+            }
+        })
+        .sum()
+}
+
+/// Prefix share of the payload span `[lo, hi]` times the suffix digit odds, and whether
+/// that is exact (no digit had to be taken as uniform).
+fn base58_probability(
+    prefix: &[CharClass],
+    suffix: &[CharClass],
+    lo: &[u8],
+    hi: &[u8],
+) -> (f64, bool) {
+    let ranges = Base58Prefix::new(prefix, lo.len());
+    let digit_odds =
+        |&class: &CharClass| base58::digits(class).len() as f64 / base58::ALPHABET.len() as f64;
+
+    // Characters past the variant cap and every suffix digit are close to uniform
+    let tail: f64 = prefix[ranges.compiled_chars()..]
+        .iter()
+        .map(digit_odds)
+        .product();
+    let exact = prefix.len() <= ranges.compiled_chars() && suffix.is_empty();
+    let suffix: f64 = suffix.iter().map(digit_odds).product();
+    (ranges.density(lo, hi) * tail * suffix, exact)
+}
+
+/// Bech32 odds, exact unless the pattern reaches into the checksum.
+fn bech32_probability(
+    layout: &Bech32Layout,
+    prefix: &[CharClass],
+    suffix: &[CharClass],
+) -> (f64, bool) {
+    let compiled = Bech32Pattern::new(layout, prefix, suffix);
+    (compiled.probability(), !compiled.needs_checksum())
+}
+
+/// Chance that a substring appears nowhere in `len` characters, taking each window and each
+/// character as independent and uniform over the address alphabet.
+fn absence(format: &AddressFormat, slots: &[Slot], len: usize) -> f64 {
+    let Some(windows) = (len + 1).checked_sub(slots.len()) else {
+        return 1.0;
+    };
+    let share = |alphabet: &[u8], class: CharClass| {
+        let allowed = alphabet.iter().filter(|&&c| class.contains(c as char));
+        allowed.count() as f64 / alphabet.len() as f64
+    };
+    let window: f64 = slots
+        .iter()
+        .map(|slot| match format {
+            AddressFormat::Ethereum => hex_odds(slot.class),
+            AddressFormat::BitcoinLegacy { .. } | AddressFormat::Solana => {
+                share(base58::ALPHABET, slot.class)
+            }
+            AddressFormat::Ton => share(URL_SAFE_ALPHABET, slot.class),
+            AddressFormat::BitcoinSegWit { .. }
+            | AddressFormat::BitcoinTaproot { .. }
+            | AddressFormat::Cosmos { .. } => share(CHARSET, slot.class),
+        })
+        .product();
+    (windows as f64 * (-window).ln_1p()).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(format: &AddressFormat, prefix: &str, suffix: &str, cs: bool) -> Difficulty {
+        Difficulty::new(format, &format.compile(prefix, suffix, cs).unwrap())
+    }
+
+    fn difficulty(format: AddressFormat, prefix: &str, suffix: &str, case_sensitive: bool) -> f64 {
+        estimate(&format, prefix, suffix, case_sensitive).probability()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual / expected - 1.0).abs() < 1e-9,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_ethereum_nibbles_and_checksum_case() {
+        let eth = || AddressFormat::Ethereum;
+        assert_close(difficulty(eth(), "0xdead", "", false), 16f64.powi(-4));
+        assert_close(difficulty(eth(), "DEAD", "", false), 16f64.powi(-4));
+        // Each case-sensitive letter also fixes an EIP-55 bit; digits do not
+        assert_close(difficulty(eth(), "DeaD", "", true), 32f64.powi(-4));
+        assert_close(
+            difficulty(eth(), "00", "Ab", true),
+            16f64.powi(-2) * 32f64.powi(-2),
+        );
+        // Overlapping prefix and suffix count each position once
+        let full = "ab".repeat(20);
+        assert_close(
+            difficulty(eth(), &full, &full[30..], false),
+            16f64.powi(-40),
+        );
+
+        assert_eq!(difficulty(eth(), "xyz", "", false), 0.0);
+        assert_eq!(difficulty(eth(), &"a".repeat(41), "", false), 0.0);
+        assert_eq!(difficulty(eth(), &"a".repeat(40), "b", false), 0.0);
+        assert_eq!(difficulty(eth(), &"a".repeat(40), "A", true), 0.0);
+    }
+
+    #[test]
+    fn test_base58_first_character_skew() {
+        // 32-byte keys encode to 43 or 44 characters; a leading '1' needs a zero byte
+        let one = difficulty(AddressFormat::Solana, "1", "", true);
+        assert_close(one, 1.0 / 256.0);
+
+        // Leading digits are far from uniform: '2' is much more common than 'z'
+        let two = difficulty(AddressFormat::Solana, "2", "", true);
+        let z = difficulty(AddressFormat::Solana, "z", "", true);
+        assert!(two > 1.0 / 58.0 && z < 1.0 / 58.0, "{two} {z}");
+        let total: f64 = base58::ALPHABET
+            .iter()
+            .map(|&c| difficulty(AddressFormat::Solana, &(c as char).to_string(), "", true))
+            .sum();
+        assert_close(total, 1.0);
+
+        // Case-insensitive letters add their variants, suffix digits are uniform
+        let sun = difficulty(AddressFormat::Solana, "Sun", "", true);
+        let any_case = difficulty(AddressFormat::Solana, "sun", "", false);
+        assert!(any_case > 2.0 * sun);
+        assert_close(
+            difficulty(AddressFormat::Solana, "", "xyz", true),
+            58f64.powi(-3),
+        );
+        assert_close(
+            difficulty(AddressFormat::Solana, "", "xyz", false),
+            8.0 * 58f64.powi(-3),
+        );
+    }
+
+    #[test]
+    fn test_legacy_version_fixes_leading_characters() {
+        let main = AddressFormat::BitcoinLegacy { version: 0x00 };
+        assert_close(difficulty(main.clone(), "1", "", true), 1.0);
+        assert_eq!(difficulty(main.clone(), "A", "", true), 0.0);
+        // After the '1' the 24-byte remainder has 32 or 33 digits: 'A' leads often, 'z' rarely
+        let one_a = difficulty(main.clone(), "1A", "", true);
+        let one_z = difficulty(main.clone(), "1z", "", true);
+        assert!(one_a > 2.0 / 58.0 && one_z < 0.1 / 58.0, "{one_a} {one_z}");
+
+        // Testnet payloads start with 'm' or 'n'
+        let test = AddressFormat::BitcoinLegacy { version: 0x6f };
+        let m = difficulty(test.clone(), "m", "", true);
+        let n = difficulty(test.clone(), "n", "", true);
+        assert_close(m + n, 1.0);
+        // Legacy is always case-sensitive
+        assert_eq!(difficulty(test, "M", "", false), 0.0);
+    }
+
+    #[test]
+    fn test_fixed_heads() {
+        let segwit = AddressFormat::BitcoinSegWit { hrp: "bc".into() };
+        assert_close(difficulty(segwit.clone(), "bc1q", "", false), 1.0);
+        assert_close(
+            difficulty(segwit.clone(), "bc1qxy", "", false),
+            32f64.powi(-2),
+        );
+        assert_close(difficulty(segwit.clone(), "", "XY", false), 32f64.powi(-2));
+        assert_eq!(difficulty(segwit.clone(), "bc1p", "", false), 0.0);
+        assert_eq!(difficulty(segwit, "bc1qb", "", false), 0.0);
+
+        let taproot = AddressFormat::BitcoinTaproot { hrp: "tb".into() };
+        assert_close(difficulty(taproot, "TB1PQQ", "", false), 32f64.powi(-2));
+
+        let cosmos = AddressFormat::Cosmos { hrp: "osmo".into() };
+        assert_close(difficulty(cosmos.clone(), "wax", "", false), 32f64.powi(-3));
+        assert_eq!(difficulty(cosmos, "WAX", "", true), 0.0);
+
+        // TON: "UQ" is fixed, the next character only has two free bits ('A'-'D')
+        assert_close(difficulty(AddressFormat::Ton, "UQ", "", true), 1.0);
+        assert_close(difficulty(AddressFormat::Ton, "UQA", "", true), 0.25);
+        assert_close(difficulty(AddressFormat::Ton, "EQA", "", true), 0.25);
+        assert_eq!(difficulty(AddressFormat::Ton, "UQE", "", true), 0.0);
+        // Both cases of 'a' are Base64 digits: two values out of 64
+        assert_close(difficulty(AddressFormat::Ton, "", "a", false), 2.0 / 64.0);
+        assert_close(difficulty(AddressFormat::Ton, "", "a", true), 1.0 / 64.0);
+    }
+
+    #[test]
+    fn test_exactness() {
+        let exact = |format: AddressFormat, prefix: &str, suffix: &str| {
+            estimate(&format, prefix, suffix, true).is_exact()
+        };
+        let segwit = AddressFormat::BitcoinSegWit { hrp: "bc".into() };
+        assert!(exact(AddressFormat::Ethereum, "abcd", "ef"));
+        assert!(exact(AddressFormat::Solana, "Sun", ""));
+        assert!(!exact(AddressFormat::Solana, "", "xyz"));
+        assert!(exact(segwit.clone(), "bc1qxy", ""));
+        // The last six characters are the checksum
+        assert!(!exact(segwit, "", "xy"));
+        assert!(exact(AddressFormat::Ton, "UQAB", ""));
+        assert!(!exact(AddressFormat::Ton, "", "ab"));
+
+        let sun = estimate(&AddressFormat::Solana, "Sun", "", true);
+        assert!(sun.odds().starts_with("1 in "));
+        assert!(sun.and(sun).odds().starts_with("about 1 in "));
+    }
+
+    #[test]
+    fn test_classes_and_substrings() {
+        let eth = AddressFormat::Ethereum;
+        assert_close(difficulty(eth.clone(), "[0-7]?", "", false), 0.5);
+        // Six letters, one case each
+        assert_close(difficulty(eth.clone(), "\\u", "", false), 6.0 / 32.0);
+        assert_close(difficulty(eth.clone(), "a[^a]", "", false), 15.0 / 256.0);
+        assert_close(difficulty(eth.clone(), "0{4}", "", false), 16f64.powi(-4));
+
+        // "dead" fits 37 windows of a 40-digit address
+        let contains = Difficulty::new(
+            &eth,
+            &Pattern::new("", "", false)
+                .unwrap()
+                .with_contains("dead")
+                .unwrap(),
+        );
+        let expected = 1.0 - (1.0 - 16f64.powi(-4)).powi(37);
+        assert_close(contains.probability(), expected);
+        let excludes = Difficulty::new(
+            &eth,
+            &Pattern::new("", "", false)
+                .unwrap()
+                .with_exclude("dead")
+                .unwrap(),
+        );
+        assert_close(excludes.probability(), 1.0 - expected);
+
+        let cosmos = AddressFormat::Cosmos { hrp: "osmo".into() };
+        assert_close(difficulty(cosmos, "[qp]", "", true), 2.0 / 32.0);
+        assert_close(
+            difficulty(AddressFormat::Solana, "", "[a-c]", true),
+            3.0 / 58.0,
+        );
+    }
+
+    #[test]
+    fn test_pattern_sets() {
+        let eth = AddressFormat::Ethereum;
+        let words = |prefixes: &[&str]| {
+            PatternSet::new(
+                prefixes
+                    .iter()
+                    .map(|p| eth.compile(p, "", false).unwrap())
+                    .collect(),
+            )
+        };
+        // Disjoint prefixes add up; a duplicate is counted as independent
+        let p = 16f64.powi(-4);
+        assert_close(Difficulty::any(&eth, &words(&["dead"])).probability(), p);
+        let two = Difficulty::any(&eth, &words(&["dead", "beef"])).probability();
+        assert_close(two, 1.0 - (1.0 - p).powi(2));
+        assert!(Difficulty::any(&eth, &words(&[])).is_impossible());
+        assert!(!Difficulty::any(&eth, &words(&["g", "a"])).is_impossible());
+    }
+
+    #[test]
+    fn test_zero_bytes() {
+        // Four leading zero bytes are eight zero nibbles
+        let leading = Difficulty::zero_bytes(&ZeroBytes::Leading(4));
+        let nibbles = difficulty(AddressFormat::Ethereum, "00000000", "", false);
+        assert_close(leading.probability(), nibbles);
+
+        // Combined with a suffix, the odds multiply
+        let suffix = estimate(&AddressFormat::Ethereum, "", "beef", false);
+        assert_close(
+            leading.and(suffix).expected_attempts(),
+            256f64.powi(4) * 16f64.powi(4),
+        );
+    }
+
+    #[test]
+    fn test_address_bits() {
+        // Fourteen hook flag bits cost as much as three and a half hex characters
+        let mut mask = [0u8; 20];
+        mask[18..].copy_from_slice(&[0x3f, 0xff]);
+        let hook = Difficulty::address_bits(&AddressBits::Mask {
+            mask,
+            value: [0; 20],
+        });
+        assert_close(hook.expected_attempts(), 16384.0);
+        let mut half = [0u8; 20];
+        half[0] = 0x80;
+        let below = Difficulty::address_bits(&AddressBits::Below(half));
+        assert_close(hook.and(below).expected_attempts(), 32768.0);
+    }
+
+    #[test]
+    fn test_estimates() {
+        let difficulty = estimate(&AddressFormat::Ethereum, "ffff", "", false);
+        assert_close(difficulty.expected_attempts(), 65536.0);
+        // Half of all searches finish within ln(2)·N attempts
+        let median = difficulty.attempts_for(0.5) / (65536.0 * 2f64.ln());
+        assert!((median - 1.0).abs() < 1e-4, "{median}");
+        assert!(difficulty.attempts_for(0.99) > difficulty.attempts_for(0.9));
+
+        let time = difficulty.time_for(0.5, 1000.0).unwrap();
+        assert_eq!(time.as_secs(), 45);
+        assert!(difficulty.time_for(0.5, 0.0).is_none());
+        assert!(!difficulty.is_impractical(1000.0));
+        assert!(estimate(&AddressFormat::Ethereum, &"f".repeat(20), "", false).is_impractical(1e9));
+        assert!(estimate(&AddressFormat::Ethereum, "g", "", false).is_impossible());
+
+        assert_eq!(format_count(950.0), "950");
+        assert_eq!(format_count(65536.0), "65536");
+        assert_eq!(format_count(4.29e9), "4.3 billion");
+        assert_eq!(format_duration(Duration::from_secs(200)), "3m 20s");
+        assert_eq!(format_duration(Duration::from_secs(3 * 86400)), "3.0 days");
+        assert_eq!(
+            format_duration(Duration::from_secs_f64(2.5 * SECONDS_PER_YEAR)),
+            "2.5 years"
+        );
+    }
+}
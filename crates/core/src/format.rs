@@ -0,0 +1,167 @@
+use crate::pattern::Pattern;
+use crate::validation::{PatternError, PatternPart};
+
+/// Address encoding a prefix/suffix pattern is matched against.
+///
+/// Patterns are given the way each generator takes them: Ethereum patterns exclude `0x`,
+/// Cosmos patterns exclude `hrp1`, and every other pattern starts at the first address
+/// character (so it includes `1`, `bc1q`, `UQ`, …).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// 40 hex characters with EIP-55 mixed-case checksum.
+    Ethereum,
+    /// Base58Check P2PKH with the given version byte (`0x00` mainnet, `0x6f` test networks).
+    BitcoinLegacy { version: u8 },
+    /// Bech32 P2WPKH under a network HRP (`bc`, `tb`, `bcrt`).
+    BitcoinSegWit { hrp: String },
+    /// Bech32m P2TR under a network HRP.
+    BitcoinTaproot { hrp: String },
+    /// Base58 ed25519 public key.
+    Solana,
+    /// User-friendly Base64 address; an `E` prefix selects the bounceable form (see [`ton_tag`]).
+    Ton,
+    /// Bech32 account address under a chain HRP.
+    Cosmos { hrp: String },
+}
+
+/// TON tag byte of bounceable (`EQ…`) user-friendly addresses.
+pub const TON_TAG_BOUNCEABLE: u8 = 0x11;
+/// TON tag byte of non-bounceable (`UQ…`) addresses, the default for wallets.
+pub const TON_TAG_NON_BOUNCEABLE: u8 = 0x51;
+
+impl AddressFormat {
+    /// Parses a prefix and suffix the way this format's generator takes them: Ethereum's `0x`
+    /// is stripped (positions still count it) and Legacy patterns are always case-sensitive.
+    pub fn compile(
+        &self,
+        prefix: &str,
+        suffix: &str,
+        case_sensitive: bool,
+    ) -> Result<Pattern, PatternError> {
+        match self {
+            AddressFormat::Ethereum => match prefix.strip_prefix("0x") {
+                Some(prefix) => Pattern::with_offset(prefix, 2, suffix, case_sensitive),
+                None => Pattern::new(prefix, suffix, case_sensitive),
+            },
+            AddressFormat::BitcoinLegacy { .. } => Pattern::new(prefix, suffix, true),
+            _ => Pattern::new(prefix, suffix, case_sensitive),
+        }
+    }
+
+    /// Compiles whitespace-separated prefixes and suffixes (e.g. a wordlist) into one pattern
+    /// per prefix/suffix pair, in list order, each needing both its prefix and its suffix.
+    /// Several words are accepted on one side only, so the patterns grow with the list rather
+    /// than with a product of two lists. Positions, in errors and in the compiled slots, count
+    /// from the start of each list.
+    pub fn compile_list(
+        &self,
+        prefixes: &str,
+        suffixes: &str,
+        case_sensitive: bool,
+    ) -> Result<Vec<Pattern>, PatternError> {
+        let (prefixes, suffixes) = (words(prefixes), words(suffixes));
+        if prefixes.len() > 1 && suffixes.len() > 1 {
+            return Err(PatternError {
+                part: PatternPart::Suffix,
+                position: Some(suffixes[1].0),
+                reason: "each suffix is paired with every prefix, so give several words to \
+                         either the prefixes or the suffixes, not both"
+                    .to_string(),
+            });
+        }
+        let mut patterns = Vec::with_capacity(prefixes.len() * suffixes.len());
+        for &prefix in &prefixes {
+            for &suffix in &suffixes {
+                patterns.push(self.compile_word(prefix, suffix, case_sensitive)?);
+            }
+        }
+        Ok(patterns)
+    }
+
+    /// Compiles one prefix and suffix word, each given with its offset in its list.
+    fn compile_word(
+        &self,
+        (prefix_start, prefix): (usize, &str),
+        (suffix_start, suffix): (usize, &str),
+        case_sensitive: bool,
+    ) -> Result<Pattern, PatternError> {
+        let pattern = self
+            .compile(prefix, suffix, case_sensitive)
+            .map_err(|mut error| {
+                let start = match error.part {
+                    PatternPart::Prefix => prefix_start,
+                    _ => suffix_start,
+                };
+                error.position = error.position.map(|position| position + start);
+                error
+            })?;
+        Ok(pattern.shifted(prefix_start, suffix_start))
+    }
+}
+
+/// Words of a whitespace-separated list with their character offsets; a single empty word
+/// when there are none.
+fn words(list: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, (byte, c)) in list.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((index, byte)),
+            (true, Some((offset, from))) => {
+                words.push((offset, &list[from..byte]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((offset, from)) = start {
+        words.push((offset, &list[from..]));
+    }
+    if words.is_empty() {
+        words.push((0, ""));
+    }
+    words
+}
+
+/// Tag a TON pattern selects: bounceable when its first character can be `E` but not `U`.
+pub fn ton_tag(pattern: &Pattern) -> u8 {
+    match pattern.prefix().first() {
+        Some(slot) if slot.class.contains('E') && !slot.class.contains('U') => TON_TAG_BOUNCEABLE,
+        _ => TON_TAG_NON_BOUNCEABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_list() {
+        let format = AddressFormat::Ethereum;
+        let patterns = format.compile_list("0xdead  beef", "", false).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns[0].matches("dEAd00") && patterns[1].matches("beef00"));
+        assert_eq!(patterns[1].prefix()[0].position, 8);
+
+        // Every suffix is paired with the prefix, and both must match
+        let patterns = format.compile_list("dead", "beef cafe", false).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns[0].matches("dead00beef") && !patterns[0].matches("0000beef"));
+        assert!(patterns[1].matches("dead00cafe") && !patterns[1].matches("dead00beef"));
+        assert_eq!(patterns[1].suffix()[0].position, 5);
+        // Several words on both sides are refused rather than multiplied
+        let error = format.compile_list("a b", "c d e", false).unwrap_err();
+        assert_eq!((error.part, error.position), (PatternPart::Suffix, Some(2)));
+        // An empty list is one empty pattern
+        assert_eq!(format.compile_list(" ", "", false).unwrap().len(), 1);
+
+        let error = format.compile_list("ab cd[", "", false).unwrap_err();
+        assert_eq!(error.position, Some(5));
+        // '0' is not Base58; the error points into the second suffix
+        let patterns = AddressFormat::Solana
+            .compile_list("", "ab 0", true)
+            .unwrap();
+        let error = AddressFormat::Solana.validate(&patterns[1]).unwrap_err();
+        assert_eq!((error.part, error.position), (PatternPart::Suffix, Some(3)));
+    }
+}
@@ -1,8 +1,36 @@
+pub mod address_bits;
+pub mod base58;
+pub mod base64_pattern;
+pub mod bech32_pattern;
 pub mod crypto;
+pub mod difficulty;
 pub mod error;
+pub mod format;
+pub mod mask;
+pub mod pattern;
+pub mod pattern_set;
+pub mod score;
+pub mod search;
 pub mod traits;
 pub mod types;
+pub mod validation;
+pub mod zero_bytes;
 
+pub use address_bits::AddressBits;
+pub use base58::{Base58Prefix, Base58Suffix};
+pub use base64_pattern::Base64Pattern;
+pub use bech32_pattern::{Bech32Layout, Bech32Pattern, Bech32Variant};
+pub use difficulty::Difficulty;
 pub use error::CoreError;
+pub use format::{ton_tag, AddressFormat, TON_TAG_BOUNCEABLE, TON_TAG_NON_BOUNCEABLE};
+pub use mask::ByteMask;
+pub use pattern::{CharClass, Pattern, Slot};
+pub use pattern_set::PatternSet;
+pub use score::{Leaderboard, Score, Scored, Scorer};
+pub use search::{CancellationToken, MatchLimits, MatchSieve, SearchBudget, SearchOptions};
 pub use traits::VanityGenerator;
-pub use types::{Address, PrivateKey, TonWalletVersion};
+pub use types::{
+    eip55_hash, eip55_is_upper, Address, KeyKind, Match, PrivateKey, TonWalletVersion,
+};
+pub use validation::{PatternError, PatternPart};
+pub use zero_bytes::ZeroBytes;
@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+/// Required bit values over a fixed-length byte string.
+///
+/// Patterns in hex, bech32 or base64 map each character to a group of 4, 5 or 6 bits of the
+/// raw address bytes; compiling them into a mask lets hot loops reject candidates with a few
+/// byte comparisons instead of encoding and comparing strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteMask {
+    mask: Vec<u8>,
+    value: Vec<u8>,
+    /// Bytes holding at least one constrained bit.
+    span: Range<usize>,
+}
+
+impl ByteMask {
+    /// An unconstrained mask over `len` bytes.
+    pub fn new(len: usize) -> Self {
+        Self {
+            mask: vec![0; len],
+            value: vec![0; len],
+            span: 0..0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.mask.len()
+    }
+
+    /// Whether no bit is constrained (every input matches).
+    pub fn is_empty(&self) -> bool {
+        self.span.is_empty()
+    }
+
+    /// Number of constrained bits.
+    pub fn constrained_bits(&self) -> u32 {
+        self.mask.iter().map(|m| m.count_ones()).sum()
+    }
+
+    /// Requires the `width` bits starting at bit `offset` (most significant bit first)
+    /// to equal the low `width` bits of `value`.
+    ///
+    /// Returns `false` if the range does not fit or a bit is already required to differ;
+    /// the mask is left unchanged in that case.
+    pub fn require_bits(&mut self, offset: usize, width: usize, value: u64) -> bool {
+        if width > 64 || offset + width > self.len() * 8 {
+            return false;
+        }
+
+        let bit_at = |i: usize| (value >> (width - 1 - i)) & 1 == 1;
+        let conflict = (0..width).any(|i| {
+            let (byte, bit) = ((offset + i) / 8, 7 - (offset + i) % 8);
+            self.mask[byte] >> bit & 1 == 1 && (self.value[byte] >> bit & 1 == 1) != bit_at(i)
+        });
+        if conflict {
+            return false;
+        }
+
+        for i in 0..width {
+            let (byte, bit) = ((offset + i) / 8, 7 - (offset + i) % 8);
+            self.mask[byte] |= 1 << bit;
+            if bit_at(i) {
+                self.value[byte] |= 1 << bit;
+            }
+        }
+
+        if width > 0 {
+            let first = offset / 8;
+            let last = (offset + width - 1) / 8 + 1;
+            self.span = if self.span.is_empty() {
+                first..last
+            } else {
+                self.span.start.min(first)..self.span.end.max(last)
+            };
+        }
+        true
+    }
+
+    /// Checks `bytes` (which must be at least [`len`](Self::len) long) against the mask.
+    #[inline]
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        let span = self.span.clone();
+        self.mask[span.clone()]
+            .iter()
+            .zip(&self.value[span.clone()])
+            .zip(&bytes[span])
+            .all(|((mask, value), byte)| byte & mask == *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nibble_constraints() {
+        let mut mask = ByteMask::new(4);
+        assert!(mask.is_empty());
+        assert!(mask.matches(&[0xff; 4]));
+
+        // "de" at nibbles 0-1, "f" at nibble 7
+        assert!(mask.require_bits(0, 4, 0xd));
+        assert!(mask.require_bits(4, 4, 0xe));
+        assert!(mask.require_bits(28, 4, 0xf));
+        assert_eq!(mask.constrained_bits(), 12);
+
+        assert!(mask.matches(&[0xde, 0x00, 0x12, 0x3f]));
+        assert!(!mask.matches(&[0xdf, 0x00, 0x12, 0x3f]));
+        assert!(!mask.matches(&[0xde, 0x00, 0x12, 0x3e]));
+    }
+
+    #[test]
+    fn test_unaligned_groups_and_conflicts() {
+        // 5-bit groups straddle byte boundaries
+        let mut mask = ByteMask::new(2);
+        assert!(mask.require_bits(5, 5, 0b10110));
+        assert!(mask.matches(&[0b0000_0101, 0b1000_0000]));
+        assert!(!mask.matches(&[0b0000_0100, 0b1000_0000]));
+
+        // Same bits again are fine, a different value conflicts and leaves the mask untouched
+        assert!(mask.require_bits(5, 3, 0b101));
+        let before = mask.clone();
+        assert!(!mask.require_bits(8, 2, 0b00));
+        assert_eq!(mask, before);
+
+        // Out of range
+        assert!(!mask.require_bits(12, 5, 0));
+    }
+}
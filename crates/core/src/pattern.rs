@@ -0,0 +1,563 @@
+use std::fmt;
+
+use crate::validation::{PatternError, PatternPart};
+
+/// Longest run a `{n}` repeat may expand to (no address is longer).
+const MAX_REPEAT: usize = 64;
+
+/// Set of ASCII characters accepted at one pattern position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CharClass(u128);
+
+impl CharClass {
+    pub const EMPTY: Self = Self(0);
+    pub const ANY: Self = Self(u128::MAX);
+    pub const DIGITS: Self = Self::range(b'0', b'9');
+    pub const UPPERCASE: Self = Self::range(b'A', b'Z');
+    pub const LOWERCASE: Self = Self::range(b'a', b'z');
+
+    /// Characters `lo..=hi`.
+    pub const fn range(lo: u8, hi: u8) -> Self {
+        if lo > hi || hi >= 128 {
+            return Self::EMPTY;
+        }
+        Self((u128::MAX >> (127 - hi)) & (u128::MAX << lo))
+    }
+
+    pub const fn single(c: u8) -> Self {
+        Self::range(c, c)
+    }
+
+    /// Every character of an alphabet.
+    pub fn of(alphabet: &[u8]) -> Self {
+        alphabet
+            .iter()
+            .fold(Self::EMPTY, |class, &c| class.union(Self::single(c)))
+    }
+
+    pub fn contains(self, c: char) -> bool {
+        c.is_ascii() && self.0 >> c as u32 & 1 == 1
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersect(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn complement(self) -> Self {
+        Self(!self.0)
+    }
+
+    /// Adds the other case of every letter.
+    pub fn fold_case(self) -> Self {
+        let upper = self.0 & Self::UPPERCASE.0;
+        let lower = self.0 & Self::LOWERCASE.0;
+        Self(self.0 | upper << 32 | lower >> 32)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The character of a one-character class.
+    pub fn only(self) -> Option<char> {
+        (self.len() == 1).then(|| char::from(self.0.trailing_zeros() as u8))
+    }
+
+    /// Members in ASCII order.
+    pub fn chars(self) -> impl Iterator<Item = char> {
+        (0u8..128)
+            .filter(move |&c| self.0 >> c & 1 == 1)
+            .map(char::from)
+    }
+}
+
+/// One position of a compiled pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slot {
+    pub class: CharClass,
+    /// Character index of the item in the pattern as given (a repeat shares its item's index).
+    pub position: usize,
+    /// The item as written, e.g. `a`, `?`, `\u` or `[0-9]`.
+    pub text: String,
+}
+
+/// A prefix/suffix pattern with optional substring constraints, compiled to per-position
+/// character classes.
+///
+/// Syntax (the same for every part):
+///
+/// | Item       | Matches                                                        |
+/// |------------|----------------------------------------------------------------|
+/// | `a`        | the character (either case when matching case-insensitively)   |
+/// | `?`        | any character                                                  |
+/// | `[a-f0-9]` | one character of the class; `[^…]` negates it                  |
+/// | `\u` `\l`  | an uppercase / lowercase letter, regardless of the case mode   |
+/// | `\d`       | a digit                                                        |
+/// | `\?`       | a literal syntax character (`?`, `[`, `]`, `{`, `}`, `\`)      |
+/// | `x{n}`     | the previous item `n` times                                    |
+///
+/// No address alphabet contains a syntax character, so plain literal patterns keep their
+/// meaning. Case-insensitive classes are folded once here, so matchers compare classes only;
+/// `\u` and `\l` pin the case of a position (e.g. an EIP-55 uppercase letter) in either mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub(crate) prefix: Vec<Slot>,
+    pub(crate) suffix: Vec<Slot>,
+    pub(crate) contains: Vec<Vec<Slot>>,
+    pub(crate) excludes: Vec<Vec<Slot>>,
+    pub(crate) case_sensitive: bool,
+}
+
+impl Pattern {
+    /// Parses an anchored prefix and suffix.
+    pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Result<Self, PatternError> {
+        Self::with_offset(prefix, 0, suffix, case_sensitive)
+    }
+
+    /// Like [`new`](Self::new) for a prefix whose first `offset` characters were stripped
+    /// (Ethereum's `0x`), so positions still refer to the pattern as given.
+    pub(crate) fn with_offset(
+        prefix: &str,
+        offset: usize,
+        suffix: &str,
+        case_sensitive: bool,
+    ) -> Result<Self, PatternError> {
+        Ok(Self {
+            prefix: parse(PatternPart::Prefix, prefix, offset, case_sensitive)?,
+            suffix: parse(PatternPart::Suffix, suffix, 0, case_sensitive)?,
+            contains: Vec::new(),
+            excludes: Vec::new(),
+            case_sensitive,
+        })
+    }
+
+    /// Moves slot positions to where the prefix and suffix start in a longer text.
+    pub(crate) fn shifted(mut self, prefix_start: usize, suffix_start: usize) -> Self {
+        for slot in &mut self.prefix {
+            slot.position += prefix_start;
+        }
+        for slot in &mut self.suffix {
+            slot.position += suffix_start;
+        }
+        self
+    }
+
+    /// Also requires `text` to appear anywhere in the address.
+    pub fn with_contains(mut self, text: &str) -> Result<Self, PatternError> {
+        let slots = parse_substring(PatternPart::Contains, text, self.case_sensitive)?;
+        self.contains.push(slots);
+        Ok(self)
+    }
+
+    /// Rejects addresses in which `text` appears anywhere.
+    pub fn with_exclude(mut self, text: &str) -> Result<Self, PatternError> {
+        let slots = parse_substring(PatternPart::Exclude, text, self.case_sensitive)?;
+        self.excludes.push(slots);
+        Ok(self)
+    }
+
+    pub fn prefix(&self) -> &[Slot] {
+        &self.prefix
+    }
+
+    pub fn suffix(&self) -> &[Slot] {
+        &self.suffix
+    }
+
+    /// Substrings every match must contain.
+    pub fn contained(&self) -> &[Vec<Slot>] {
+        &self.contains
+    }
+
+    /// Substrings no match may contain.
+    pub fn excluded(&self) -> &[Vec<Slot>] {
+        &self.excludes
+    }
+
+    pub fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    pub fn prefix_classes(&self) -> Vec<CharClass> {
+        self.prefix.iter().map(|slot| slot.class).collect()
+    }
+
+    pub fn suffix_classes(&self) -> Vec<CharClass> {
+        self.suffix.iter().map(|slot| slot.class).collect()
+    }
+
+    /// Whether the pattern has substring constraints, which only the string check covers.
+    pub fn has_substrings(&self) -> bool {
+        !self.contains.is_empty() || !self.excludes.is_empty()
+    }
+
+    /// Prefix and suffix every pattern in `patterns` satisfies: the union of their classes at
+    /// each position all of them constrain. Byte prefilters built from it pass every match of
+    /// the set; substrings are left out.
+    pub fn envelope(patterns: &[Pattern]) -> Pattern {
+        let merge = |part: fn(&Pattern) -> &[Slot]| -> Vec<Slot> {
+            let len = patterns.iter().map(|p| part(p).len()).min().unwrap_or(0);
+            (0..len)
+                .map(|i| {
+                    let first = &part(&patterns[0])[i];
+                    let class = patterns
+                        .iter()
+                        .fold(CharClass::EMPTY, |class, p| class.union(part(p)[i].class));
+                    let same = patterns.iter().all(|p| part(p)[i].text == first.text);
+                    Slot {
+                        class,
+                        position: first.position,
+                        text: if same {
+                            first.text.clone()
+                        } else {
+                            "?".to_string()
+                        },
+                    }
+                })
+                .collect()
+        };
+        Pattern {
+            prefix: merge(Pattern::prefix),
+            suffix: merge(Pattern::suffix),
+            case_sensitive: patterns.iter().all(|p| p.case_sensitive),
+            ..Pattern::default()
+        }
+    }
+
+    /// Checks the encoded address (without Ethereum's `0x` or the Cosmos `hrp1`).
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.as_bytes();
+        let fits = |slots: &[Slot], window: &[u8]| {
+            slots
+                .iter()
+                .zip(window)
+                .all(|(slot, &c)| slot.class.contains(char::from(c)))
+        };
+        let occurs = |slots: &Vec<Slot>| {
+            slots.len() <= text.len() && text.windows(slots.len()).any(|w| fits(slots, w))
+        };
+
+        self.prefix.len() <= text.len()
+            && self.suffix.len() <= text.len()
+            && fits(&self.prefix, text)
+            && fits(&self.suffix, &text[text.len() - self.suffix.len()..])
+            && self.contains.iter().all(occurs)
+            && !self.excludes.iter().any(occurs)
+    }
+}
+
+impl fmt::Display for Pattern {
+    /// Anchors as written around `…` (`dead…`, `…beef`, `dead…beef`), then `+substring` for
+    /// each required and `-substring` for each excluded substring.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = |slots: &[Slot]| {
+            slots
+                .iter()
+                .map(|slot| slot.text.as_str())
+                .collect::<String>()
+        };
+        write!(f, "{}…{}", text(&self.prefix), text(&self.suffix))?;
+        for slots in &self.contains {
+            write!(f, " +{}", text(slots))?;
+        }
+        for slots in &self.excludes {
+            write!(f, " -{}", text(slots))?;
+        }
+        Ok(())
+    }
+}
+
+/// Class required at each of `len` positions by an anchored prefix and suffix, intersected
+/// where they overlap; `None` when either is longer than `len`.
+pub(crate) fn anchored(
+    len: usize,
+    prefix: &[CharClass],
+    suffix: &[CharClass],
+) -> Option<Vec<CharClass>> {
+    let suffix_start = len.checked_sub(suffix.len())?;
+    if prefix.len() > len {
+        return None;
+    }
+    let mut positions = vec![CharClass::ANY; len];
+    for (position, &class) in prefix.iter().enumerate() {
+        positions[position] = class;
+    }
+    for (i, &class) in suffix.iter().enumerate() {
+        let position = &mut positions[suffix_start + i];
+        *position = position.intersect(class);
+    }
+    Some(positions)
+}
+
+fn parse_substring(
+    part: PatternPart,
+    text: &str,
+    case_sensitive: bool,
+) -> Result<Vec<Slot>, PatternError> {
+    let slots = parse(part, text, 0, case_sensitive)?;
+    if slots.is_empty() {
+        return Err(PatternError {
+            part,
+            position: None,
+            reason: "is empty".to_string(),
+        });
+    }
+    Ok(slots)
+}
+
+/// Parses one part of a pattern; `offset` is added to every reported position.
+fn parse(
+    part: PatternPart,
+    text: &str,
+    offset: usize,
+    case_sensitive: bool,
+) -> Result<Vec<Slot>, PatternError> {
+    let chars: Vec<char> = text.chars().collect();
+    let error = |start: usize, end: usize, reason: &str| {
+        let item: String = chars[start..end].iter().collect();
+        PatternError {
+            part,
+            position: Some(offset + start),
+            reason: format!("'{item}' at position {} {reason}", offset + start),
+        }
+    };
+    let fold = |class: CharClass| {
+        if case_sensitive {
+            class
+        } else {
+            class.fold_case()
+        }
+    };
+
+    let mut slots: Vec<Slot> = Vec::new();
+    // Whether the last slot came from an item a `{n}` may repeat
+    let mut repeatable = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let class = match chars[i] {
+            '?' => {
+                i += 1;
+                CharClass::ANY
+            }
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|n| i + n)
+                    .ok_or_else(|| error(start, start + 1, "is never closed"))?;
+                let class = parse_class(&chars[i + 1..close], case_sensitive)
+                    .map_err(|reason| error(start, close + 1, reason))?;
+                i = close + 1;
+                if class.is_empty() {
+                    return Err(error(start, i, "matches no character"));
+                }
+                class
+            }
+            '\\' => {
+                let Some(&c) = chars.get(i + 1) else {
+                    return Err(error(start, start + 1, "escapes nothing"));
+                };
+                i += 2;
+                escape(c).unwrap_or_else(|| fold(literal(c)))
+            }
+            '{' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|&c| c == '}')
+                    .map(|n| i + n)
+                    .ok_or_else(|| error(start, start + 1, "is never closed"))?;
+                if !std::mem::take(&mut repeatable) {
+                    return Err(error(start, close + 1, "does not follow an item to repeat"));
+                }
+                let count: usize = chars[i + 1..close]
+                    .iter()
+                    .collect::<String>()
+                    .parse()
+                    .map_err(|_| error(start, close + 1, "is not a repeat count like {4}"))?;
+                if count > MAX_REPEAT {
+                    return Err(error(
+                        start,
+                        close + 1,
+                        &format!("repeats more than {MAX_REPEAT} times"),
+                    ));
+                }
+                // The item itself is already in place once
+                let slot = slots.pop().expect("repeated item");
+                slots.extend(std::iter::repeat_n(slot, count));
+                i = close + 1;
+                continue;
+            }
+            ']' | '}' => return Err(error(start, start + 1, "closes nothing")),
+            c => {
+                i += 1;
+                fold(literal(c))
+            }
+        };
+        repeatable = true;
+        slots.push(Slot {
+            class,
+            position: offset + start,
+            text: chars[start..i].iter().collect(),
+        });
+    }
+    Ok(slots)
+}
+
+/// Members of a bracket class (between `[` and `]`).
+fn parse_class(members: &[char], case_sensitive: bool) -> Result<CharClass, &'static str> {
+    let (negated, members) = match members {
+        ['^', rest @ ..] => (true, rest),
+        _ => (false, members),
+    };
+
+    // Letters are folded when case-insensitive; `\u` and `\l` keep their case
+    let mut folded = CharClass::EMPTY;
+    let mut exact = CharClass::EMPTY;
+    let mut i = 0;
+    while i < members.len() {
+        match members[i..] {
+            ['\\', c, ..] => {
+                match escape(c) {
+                    Some(class) => exact = exact.union(class),
+                    None => folded = folded.union(literal(c)),
+                }
+                i += 2;
+            }
+            ['\\'] => return Err("escapes nothing"),
+            [lo, '-', hi, ..] => {
+                if lo > hi {
+                    return Err("has a reversed range");
+                }
+                let range = if lo.is_ascii() && hi.is_ascii() {
+                    CharClass::range(lo as u8, hi as u8)
+                } else {
+                    CharClass::EMPTY
+                };
+                folded = folded.union(range);
+                i += 3;
+            }
+            [c, ..] => {
+                folded = folded.union(literal(c));
+                i += 1;
+            }
+            [] => unreachable!(),
+        }
+    }
+
+    let folded = if case_sensitive {
+        folded
+    } else {
+        folded.fold_case()
+    };
+    let class = folded.union(exact);
+    Ok(if negated { class.complement() } else { class })
+}
+
+/// Class of a `\x` escape; `None` for an escaped literal.
+fn escape(c: char) -> Option<CharClass> {
+    match c {
+        'u' => Some(CharClass::UPPERCASE),
+        'l' => Some(CharClass::LOWERCASE),
+        'd' => Some(CharClass::DIGITS),
+        _ => None,
+    }
+}
+
+/// A literal character; non-ASCII characters match nothing (no address uses them).
+fn literal(c: char) -> CharClass {
+    if c.is_ascii() {
+        CharClass::single(c as u8)
+    } else {
+        CharClass::EMPTY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classes(text: &str, case_sensitive: bool) -> Vec<String> {
+        Pattern::new(text, "", case_sensitive)
+            .unwrap()
+            .prefix_classes()
+            .into_iter()
+            .map(|class| class.chars().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_syntax() {
+        assert_eq!(classes("aB1", true), ["a", "B", "1"]);
+        assert_eq!(classes("aB1", false), ["Aa", "Bb", "1"]);
+        assert_eq!(classes("[0-3x]", true), ["0123x"]);
+        assert_eq!(classes("[a-c]", false), ["ABCabc"]);
+        assert_eq!(classes("\\u{2}\\d", false).len(), 3);
+        assert_eq!(classes("\\u", false)[0].len(), 26);
+        assert_eq!(classes("[\\l0]", true)[0].len(), 27);
+        assert_eq!(classes("a{3}b{0}", true), ["a", "a", "a"]);
+        assert_eq!(classes("[ab]{2}", true), ["ab", "ab"]);
+        assert_eq!(classes("\\?\\[", true), ["?", "["]);
+        assert_eq!(classes("?", true)[0].len(), 128);
+        assert!(!classes("[^a]", false)[0].contains('A'));
+        assert_eq!(classes("[^\\d]", true)[0].len(), 118);
+
+        // Repeats keep the item's position for error messages
+        let pattern = Pattern::new("x[0-9]{3}", "", true).unwrap();
+        let positions: Vec<usize> = pattern.prefix().iter().map(|s| s.position).collect();
+        assert_eq!(positions, [0, 1, 1, 1]);
+        assert_eq!(pattern.prefix()[2].text, "[0-9]");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        for (text, position, reason) in [
+            ("ab[cd", 2, "'[' at position 2 is never closed"),
+            ("a]", 1, "']' at position 1 closes nothing"),
+            ("{2}", 0, "does not follow an item"),
+            ("a{x}", 1, "'{x}' at position 1 is not a repeat count"),
+            ("a{65}", 1, "repeats more than 64 times"),
+            ("ab\\", 2, "escapes nothing"),
+            ("[z-a]", 0, "reversed range"),
+            ("a[]", 1, "'[]' at position 1 matches no character"),
+        ] {
+            let e = Pattern::new(text, "", true).expect_err(text);
+            assert_eq!(e.position, Some(position), "{text}");
+            assert!(e.reason.contains(reason), "{text}: {e}");
+        }
+        let e = Pattern::new("", "", true)
+            .unwrap()
+            .with_contains("")
+            .unwrap_err();
+        assert_eq!(e.part, PatternPart::Contains);
+    }
+
+    #[test]
+    fn test_matches() {
+        let pattern = Pattern::new("a?[0-9]", "\\uz", false)
+            .unwrap()
+            .with_contains("dead")
+            .unwrap()
+            .with_exclude("0000")
+            .unwrap();
+        assert!(pattern.matches("Ax5_DEAD_Kz"));
+        assert!(!pattern.matches("Ax5_DEAD_kz"), "\\u pins the case");
+        assert!(!pattern.matches("Axx_DEAD_Kz"));
+        assert!(!pattern.matches("Ax5_DEAF_Kz"));
+        assert!(!pattern.matches("Ax5_DEAD_0000_Kz"));
+        assert!(!pattern.matches("Kz"));
+
+        let pattern = Pattern::new("ab", "", true).unwrap();
+        assert!(pattern.matches("abc"));
+        assert!(!pattern.matches("ABc"));
+        assert!(!pattern.has_substrings());
+    }
+}
@@ -0,0 +1,302 @@
+use crate::error::CoreError;
+use crate::format::AddressFormat;
+use crate::pattern::{CharClass, Pattern, Slot};
+use crate::types::Address;
+
+/// Several patterns searched at once, e.g. a list of brand words.
+///
+/// Anchored prefixes (and suffixes, read backwards) are merged into a trie of character
+/// classes, so testing an address follows the few branches its characters select instead of
+/// every pattern in the list. The trie compares case-folded classes; candidates it reaches are
+/// confirmed with [`Pattern::matches`], which also checks case pins and substrings.
+#[derive(Clone, Debug, Default)]
+pub struct PatternSet {
+    patterns: Vec<Pattern>,
+    /// Patterns indexed by their prefix.
+    prefixes: Trie,
+    /// Patterns without a prefix, indexed by their reversed suffix.
+    suffixes: Trie,
+    /// Patterns with neither, tested on every address.
+    floating: Vec<usize>,
+}
+
+impl PatternSet {
+    pub fn new(patterns: Vec<Pattern>) -> Self {
+        let mut set = Self {
+            patterns: Vec::new(),
+            prefixes: Trie::default(),
+            suffixes: Trie::default(),
+            floating: Vec::new(),
+        };
+        for pattern in patterns {
+            set.push(pattern);
+        }
+        set
+    }
+
+    /// Adds a pattern; its index is the current [`len`](Self::len).
+    pub fn push(&mut self, pattern: Pattern) {
+        let index = self.patterns.len();
+        if !pattern.prefix.is_empty() {
+            self.prefixes.insert(pattern.prefix.iter(), index);
+        } else if !pattern.suffix.is_empty() {
+            self.suffixes.insert(pattern.suffix.iter().rev(), index);
+        } else {
+            self.floating.push(index);
+        }
+        self.patterns.push(pattern);
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Pattern> {
+        self.patterns.get(index)
+    }
+
+    /// Anchors every pattern of the set satisfies (see [`Pattern::envelope`]).
+    pub fn envelope(&self) -> Pattern {
+        Pattern::envelope(&self.patterns)
+    }
+
+    /// Rejects an empty set and any pattern `format` can never match.
+    /// Errors of multi-pattern sets name the offending pattern.
+    pub fn validate(&self, format: &AddressFormat) -> Result<(), CoreError> {
+        if self.patterns.is_empty() {
+            return Err(CoreError::InvalidPattern(
+                "no patterns to search for".to_string(),
+            ));
+        }
+        for pattern in &self.patterns {
+            format.validate(pattern).map_err(|error| match self.len() {
+                1 => CoreError::from(error),
+                _ => CoreError::InvalidPattern(format!("'{pattern}': {error}")),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Index of the first pattern `text` matches.
+    pub fn find(&self, text: &str) -> Option<usize> {
+        self.find_with(text, |pattern| pattern.matches(text))
+    }
+
+    /// Index of the first pattern `address` matches, read the way the generators read it:
+    /// Ethereum as checksummed hex without `0x`, Cosmos from after the HRP separator, and the
+    /// other chains whole. Used to re-check results that did not come from a local search.
+    pub fn find_address(&self, address: &Address) -> Option<usize> {
+        match address {
+            Address::Ethereum(bytes) => {
+                let checksummed = address.to_string();
+                self.find_with(&hex::encode(bytes), |pattern| {
+                    pattern.matches(&checksummed[2..])
+                })
+            }
+            Address::Cosmos(text) => {
+                let data = text.rfind('1').map_or(text.as_str(), |at| &text[at + 1..]);
+                self.find(data)
+            }
+            Address::Bitcoin(text) | Address::Solana(text) | Address::Ton(text) => self.find(text),
+        }
+    }
+
+    /// Walks the anchors over `text` ignoring case and returns the lowest candidate index
+    /// `confirm` accepts. Ethereum walks lowercase hex and confirms on the checksummed form.
+    pub fn find_with(
+        &self,
+        text: &str,
+        mut confirm: impl FnMut(&Pattern) -> bool,
+    ) -> Option<usize> {
+        let mut candidates = self.floating.clone();
+        self.prefixes.walk(text.chars(), &mut candidates);
+        self.suffixes.walk(text.chars().rev(), &mut candidates);
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .find(|&index| confirm(&self.patterns[index]))
+    }
+}
+
+impl From<Pattern> for PatternSet {
+    fn from(pattern: Pattern) -> Self {
+        Self::new(vec![pattern])
+    }
+}
+
+/// Trie over case-folded character classes; equal classes share a branch.
+#[derive(Clone, Debug)]
+struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    edges: Vec<(CharClass, usize)>,
+    /// Patterns whose anchor ends here.
+    ends: Vec<usize>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl Trie {
+    fn insert<'a>(&mut self, slots: impl Iterator<Item = &'a Slot>, index: usize) {
+        let mut node = 0;
+        for slot in slots {
+            let class = slot.class.fold_case();
+            let existing = self.nodes[node]
+                .edges
+                .iter()
+                .find(|&&(edge, _)| edge == class)
+                .map(|&(_, child)| child);
+            node = match existing {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].edges.push((class, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].ends.push(index);
+    }
+
+    /// Collects the patterns whose anchor `chars` can satisfy.
+    fn walk(&self, chars: impl Iterator<Item = char>, hits: &mut Vec<usize>) {
+        let mut active = vec![0];
+        let mut next = Vec::new();
+        for c in chars {
+            for &node in &active {
+                for &(class, child) in &self.nodes[node].edges {
+                    if class.contains(c) {
+                        hits.extend(&self.nodes[child].ends);
+                        next.push(child);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            std::mem::swap(&mut active, &mut next);
+            next.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(prefixes: &[&str], suffixes: &[&str], case_sensitive: bool) -> PatternSet {
+        let prefixes = prefixes.iter().map(|p| (*p, ""));
+        let suffixes = suffixes.iter().map(|s| ("", *s));
+        PatternSet::new(
+            prefixes
+                .chain(suffixes)
+                .map(|(p, s)| Pattern::new(p, s, case_sensitive).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_find_tags_pattern() {
+        let words = set(&["dead", "de", "cafe", "?00"], &["beef"], false);
+        assert_eq!(words.find("DEADbeef"), Some(0));
+        assert_eq!(words.find("de11"), Some(1));
+        assert_eq!(words.find("Cafe0"), Some(2));
+        assert_eq!(words.find("x00x"), Some(3));
+        assert_eq!(words.find("12beef"), Some(4));
+        assert_eq!(words.find("d0ad"), None);
+
+        // Shared branches: "de" and "dead" hang off one 'd' edge
+        assert_eq!(words.prefixes.nodes[0].edges.len(), 3);
+    }
+
+    #[test]
+    fn test_case_and_substrings_are_confirmed() {
+        let words = set(&["Ab", "ab"], &[], true);
+        assert_eq!(words.find("Abc"), Some(0));
+        assert_eq!(words.find("abc"), Some(1));
+        assert_eq!(words.find("ABc"), None);
+
+        let mut words = set(&["a"], &[], false);
+        words.push(
+            Pattern::new("", "", false)
+                .unwrap()
+                .with_contains("xyz")
+                .unwrap(),
+        );
+        assert_eq!(words.find("bxyz"), Some(1));
+        assert_eq!(words.find("b"), None);
+
+        // Ethereum walks lowercase hex and confirms on the checksummed text
+        let words = set(&["\\uB"], &[], true);
+        assert_eq!(words.find_with("ab", |p| p.matches("AB")), Some(0));
+        assert_eq!(words.find_with("ab", |p| p.matches("aB")), None);
+    }
+
+    #[test]
+    fn test_find_address() {
+        let words = set(&["\\uB", "ab"], &[], true);
+        let mut bytes = [0u8; 20];
+        bytes[0] = 0xab;
+        // Checksum case decides between the two patterns
+        let address = Address::Ethereum(bytes);
+        let expected = match &address.to_string()[2..4] {
+            "AB" => Some(0),
+            "ab" => Some(1),
+            _ => None,
+        };
+        assert_eq!(words.find_address(&address), expected);
+
+        let words = set(&["qq"], &[], false);
+        let cosmos = |text: &str| Address::Cosmos(text.to_string());
+        assert_eq!(words.find_address(&cosmos("cosmos1qqxyz")), Some(0));
+        assert_eq!(words.find_address(&cosmos("cosmos1xqqyz")), None);
+        assert_eq!(
+            words.find_address(&Address::Solana("qqAb".to_string())),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_envelope() {
+        let words = set(&["dead", "dumb", "d0"], &[], false);
+        let envelope = words.envelope();
+        assert_eq!(envelope.prefix().len(), 2);
+        assert_eq!(envelope.prefix()[0].text, "d");
+        assert!(envelope.prefix()[1].class.contains('0'));
+        assert!(envelope.prefix()[1].class.contains('U'));
+        assert!(!envelope.prefix()[1].class.contains('b'));
+
+        // A pattern without a suffix leaves the suffix open
+        assert!(set(&["a"], &["b"], false).envelope().prefix().is_empty());
+        assert!(set(&["a"], &[], false).envelope().suffix().is_empty());
+    }
+
+    #[test]
+    fn test_validate_names_pattern() {
+        assert!(PatternSet::default()
+            .validate(&AddressFormat::Solana)
+            .is_err());
+        let error = set(&["abc", "0x"], &[], true)
+            .validate(&AddressFormat::Solana)
+            .unwrap_err();
+        assert!(error.to_string().contains("'0x…'"), "{error}");
+    }
+}
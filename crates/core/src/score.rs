@@ -0,0 +1,232 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::types::{Address, PrivateKey};
+
+/// Rates addresses for a best-of search; higher is better.
+///
+/// Any `Fn(&Address) -> u32` closure is a scorer, so callers can plug in their own rule;
+/// [`Score`] covers the common ones.
+pub trait Scorer: Sync {
+    fn score(&self, address: &Address) -> u32;
+}
+
+impl<F> Scorer for F
+where
+    F: Fn(&Address) -> u32 + Sync,
+{
+    fn score(&self, address: &Address) -> u32 {
+        self(address)
+    }
+}
+
+/// Built-in scores, computed on the text patterns are matched against: Ethereum without
+/// `0x`, Cosmos without `hrp1`, other addresses in full.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Score {
+    /// Copies of a character at the start, e.g. zero nibbles of an Ethereum address.
+    Leading(char),
+    /// Length of the longest run of one repeated character anywhere.
+    LongestRun,
+    /// Characters equal to `target` at the same position.
+    Target { text: String, case_sensitive: bool },
+}
+
+impl Scorer for Score {
+    fn score(&self, address: &Address) -> u32 {
+        match self {
+            Score::Leading(c) => {
+                let text = scored_text(address, false);
+                text.chars().take_while(|x| x == c).count() as u32
+            }
+            Score::LongestRun => {
+                let text = scored_text(address, false);
+                let mut longest = 0;
+                let mut run = 0;
+                let mut last = None;
+                for c in text.chars() {
+                    run = if last == Some(c) { run + 1 } else { 1 };
+                    longest = longest.max(run);
+                    last = Some(c);
+                }
+                longest
+            }
+            Score::Target {
+                text: target,
+                case_sensitive,
+            } => {
+                let text = scored_text(address, *case_sensitive);
+                text.chars()
+                    .zip(target.chars())
+                    .filter(|&(a, b)| a == b || (!case_sensitive && a.eq_ignore_ascii_case(&b)))
+                    .count() as u32
+            }
+        }
+    }
+}
+
+impl FromStr for Score {
+    type Err = String;
+
+    /// `leading-zeros`, `leading:<char>`, `run` or `target:<text>` (case-insensitive; build a
+    /// [`Score::Target`] directly to compare case).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "leading-zeros" => Ok(Score::Leading('0')),
+            None if s == "run" => Ok(Score::LongestRun),
+            Some(("leading", c)) if c.chars().count() == 1 => {
+                Ok(Score::Leading(c.chars().next().unwrap_or('0')))
+            }
+            Some(("target", text)) if !text.is_empty() => Ok(Score::Target {
+                text: text.to_string(),
+                case_sensitive: false,
+            }),
+            _ => Err(format!(
+                "unknown score '{s}' (expected leading-zeros, leading:<char>, run or target:<text>)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Leading(c) => write!(f, "leading:{c}"),
+            Score::LongestRun => write!(f, "run"),
+            Score::Target { text, .. } => write!(f, "target:{text}"),
+        }
+    }
+}
+
+/// Address text a score reads; Ethereum is lowercase unless case matters.
+fn scored_text(address: &Address, case_sensitive: bool) -> Cow<'_, str> {
+    match address {
+        Address::Ethereum(_) if case_sensitive => Cow::Owned(address.to_string()[2..].to_string()),
+        Address::Cosmos(text) => match text.rsplit_once('1') {
+            Some((_, data)) => Cow::Borrowed(data),
+            None => Cow::Borrowed(text),
+        },
+        _ => address.to_match_string(),
+    }
+}
+
+/// A key pair kept by a best-of search, with its score.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scored {
+    pub score: u32,
+    pub private_key: PrivateKey,
+    pub address: Address,
+}
+
+/// The best `size` keys offered so far, highest score first; among equal scores the key
+/// offered first ranks higher.
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    size: usize,
+    entries: Vec<Scored>,
+}
+
+impl Leaderboard {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            entries: Vec::with_capacity(size),
+        }
+    }
+
+    /// Lowest score that can still enter the board; searches skip anything below it.
+    pub fn floor(&self) -> u32 {
+        if self.entries.len() < self.size {
+            return 0;
+        }
+        self.entries
+            .last()
+            .map_or(u32::MAX, |last| last.score.saturating_add(1))
+    }
+
+    /// Ranks `entry`, dropping the lowest entry if the board overflows.
+    /// Returns whether it was kept; repeated addresses are not.
+    pub fn offer(&mut self, entry: Scored) -> bool {
+        if entry.score < self.floor() || self.entries.iter().any(|e| e.address == entry.address) {
+            return false;
+        }
+        let rank = self.entries.partition_point(|e| e.score >= entry.score);
+        self.entries.insert(rank, entry);
+        self.entries.truncate(self.size);
+        true
+    }
+
+    pub fn entries(&self) -> &[Scored] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<Scored> {
+        self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(hex_text: &str) -> Address {
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(hex_text, &mut bytes).unwrap();
+        Address::Ethereum(bytes)
+    }
+
+    #[test]
+    fn test_scores() {
+        let address = eth("000a77770000000000000000000000000000beef");
+        assert_eq!(Score::Leading('0').score(&address), 3);
+        assert_eq!(Score::LongestRun.score(&address), 28);
+
+        let target = |text: &str, case_sensitive| Score::Target {
+            text: text.to_string(),
+            case_sensitive,
+        };
+        assert_eq!(target("00FA", false).score(&address), 3);
+        let cosmos = Address::Cosmos("cosmos1qqxyz".to_string());
+        assert_eq!(target("qqx", false).score(&cosmos), 3);
+        assert_eq!(Score::Leading('q').score(&cosmos), 2);
+
+        let custom = |address: &Address| address.to_string().len() as u32;
+        assert_eq!(custom.score(&address), 42);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("leading-zeros".parse(), Ok(Score::Leading('0')));
+        assert_eq!("leading:f".parse(), Ok(Score::Leading('f')));
+        assert_eq!("run".parse(), Ok(Score::LongestRun));
+        let target: Score = "target:dead".parse().unwrap();
+        assert_eq!(target.to_string(), "target:dead");
+        assert!("leading:ab".parse::<Score>().is_err());
+        assert!("best".parse::<Score>().is_err());
+    }
+
+    #[test]
+    fn test_leaderboard_keeps_best() {
+        let entry = |score, byte| Scored {
+            score,
+            private_key: PrivateKey::Ethereum([byte; 32]),
+            address: Address::Ethereum([byte; 20]),
+        };
+        let mut board = Leaderboard::new(3);
+        assert_eq!(board.floor(), 0);
+        for (score, byte) in [(2, 1), (5, 2), (2, 3), (1, 4), (7, 5)] {
+            board.offer(entry(score, byte));
+        }
+        let scores: Vec<u32> = board.entries().iter().map(|e| e.score).collect();
+        assert_eq!(scores, [7, 5, 2]);
+        // The first key with score 2 keeps its place; ties do not enter a full board
+        assert_eq!(board.entries()[2].address, Address::Ethereum([1; 20]));
+        assert_eq!(board.floor(), 3);
+        assert!(!board.offer(entry(2, 6)));
+        // Repeats are ignored
+        assert!(!board.offer(entry(9, 5)));
+        assert!(board.offer(entry(9, 7)));
+        assert_eq!(board.entries()[0].score, 9);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::types::Match;
+
+/// Cooperative cancellation flag shared between a running search and its owner.
+/// Clones refer to the same flag, so any clone can stop every worker.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that every search holding this token stops at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits and shared state for a single search run.
+/// The default value searches until a match is found, without progress reporting.
+#[derive(Clone, Debug, Default)]
+pub struct SearchOptions {
+    /// Shared counter of candidates tried (used for progress display); searches add to it in
+    /// batches.
+    pub progress: Option<Arc<AtomicU64>>,
+    /// Stops the search when cancelled.
+    pub cancel: CancellationToken,
+    /// Stops the search once this instant has passed.
+    pub deadline: Option<Instant>,
+    /// Stops the search after this many candidates.
+    pub max_attempts: Option<u64>,
+}
+
+impl SearchOptions {
+    /// Unbounded search that reports attempts to `progress`.
+    pub fn with_progress(progress: Arc<AtomicU64>) -> Self {
+        Self {
+            progress: Some(progress),
+            ..Self::default()
+        }
+    }
+}
+
+/// Per-run attempt accounting against a [`SearchOptions`].
+/// Shared by all worker threads of one search.
+pub struct SearchBudget<'a> {
+    options: &'a SearchOptions,
+    attempts: AtomicU64,
+}
+
+impl<'a> SearchBudget<'a> {
+    pub fn new(options: &'a SearchOptions) -> Self {
+        Self {
+            options,
+            attempts: AtomicU64::new(0),
+        }
+    }
+
+    /// Records `n` finished attempts.
+    /// Returns `false` once the search should stop (cancelled, deadline passed or cap reached).
+    #[inline]
+    pub fn record(&self, n: u64) -> bool {
+        if let Some(progress) = &self.options.progress {
+            progress.fetch_add(n, Ordering::Relaxed);
+        }
+        let total = self.attempts.fetch_add(n, Ordering::Relaxed) + n;
+        !self.is_stopped(total)
+    }
+
+    /// Checks the limits without recording an attempt.
+    pub fn is_exhausted(&self) -> bool {
+        self.is_stopped(self.attempts())
+    }
+
+    /// Attempts recorded so far by this run.
+    pub fn attempts(&self) -> u64 {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn is_stopped(&self, total: u64) -> bool {
+        self.options.cancel.is_cancelled()
+            || self.options.max_attempts.is_some_and(|max| total >= max)
+            || self
+                .options
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// How many matches a streaming search (see
+/// [`VanityGenerator::search_each`](crate::VanityGenerator::search_each)) should report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchLimits {
+    /// Total number of distinct matches wanted.
+    pub count: usize,
+    /// Most matches reported for any one pattern; `None` for no cap.
+    pub per_pattern: Option<usize>,
+}
+
+impl Default for MatchLimits {
+    /// The first match only.
+    fn default() -> Self {
+        Self {
+            count: 1,
+            per_pattern: None,
+        }
+    }
+}
+
+/// Filters a stream of matches: drops repeated addresses and matches of patterns that have
+/// reached their cap, and tells when the search has everything it asked for.
+#[derive(Clone, Debug)]
+pub struct MatchSieve {
+    limits: MatchLimits,
+    /// Patterns in the search, so a per-pattern cap on all of them ends it early.
+    patterns: usize,
+    seen: HashSet<String>,
+    per_pattern: HashMap<usize, usize>,
+    accepted: usize,
+}
+
+impl MatchSieve {
+    pub fn new(limits: MatchLimits, patterns: usize) -> Self {
+        Self {
+            limits,
+            patterns,
+            seen: HashSet::new(),
+            per_pattern: HashMap::new(),
+            accepted: 0,
+        }
+    }
+
+    /// Whether `found` should be reported; counts it if so.
+    pub fn admit(&mut self, found: &Match) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        let taken = self.per_pattern.get(&found.pattern).copied().unwrap_or(0);
+        if self.limits.per_pattern.is_some_and(|cap| taken >= cap)
+            || !self.seen.insert(found.address.to_string())
+        {
+            return false;
+        }
+        self.per_pattern.insert(found.pattern, taken + 1);
+        self.accepted += 1;
+        true
+    }
+
+    /// Matches reported so far.
+    pub fn accepted(&self) -> usize {
+        self.accepted
+    }
+
+    /// Whether the count is reached or every pattern is at its cap.
+    pub fn is_done(&self) -> bool {
+        let capped = self.limits.per_pattern.is_some_and(|cap| {
+            self.per_pattern.len() == self.patterns
+                && self.per_pattern.values().all(|&taken| taken >= cap)
+        });
+        self.accepted >= self.limits.count || capped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_budget_stops_at_max_attempts() {
+        let options = SearchOptions {
+            max_attempts: Some(3),
+            ..SearchOptions::default()
+        };
+        let budget = SearchBudget::new(&options);
+        assert!(budget.record(1));
+        assert!(budget.record(1));
+        assert!(!budget.record(1));
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.attempts(), 3);
+    }
+
+    #[test]
+    fn test_budget_reports_progress() {
+        let progress = Arc::new(AtomicU64::new(10));
+        let options = SearchOptions::with_progress(progress.clone());
+        let budget = SearchBudget::new(&options);
+        assert!(budget.record(5));
+        assert_eq!(progress.load(Ordering::Relaxed), 15);
+        assert_eq!(budget.attempts(), 5);
+    }
+
+    #[test]
+    fn test_budget_cancel_and_deadline() {
+        let options = SearchOptions::default();
+        let budget = SearchBudget::new(&options);
+        assert!(!budget.is_exhausted());
+        options.cancel.clone().cancel();
+        assert!(budget.is_exhausted());
+
+        let options = SearchOptions {
+            deadline: Some(Instant::now() - Duration::from_millis(1)),
+            ..SearchOptions::default()
+        };
+        assert!(SearchBudget::new(&options).is_exhausted());
+    }
+
+    #[test]
+    fn test_sieve_dedups_and_caps() {
+        use crate::types::{Address, PrivateKey};
+        let found = |address: &str, pattern| Match {
+            private_key: PrivateKey::Ton([0; 32]),
+            address: Address::Ton(address.to_string()),
+            pattern,
+        };
+        let limits = MatchLimits {
+            count: 4,
+            per_pattern: Some(2),
+        };
+        let mut sieve = MatchSieve::new(limits, 3);
+        assert!(sieve.admit(&found("a", 0)));
+        assert!(!sieve.admit(&found("a", 0)), "duplicate address");
+        assert!(sieve.admit(&found("b", 0)));
+        assert!(!sieve.admit(&found("c", 0)), "pattern 0 is at its cap");
+        assert!(sieve.admit(&found("c", 1)));
+        assert!(!sieve.is_done());
+        assert!(sieve.admit(&found("d", 2)));
+        assert!(sieve.is_done());
+        assert!(!sieve.admit(&found("e", 2)));
+        assert_eq!(sieve.accepted(), 4);
+
+        // Every pattern at its cap ends the search before the count
+        let limits = MatchLimits {
+            count: 10,
+            per_pattern: Some(1),
+        };
+        let mut sieve = MatchSieve::new(limits, 2);
+        sieve.admit(&found("a", 0));
+        assert!(!sieve.is_done());
+        sieve.admit(&found("b", 1));
+        assert!(sieve.is_done());
+    }
+}
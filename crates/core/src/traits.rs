@@ -1,4 +1,4 @@
-use crate::{Address, PrivateKey};
+use crate::{Address, CoreError, Leaderboard, Match, PrivateKey, Scorer, SearchOptions};
 
 /// Defines the interface for a vanity address generator.
 /// This allows us to swap implementations (CPU vs GPU, Eth vs BTC) easily.
@@ -6,4 +6,44 @@ pub trait VanityGenerator {
     /// Generates a keypair that matches the given predicate.
     /// This is a blocking operation intended to be run in a separate thread/task.
     fn generate(&self) -> (PrivateKey, Address);
+
+    /// Keeps searching on all workers and hands every match, tagged with the pattern it
+    /// matched, to `on_match` as it is found. Matches arrive one at a time; a
+    /// [`MatchSieve`](crate::MatchSieve) drops repeats and caps matches per pattern.
+    ///
+    /// `on_match` receives every hit and returns `false` to stop; the run also ends when the
+    /// options cancel it or its limits are hit. Patterns are validated when the search starts,
+    /// so a generator built from an invalid pattern returns its error here.
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError>;
+
+    /// Scores every candidate and keeps the best in `board`, ignoring the generator's
+    /// patterns. Runs until the options stop it, so give it a deadline or an attempt cap.
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError>;
+
+    /// Searches for a keypair matching any of the generator's patterns, tagged with the
+    /// pattern it matched.
+    /// Returns `Ok(None)` when the search was cancelled or ran out of time/attempts.
+    fn search_match(&self, options: &SearchOptions) -> Result<Option<Match>, CoreError> {
+        let mut first = None;
+        self.search_each(options, &mut |found| {
+            first = Some(found);
+            false
+        })?;
+        Ok(first)
+    }
+
+    /// Like [`search_match`](Self::search_match), without the pattern tag.
+    fn search(&self, options: &SearchOptions) -> Result<Option<(PrivateKey, Address)>, CoreError> {
+        let found = self.search_match(options)?;
+        Ok(found.map(|found| (found.private_key, found.address)))
+    }
 }
@@ -45,17 +45,13 @@ impl fmt::Display for Address {
         match self {
             Address::Ethereum(bytes) => {
                 // Implement EIP-55 checksum validation
-                use sha3::{Digest, Keccak256};
                 let addr_hex = hex::encode(bytes);
-                let mut hasher = Keccak256::new();
-                hasher.update(addr_hex.as_bytes());
-                let checksum_hash = hasher.finalize();
-                let checksum_hex = hex::encode(checksum_hash);
+                let checksum_hash = eip55_hash(bytes);
 
                 let mut checksummed = String::with_capacity(42);
                 checksummed.push_str("0x");
-                for (c, hash_char) in addr_hex.chars().zip(checksum_hex.chars()) {
-                    if hash_char >= '8' {
+                for (i, c) in addr_hex.chars().enumerate() {
+                    if eip55_is_upper(&checksum_hash, i) {
                         checksummed.push(c.to_ascii_uppercase());
                     } else {
                         checksummed.push(c);
@@ -91,6 +87,37 @@ impl Address {
     }
 }
 
+/// Keccak-256 of the lowercase hex form of an Ethereum address, as used by EIP-55.
+/// Hex character `i` is uppercase in the checksummed address when nibble `i` of the hash is >= 8
+/// (see [`eip55_is_upper`]).
+pub fn eip55_hash(bytes: &[u8; 20]) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    let mut addr_hex = [0u8; 40];
+    hex::encode_to_slice(bytes, &mut addr_hex).expect("40 bytes hold 20 bytes of hex");
+    Keccak256::digest(addr_hex).into()
+}
+
+/// Whether hex character `index` (0..40) of the address is uppercase under EIP-55.
+#[inline]
+pub fn eip55_is_upper(checksum_hash: &[u8; 32], index: usize) -> bool {
+    let byte = checksum_hash[index / 2];
+    let nibble = if index.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0x0f
+    };
+    nibble >= 8
+}
+
+/// A key pair found by a search, tagged with the index of the pattern it matched
+/// (its position in the generator's [`PatternSet`](crate::PatternSet)).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub private_key: PrivateKey,
+    pub address: Address,
+    pub pattern: usize,
+}
+
 /// Represents a 32-byte Private Key.
 /// derived with Debug that redacts the actual key for safety logs,
 /// but Display shows it (assuming user intends to see it).
@@ -101,6 +128,50 @@ pub enum PrivateKey {
     Solana([u8; 64]), // Solana keypairs are 64 bytes (32 private + 32 public)
     Ton([u8; 32]),    // TON private keys are usually stored as 32-byte seeds for import
     Cosmos([u8; 32]),
+    /// Not a key: the 32-byte salt a factory deploys with to reach a mined contract address.
+    /// It is public, but shown like a key because it is what the search was for.
+    Salt([u8; 32]),
+    /// Not a usable key: the secp256k1 scalar `k` (big-endian) a split-key search found for
+    /// someone else's public key. Only its holder can add it to their secret.
+    Secp256k1Partial([u8; 32]),
+    /// Not a usable key: the ed25519 scalar `k` (little-endian) a split-key search found for
+    /// someone else's public key. Only its holder can add it to their secret.
+    Ed25519Partial([u8; 32]),
+    /// Expanded ed25519 secret key: the signing scalar (little-endian) followed by the 32-byte
+    /// nonce prefix. Combined split keys have no seed, so they are carried in this form.
+    Ed25519Expanded([u8; 64]),
+}
+
+/// What a [`PrivateKey`] is to whoever receives it, which decides how it is labelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyKind {
+    /// A key that controls the address on its own.
+    Secret,
+    /// A split-key offset, useless without the requester's secret.
+    Partial,
+    /// A public deployment salt.
+    Salt,
+}
+
+impl KeyKind {
+    /// Label the key is printed under.
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyKind::Secret => "Private Key",
+            KeyKind::Partial => "Partial Key",
+            KeyKind::Salt => "Salt",
+        }
+    }
+}
+
+impl PrivateKey {
+    pub fn kind(&self) -> KeyKind {
+        match self {
+            PrivateKey::Salt(_) => KeyKind::Salt,
+            PrivateKey::Secp256k1Partial(_) | PrivateKey::Ed25519Partial(_) => KeyKind::Partial,
+            _ => KeyKind::Secret,
+        }
+    }
 }
 
 impl fmt::Debug for PrivateKey {
@@ -117,6 +188,10 @@ impl fmt::Display for PrivateKey {
             PrivateKey::Solana(bytes) => write!(f, "{}", bs58::encode(&bytes[..]).into_string()),
             PrivateKey::Ton(bytes) => write!(f, "{}", hex::encode(bytes)),
             PrivateKey::Cosmos(bytes) => write!(f, "{}", hex::encode(bytes)),
+            PrivateKey::Salt(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+            PrivateKey::Secp256k1Partial(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+            PrivateKey::Ed25519Partial(bytes) => write!(f, "{}", hex::encode(bytes)),
+            PrivateKey::Ed25519Expanded(bytes) => write!(f, "{}", hex::encode(bytes)),
         }
     }
 }
@@ -0,0 +1,496 @@
+use std::fmt;
+
+use crate::base58;
+use crate::base64_pattern::URL_SAFE_ALPHABET;
+use crate::bech32_pattern::{self, Bech32Layout};
+use crate::difficulty::Difficulty;
+use crate::error::CoreError;
+use crate::format::AddressFormat;
+use crate::pattern::{CharClass, Pattern, Slot};
+
+const HEX_DIGITS: &[u8; 22] = b"0123456789abcdefABCDEF";
+
+/// Lists of valid characters longer than this are left out of error messages.
+const MAX_LISTED: usize = 8;
+
+/// The part of a pattern an error refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternPart {
+    Prefix,
+    Suffix,
+    /// A substring the address must contain.
+    Contains,
+    /// A substring the address must not contain.
+    Exclude,
+}
+
+impl fmt::Display for PatternPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternPart::Prefix => write!(f, "prefix"),
+            PatternPart::Suffix => write!(f, "suffix"),
+            PatternPart::Contains => write!(f, "contains"),
+            PatternPart::Exclude => write!(f, "exclude"),
+        }
+    }
+}
+
+/// Why a pattern is malformed or can never match, pointing at the offending item if there is one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    pub part: PatternPart,
+    /// Character index in the pattern part as given (counting any `0x`).
+    pub position: Option<usize>,
+    pub reason: String,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.part, self.reason)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<PatternError> for CoreError {
+    fn from(error: PatternError) -> Self {
+        CoreError::InvalidPattern(error.to_string())
+    }
+}
+
+impl AddressFormat {
+    /// Name used in messages, e.g. "Bitcoin Taproot".
+    pub fn name(&self) -> &'static str {
+        match self {
+            AddressFormat::Ethereum => "Ethereum",
+            AddressFormat::BitcoinLegacy { .. } => "Bitcoin Legacy",
+            AddressFormat::BitcoinSegWit { .. } => "Bitcoin SegWit",
+            AddressFormat::BitcoinTaproot { .. } => "Bitcoin Taproot",
+            AddressFormat::Solana => "Solana",
+            AddressFormat::Ton => "TON",
+            AddressFormat::Cosmos { .. } => "Cosmos",
+        }
+    }
+
+    /// Checks that some address of this format can match a compiled pattern.
+    ///
+    /// Length and alphabet errors are reported first. Anything else that makes the pattern
+    /// impossible (fixed heads such as `bc1q` or `UQ`, the leading digits of Base58 payloads,
+    /// prefix and suffix disagreeing where they overlap) is located by growing the pattern one
+    /// position at a time until it can no longer match.
+    pub fn validate(&self, pattern: &Pattern) -> Result<(), PatternError> {
+        let anchored = [
+            (PatternPart::Prefix, pattern.prefix()),
+            (PatternPart::Suffix, pattern.suffix()),
+        ];
+        let contains = pattern
+            .contained()
+            .iter()
+            .map(|slots| (PatternPart::Contains, &slots[..]));
+        let excludes = pattern
+            .excluded()
+            .iter()
+            .map(|slots| (PatternPart::Exclude, &slots[..]));
+        for (part, slots) in anchored.into_iter().chain(contains).chain(excludes) {
+            if slots.len() > self.max_len() {
+                return Err(PatternError {
+                    part,
+                    position: Some(slots[self.max_len()].position),
+                    reason: format!(
+                        "'{}' is {} characters long, but {} addresses have {} {}",
+                        text(slots),
+                        slots.len(),
+                        self.name(),
+                        self.max_len(),
+                        if matches!(self, AddressFormat::Ethereum) {
+                            "hex digits"
+                        } else {
+                            "characters at most"
+                        },
+                    ),
+                });
+            }
+            for (index, slot) in slots.iter().enumerate() {
+                if let Some(reason) = self.check_slot(part, index, slot) {
+                    return Err(PatternError {
+                        part,
+                        position: Some(slot.position),
+                        reason: format!("'{}' at position {} {reason}", slot.text, slot.position),
+                    });
+                }
+            }
+        }
+
+        if Difficulty::new(self, pattern).is_impossible() {
+            return Err(self.locate(pattern));
+        }
+        Ok(())
+    }
+
+    /// Longest prefix or suffix (excluding Ethereum's `0x` and the Cosmos `hrp1`).
+    pub(crate) fn max_len(&self) -> usize {
+        match self {
+            AddressFormat::Ethereum => 40,
+            AddressFormat::BitcoinLegacy { .. } => 34,
+            AddressFormat::BitcoinSegWit { hrp } => Bech32Layout::p2wpkh(hrp).address_len(),
+            AddressFormat::BitcoinTaproot { hrp } => Bech32Layout::p2tr(hrp).address_len(),
+            AddressFormat::Solana => 44,
+            AddressFormat::Ton => 48,
+            AddressFormat::Cosmos { hrp } => {
+                let layout = Bech32Layout::cosmos(hrp);
+                layout.address_len() - layout.head().len()
+            }
+        }
+    }
+
+    /// Alphabet and case rules for one position; `None` when some address character fits.
+    fn check_slot(&self, part: PatternPart, index: usize, slot: &Slot) -> Option<String> {
+        let fits = |alphabet: &[u8]| !slot.class.intersect(CharClass::of(alphabet)).is_empty();
+        // Literals "are not in" an alphabet, classes "match nothing in" it
+        let literal =
+            !slot.text.starts_with('[') && !matches!(&slot.text[..], "?" | r"\u" | r"\l" | r"\d");
+        let outside = |alphabet: &str| {
+            if literal {
+                format!("is not in the {alphabet}")
+            } else {
+                format!("matches nothing in the {alphabet}")
+            }
+        };
+
+        match self {
+            AddressFormat::Ethereum => (!fits(HEX_DIGITS)).then(|| {
+                if literal {
+                    "is not a hexadecimal digit".to_string()
+                } else {
+                    "matches no hexadecimal digit".to_string()
+                }
+            }),
+            AddressFormat::BitcoinLegacy { .. } | AddressFormat::Solana => {
+                (!fits(base58::ALPHABET))
+                    .then(|| outside("Base58 alphabet (which leaves out 0, O, I and l)"))
+            }
+            AddressFormat::Ton => (!fits(URL_SAFE_ALPHABET))
+                .then(|| outside("URL-safe Base64 alphabet (A-Z, a-z, 0-9, - and _)")),
+            AddressFormat::BitcoinSegWit { .. }
+            | AddressFormat::BitcoinTaproot { .. }
+            | AddressFormat::Cosmos { .. } => {
+                // The Bitcoin HRP and separator are checked against the fixed head instead
+                let head = match self {
+                    AddressFormat::BitcoinSegWit { hrp }
+                    | AddressFormat::BitcoinTaproot { hrp } => format!("{hrp}1"),
+                    _ => String::new(),
+                };
+                let in_head = match part {
+                    PatternPart::Prefix => index < head.len(),
+                    PatternPart::Suffix => false,
+                    PatternPart::Contains | PatternPart::Exclude => true,
+                };
+                let mut alphabet = CharClass::of(bech32_pattern::CHARSET);
+                if in_head {
+                    alphabet = alphabet.union(CharClass::of(head.as_bytes()));
+                }
+                if !slot.class.intersect(alphabet).is_empty() {
+                    None
+                } else if !slot.class.fold_case().intersect(alphabet).is_empty() {
+                    Some("is uppercase, but Bech32 addresses are lowercase".to_string())
+                } else {
+                    Some(outside("Bech32 alphabet (which leaves out 1, b, i and o)"))
+                }
+            }
+        }
+    }
+
+    /// Finds the first position that makes an impossible pattern impossible.
+    fn locate(&self, pattern: &Pattern) -> PatternError {
+        let possible = |prefix: &[Slot], suffix: &[Slot]| {
+            let anchored = Pattern {
+                prefix: prefix.to_vec(),
+                suffix: suffix.to_vec(),
+                ..Pattern::default()
+            };
+            !Difficulty::new(self, &anchored).is_impossible()
+        };
+        let case_sensitive = pattern.case_sensitive();
+
+        let prefix = pattern.prefix();
+        for (position, slot) in prefix.iter().enumerate() {
+            if possible(&prefix[..=position], &[]) {
+                continue;
+            }
+            let mut probe = prefix[..=position].to_vec();
+            let valid: Vec<char> = (b'!'..=b'~')
+                .map(char::from)
+                .filter(|&c| {
+                    let class = CharClass::single(c as u8);
+                    probe[position].class = if case_sensitive {
+                        class
+                    } else {
+                        class.fold_case()
+                    };
+                    possible(&probe, &[])
+                })
+                .collect();
+            // Both cases of a letter match the same addresses when case-insensitive: list the
+            // one addresses use (lowercase for Bech32, uppercase otherwise)
+            let lowercase = matches!(
+                self,
+                AddressFormat::BitcoinSegWit { .. }
+                    | AddressFormat::BitcoinTaproot { .. }
+                    | AddressFormat::Cosmos { .. }
+            );
+            let valid: Vec<char> = valid
+                .iter()
+                .copied()
+                .filter(|&c| {
+                    let other = if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    };
+                    case_sensitive
+                        || c == other
+                        || c.is_ascii_lowercase() == lowercase
+                        || !valid.contains(&other)
+                })
+                .collect();
+            let before = text(&prefix[..position]);
+            let place = if before.is_empty() {
+                format!("cannot start a {} address", self.name())
+            } else {
+                format!("cannot follow '{before}' in a {} address", self.name())
+            };
+            return PatternError {
+                part: PatternPart::Prefix,
+                position: Some(slot.position),
+                reason: format!("'{}' {place}{}", slot.text, listing(&valid)),
+            };
+        }
+
+        let suffix = pattern.suffix();
+        for (position, slot) in suffix.iter().enumerate().rev() {
+            if !possible(&[], &suffix[position..]) {
+                return PatternError {
+                    part: PatternPart::Suffix,
+                    position: Some(slot.position),
+                    reason: format!(
+                        "'{}' cannot appear {} characters from the end of a {} address",
+                        slot.text,
+                        suffix.len() - position,
+                        self.name()
+                    ),
+                };
+            }
+        }
+
+        if !possible(prefix, suffix) {
+            return PatternError {
+                part: PatternPart::Suffix,
+                position: None,
+                reason: format!(
+                    "'{}' overlaps prefix '{}' and disagrees with it",
+                    text(suffix),
+                    text(prefix)
+                ),
+            };
+        }
+
+        // Only substrings are left: a contained one that can never appear, or an excluded one
+        // that always does
+        let substring = pattern
+            .contained()
+            .iter()
+            .map(|slots| (PatternPart::Contains, slots))
+            .chain(
+                pattern
+                    .excluded()
+                    .iter()
+                    .map(|slots| (PatternPart::Exclude, slots)),
+            )
+            .find(|&(part, slots)| {
+                let mut single = Pattern::default();
+                match part {
+                    PatternPart::Contains => single.contains.push(slots.clone()),
+                    _ => single.excludes.push(slots.clone()),
+                }
+                Difficulty::new(self, &single).is_impossible()
+            });
+        match substring {
+            Some((PatternPart::Contains, slots)) => PatternError {
+                part: PatternPart::Contains,
+                position: None,
+                reason: format!(
+                    "'{}' cannot appear in a {} address",
+                    text(slots),
+                    self.name()
+                ),
+            },
+            Some((part, slots)) => PatternError {
+                part,
+                position: None,
+                reason: format!("'{}' appears in every {} address", text(slots), self.name()),
+            },
+            // Every part is possible on its own, but the odds of all of them underflow
+            None => PatternError {
+                part: PatternPart::Prefix,
+                position: None,
+                reason: format!(
+                    "the pattern is too unlikely to match a {} address",
+                    self.name()
+                ),
+            },
+        }
+    }
+}
+
+/// The pattern text of a run of slots.
+fn text(slots: &[Slot]) -> String {
+    slots.iter().map(|slot| slot.text.as_str()).collect()
+}
+
+/// " (expected one of: A, B)" for short lists of valid characters.
+fn listing(valid: &[char]) -> String {
+    match valid {
+        [] => String::new(),
+        [c] => format!(" (expected '{c}')"),
+        _ if valid.len() <= MAX_LISTED => {
+            let list: Vec<String> = valid.iter().map(|c| c.to_string()).collect();
+            format!(" (expected one of {})", list.join(", "))
+        }
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(
+        format: AddressFormat,
+        prefix: &str,
+        suffix: &str,
+        case_sensitive: bool,
+    ) -> PatternError {
+        check(&format, prefix, suffix, case_sensitive).expect_err("pattern should be rejected")
+    }
+
+    fn check(
+        format: &AddressFormat,
+        prefix: &str,
+        suffix: &str,
+        case_sensitive: bool,
+    ) -> Result<(), PatternError> {
+        format.validate(&format.compile(prefix, suffix, case_sensitive)?)
+    }
+
+    #[test]
+    fn test_alphabets() {
+        let e = error(AddressFormat::Solana, "Sol", "", true);
+        assert_eq!((e.part, e.position), (PatternPart::Prefix, Some(2)));
+        assert!(e.reason.contains("Base58"), "{e}");
+        // 'l' has a valid uppercase variant
+        assert!(check(&AddressFormat::Solana, "Sol", "", false).is_ok());
+        assert_eq!(
+            error(AddressFormat::Solana, "", "0", false).part,
+            PatternPart::Suffix
+        );
+
+        let cosmos = AddressFormat::Cosmos {
+            hrp: "cosmos".into(),
+        };
+        let e = error(cosmos.clone(), "abc", "", false);
+        assert_eq!(e.position, Some(1));
+        assert!(e.reason.contains("Bech32"), "{e}");
+        let e = error(cosmos, "aC", "", true);
+        assert_eq!(e.position, Some(1));
+        assert!(e.reason.contains("lowercase"), "{e}");
+
+        let e = error(AddressFormat::Ethereum, "0xdeag", "", false);
+        assert_eq!(e.position, Some(5));
+        assert!(check(&AddressFormat::Ethereum, "0xDEAD", "beef", true).is_ok());
+
+        let e = error(AddressFormat::Ton, "UQ!", "", false);
+        assert_eq!(e.position, Some(2));
+    }
+
+    #[test]
+    fn test_fixed_heads() {
+        let segwit = AddressFormat::BitcoinSegWit { hrp: "bc".into() };
+        let e = error(segwit.clone(), "bc1p", "", false);
+        assert_eq!(e.position, Some(3));
+        assert!(e.reason.contains("expected 'q'"), "{e}");
+        let e = error(segwit.clone(), "BC1Q", "", true);
+        assert_eq!(e.position, Some(0));
+        assert!(e.reason.contains("lowercase"), "{e}");
+        assert!(check(&segwit, "BC1QW", "", false).is_ok());
+
+        let e = error(AddressFormat::Ton, "UQE", "", true);
+        assert_eq!(e.position, Some(2));
+        assert!(e.reason.contains("expected one of A, B, C, D"), "{e}");
+        let e = error(AddressFormat::Ton, "XQ", "", true);
+        assert_eq!(e.position, Some(0));
+
+        let legacy = AddressFormat::BitcoinLegacy { version: 0x00 };
+        let e = error(legacy.clone(), "A", "", false);
+        assert!(e.reason.contains("expected '1'"), "{e}");
+        assert!(check(&legacy, "1Love", "", false).is_ok());
+        let testnet = AddressFormat::BitcoinLegacy { version: 0x6f };
+        let e = error(testnet, "1", "", true);
+        assert!(e.reason.contains("expected one of m, n"), "{e}");
+    }
+
+    #[test]
+    fn test_lengths_and_overlaps() {
+        let e = error(AddressFormat::Solana, &"a".repeat(45), "", true);
+        assert_eq!(e.position, Some(44));
+        let e = error(
+            AddressFormat::Ethereum,
+            &format!("0x{}", "a".repeat(41)),
+            "",
+            true,
+        );
+        assert_eq!(e.position, Some(42));
+        assert!(check(
+            &AddressFormat::Ethereum,
+            &format!("0x{}", "a".repeat(40)),
+            "",
+            true
+        )
+        .is_ok());
+
+        let e = error(AddressFormat::Ethereum, &"a".repeat(40), "b", false);
+        assert_eq!((e.part, e.position), (PatternPart::Suffix, None));
+
+        let e: CoreError = error(AddressFormat::Solana, "0", "", true).into();
+        assert!(matches!(e, CoreError::InvalidPattern(message) if message.starts_with("prefix")));
+    }
+
+    #[test]
+    fn test_classes_and_substrings() {
+        let e = error(AddressFormat::Ethereum, "[g-z]", "", false);
+        assert!(e.reason.contains("matches no hexadecimal digit"), "{e}");
+        assert!(check(&AddressFormat::Ethereum, "\\u{2}[0-3]?", "", true).is_ok());
+
+        // A class reports the item as written
+        let segwit = AddressFormat::BitcoinSegWit { hrp: "bc".into() };
+        let e = error(segwit, "bc1[pz]", "", true);
+        assert_eq!(e.position, Some(3));
+        assert!(e.reason.starts_with("'[pz]' cannot follow 'bc1'"), "{e}");
+
+        let pattern = AddressFormat::Solana
+            .compile("", "", true)
+            .unwrap()
+            .with_contains("0x")
+            .unwrap();
+        let e = AddressFormat::Solana.validate(&pattern).unwrap_err();
+        assert_eq!((e.part, e.position), (PatternPart::Contains, Some(0)));
+
+        let pattern = AddressFormat::Ton
+            .compile("", "", true)
+            .unwrap()
+            .with_exclude("?")
+            .unwrap();
+        let e = AddressFormat::Ton.validate(&pattern).unwrap_err();
+        assert_eq!(e.part, PatternPart::Exclude);
+        assert!(e.reason.contains("every TON address"), "{e}");
+    }
+}
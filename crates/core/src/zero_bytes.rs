@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::error::CoreError;
+
+/// Zero bytes required in a raw 20-byte Ethereum address.
+///
+/// Every zero byte in calldata costs 4 gas instead of 16, so contracts and hot wallets with
+/// zero-heavy addresses are cheaper to call. The rule is checked on the `[u8; 20]` of
+/// [`Address::Ethereum`](crate::Address::Ethereum) without encoding any string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZeroBytes {
+    /// At least this many zero bytes at the start.
+    Leading(usize),
+    /// At least this many zero bytes anywhere.
+    Anywhere(usize),
+}
+
+impl ZeroBytes {
+    /// Rejects counts no address can reach (or every address does).
+    pub fn validate(&self) -> Result<(), CoreError> {
+        match self.count() {
+            1..=20 => Ok(()),
+            n => Err(CoreError::InvalidPattern(format!(
+                "{n} zero bytes: an address has 1 to 20"
+            ))),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        match self {
+            ZeroBytes::Leading(n) | ZeroBytes::Anywhere(n) => *n,
+        }
+    }
+
+    #[inline]
+    pub fn matches(&self, address: &[u8; 20]) -> bool {
+        match *self {
+            ZeroBytes::Leading(n) => address
+                .get(..n)
+                .is_some_and(|head| head.iter().all(|&b| b == 0)),
+            ZeroBytes::Anywhere(n) => zero_bytes(address) >= n,
+        }
+    }
+
+    /// Chance that a uniformly random address satisfies the rule.
+    pub fn probability(&self) -> f64 {
+        const P: f64 = 1.0 / 256.0;
+        match *self {
+            ZeroBytes::Leading(n) if n > 20 => 0.0,
+            ZeroBytes::Leading(n) => P.powi(n as i32),
+            // Binomial tail: k of the 20 bytes are zero, for every k >= n
+            ZeroBytes::Anywhere(n) => (n..=20)
+                .map(|k| binomial(20, k) * P.powi(k as i32) * (1.0 - P).powi(20 - k as i32))
+                .sum(),
+        }
+    }
+}
+
+impl fmt::Display for ZeroBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZeroBytes::Leading(n) => write!(f, "{n}+ leading zero bytes"),
+            ZeroBytes::Anywhere(n) => write!(f, "{n}+ zero bytes"),
+        }
+    }
+}
+
+/// Zero bytes at the start of `address`.
+pub fn leading_zero_bytes(address: &[u8]) -> usize {
+    address.iter().take_while(|&&b| b == 0).count()
+}
+
+/// Zero bytes anywhere in `address`.
+pub fn zero_bytes(address: &[u8]) -> usize {
+    address.iter().filter(|&&b| b == 0).count()
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_and_counts() {
+        let mut address = [0x11u8; 20];
+        address[..2].fill(0);
+        address[7] = 0;
+        assert_eq!(leading_zero_bytes(&address), 2);
+        assert_eq!(zero_bytes(&address), 3);
+
+        assert!(ZeroBytes::Leading(2).matches(&address));
+        assert!(!ZeroBytes::Leading(3).matches(&address));
+        assert!(ZeroBytes::Anywhere(3).matches(&address));
+        assert!(!ZeroBytes::Anywhere(4).matches(&address));
+    }
+
+    #[test]
+    fn test_probability() {
+        assert_eq!(ZeroBytes::Leading(2).probability(), 1.0 / 65536.0);
+        // One zero byte among 20: 1 - (255/256)^20
+        let any = ZeroBytes::Anywhere(1).probability();
+        assert!((any - (1.0 - (255.0f64 / 256.0).powi(20))).abs() < 1e-12);
+        // Anywhere is more likely than leading, and exactly as likely for all 20 bytes
+        assert!(ZeroBytes::Anywhere(4).probability() > ZeroBytes::Leading(4).probability());
+        let all = ZeroBytes::Anywhere(20).probability() / ZeroBytes::Leading(20).probability();
+        assert!((all - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ZeroBytes::Leading(4).validate().is_ok());
+        assert!(ZeroBytes::Anywhere(0).validate().is_err());
+        assert!(ZeroBytes::Leading(21).validate().is_err());
+    }
+}
@@ -1,8 +1,12 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
+use vanity_core::difficulty::SLOWEST_RATE;
+use vanity_core::{
+    AddressBits, AddressFormat, Difficulty, KeyKind, PatternError, PatternSet, ZeroBytes,
+};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Chain {
     Ethereum,
     Bitcoin,
@@ -84,12 +88,106 @@ impl Network {
     }
 }
 
+/// Address encoding selected by the chain options, used for difficulty estimates.
+pub fn address_format(
+    chain: Chain,
+    network: Network,
+    btc_type: BitcoinType,
+    hrp: &str,
+) -> AddressFormat {
+    match chain {
+        Chain::Ethereum => AddressFormat::Ethereum,
+        Chain::Bitcoin => {
+            let hrp = match network {
+                Network::Mainnet => "bc",
+                Network::Testnet => "tb",
+                Network::Regtest => "bcrt",
+            }
+            .to_string();
+            match btc_type {
+                BitcoinType::Legacy => AddressFormat::BitcoinLegacy {
+                    version: if network == Network::Mainnet {
+                        0x00
+                    } else {
+                        0x6f
+                    },
+                },
+                BitcoinType::SegWit => AddressFormat::BitcoinSegWit { hrp },
+                BitcoinType::Taproot => AddressFormat::BitcoinTaproot { hrp },
+            }
+        }
+        Chain::Solana => AddressFormat::Solana,
+        Chain::Ton => AddressFormat::Ton,
+        Chain::Cosmos => AddressFormat::Cosmos {
+            hrp: hrp.to_string(),
+        },
+    }
+}
+
 pub enum AppState {
     Config,
     Searching,
     Finished,
 }
 
+/// Messages sent from the search workers to the UI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+    /// A match was found. More may follow until the search completes or stops.
+    Found(FoundKey),
+    /// Every requested match was found.
+    Completed,
+    /// The search ended before finding every requested match (limit reached or error), with a
+    /// reason.
+    Stopped(String),
+}
+
+/// A match reported by the search, as shown and printed by the UI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoundKey {
+    pub address: String,
+    pub private_key: String,
+    /// Whether `private_key` is a usable key, a split-key offset or a salt.
+    pub kind: KeyKind,
+    /// Pattern the key matched, when searching for several.
+    pub pattern: Option<String>,
+}
+
+/// Compiles space-separated prefixes and suffixes for `format` (see
+/// [`AddressFormat::compile_list`]), each pattern with the `contains` and `exclude` substrings.
+pub fn compile_patterns(
+    format: &AddressFormat,
+    prefixes: &str,
+    suffixes: &str,
+    case_sensitive: bool,
+    contains: &[String],
+    excludes: &[String],
+) -> Result<PatternSet, PatternError> {
+    let patterns = format.compile_list(prefixes, suffixes, case_sensitive)?;
+    let patterns = patterns
+        .into_iter()
+        .map(|pattern| {
+            let pattern = contains
+                .iter()
+                .try_fold(pattern, |pattern, text| pattern.with_contains(text))?;
+            excludes
+                .iter()
+                .try_fold(pattern, |pattern, text| pattern.with_exclude(text))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(PatternSet::new(patterns))
+}
+
+/// Leading and total zero bytes of an Ethereum address (`0x…`), as shown with results of a
+/// zero-byte search.
+pub fn zero_byte_summary(address: &str) -> Option<String> {
+    let hex = address.strip_prefix("0x")?;
+    let pairs: Vec<&str> = (0..hex.len() / 2).map(|i| &hex[2 * i..2 * i + 2]).collect();
+    let leading = pairs.iter().take_while(|&&pair| pair == "00").count();
+    let total = pairs.iter().filter(|&&pair| pair == "00").count();
+    Some(format!("{leading} leading, {total} total"))
+}
+
 pub struct App {
     pub state: AppState,
     pub should_quit: bool,
@@ -97,7 +195,11 @@ pub struct App {
     pub attempts: Arc<AtomicU64>,
     pub attempts_last_tick: u64,
     pub rate_per_second: u64,
-    pub found_address: Option<(String, String)>,
+    /// Matches in the order they arrived.
+    pub found: Vec<FoundKey>,
+    /// Matches the search is asked for; more than one lists results as they arrive.
+    pub count: usize,
+    pub stop_reason: Option<String>,
 
     // Search Config Input
     pub chain: Chain,
@@ -105,9 +207,22 @@ pub struct App {
     pub btc_type: BitcoinType,
     pub ton_version: TonVersion,
     pub hrp: String,
+    /// Space-separated prefixes; the search matches any of them.
     pub prefix: String,
+    /// Space-separated suffixes, combined with every prefix.
     pub suffix: String,
     pub case_sensitive: bool,
+    /// Substrings from the command line; not editable in the form.
+    pub contains: Vec<String>,
+    pub excludes: Vec<String>,
+    /// Ethereum zero-byte requirement from the command line.
+    pub zero_bytes: Option<ZeroBytes>,
+    /// Ethereum bit masks and numeric bounds from the command line.
+    pub address_bits: Vec<AddressBits>,
+    /// Key rate benchmarked for a chain before starting a search that might take years.
+    pub key_rate: Option<(Chain, f64)>,
+    /// The form shows the years warning for its current search; Start again runs it anyway.
+    pub years_confirmed: bool,
 
     // Form Focus
     // 0: Chain, 1: Network, 2: Type/Version (BTC/TON), 3: Prefix, 4: Suffix, 5: Case Sensitive, 6: Start Button
@@ -147,7 +262,9 @@ impl App {
             attempts: attempts_counter,
             attempts_last_tick: 0,
             rate_per_second: 0,
-            found_address: None,
+            found: Vec::new(),
+            count: 1,
+            stop_reason: None,
             chain: initial_chain,
             network: initial_network,
             btc_type: initial_btc_type,
@@ -156,10 +273,118 @@ impl App {
             prefix,
             suffix,
             case_sensitive,
+            contains: Vec::new(),
+            excludes: Vec::new(),
+            zero_bytes: None,
+            address_bits: Vec::new(),
+            key_rate: None,
+            years_confirmed: false,
             input_focus_index: 3, // Start focus on Prefix (3)
         }
     }
 
+    /// Chance of matching any configured pattern, with the zero bytes and bit rules on
+    /// Ethereum. `None` while the patterns do not parse.
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        let patterns = self.patterns().ok()?;
+        let difficulty = Difficulty::any(&self.format(), &patterns);
+        let difficulty = match self.active_zero_bytes() {
+            Some(zero_bytes) => difficulty.and(Difficulty::zero_bytes(&zero_bytes)),
+            None => difficulty,
+        };
+        Some(
+            self.active_address_bits()
+                .iter()
+                .fold(difficulty, |difficulty, bits| {
+                    difficulty.and(Difficulty::address_bits(bits))
+                }),
+        )
+    }
+
+    /// The zero-byte requirement, when the selected chain is Ethereum.
+    pub fn active_zero_bytes(&self) -> Option<ZeroBytes> {
+        self.zero_bytes.filter(|_| self.chain == Chain::Ethereum)
+    }
+
+    /// The bit rules, when the selected chain is Ethereum.
+    pub fn active_address_bits(&self) -> &[AddressBits] {
+        match self.chain {
+            Chain::Ethereum => &self.address_bits,
+            _ => &[],
+        }
+    }
+
+    /// Why a configured pattern is malformed or can never match, if one is.
+    pub fn pattern_error(&self) -> Option<PatternError> {
+        let format = self.format();
+        self.patterns()
+            .and_then(|patterns| {
+                patterns
+                    .patterns()
+                    .iter()
+                    .try_for_each(|pattern| format.validate(pattern))
+            })
+            .err()
+    }
+
+    /// The configured patterns compiled for the selected address format.
+    pub fn patterns(&self) -> Result<PatternSet, PatternError> {
+        compile_patterns(
+            &self.format(),
+            &self.prefix,
+            &self.suffix,
+            self.case_sensitive,
+            &self.contains,
+            &self.excludes,
+        )
+    }
+
+    fn format(&self) -> AddressFormat {
+        address_format(self.chain, self.network, self.btc_type, &self.hrp)
+    }
+
+    /// Benchmarked key rate for the selected chain, if any.
+    pub fn config_rate(&self) -> Option<f64> {
+        self.key_rate
+            .filter(|&(chain, _)| chain == self.chain)
+            .map(|(_, rate)| rate)
+    }
+
+    /// Whether the configured search is likely to take years, benchmarking the selected chain
+    /// with `measure_rate` when even [`SLOWEST_RATE`] could not rule that out. Matches the
+    /// benchmark finds are kept with the search's.
+    pub fn takes_years(&mut self, measure_rate: impl FnOnce(&App) -> (f64, Vec<FoundKey>)) -> bool {
+        let Some(difficulty) = self.difficulty() else {
+            return false;
+        };
+        if !difficulty.is_impractical(SLOWEST_RATE) {
+            return false;
+        }
+        let rate = match self.config_rate() {
+            Some(rate) => rate,
+            None => {
+                let (rate, found) = measure_rate(self);
+                self.found.extend(found);
+                self.key_rate = Some((self.chain, rate));
+                rate
+            }
+        };
+        difficulty.is_impractical(rate)
+    }
+
+    pub fn on_search_event(&mut self, event: SearchEvent) {
+        match event {
+            // Benchmark matches may already cover some of the count
+            SearchEvent::Found(found) if self.found.len() < self.count => self.found.push(found),
+            SearchEvent::Found(_) => {}
+            SearchEvent::Completed => self.state = AppState::Finished,
+            SearchEvent::Stopped(reason) => {
+                self.stop_reason = Some(reason);
+                self.state = AppState::Finished;
+            }
+        }
+    }
+
     pub fn on_tick(&mut self) {
         if let AppState::Searching = self.state {
             let current_attempts = self.attempts.load(Ordering::Relaxed);
@@ -212,16 +437,18 @@ impl App {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     pub fn enter_char(&mut self, c: char) {
+        self.years_confirmed = false;
         match self.input_focus_index {
             3 => {
                 // Prefix
-                // Allow any character; validation is handled by the matching logic.
+                // Allow any character; the view highlights characters that cannot match.
                 self.prefix.push(c);
             }
             4 => {
                 // Suffix
-                // Allow any character; validation is handled by the matching logic.
+                // Allow any character; the view highlights characters that cannot match.
                 self.suffix.push(c);
             }
             0 => {
@@ -288,7 +515,9 @@ impl App {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     pub fn delete_char(&mut self) {
+        self.years_confirmed = false;
         match self.input_focus_index {
             3 => {
                 // Prefix
@@ -308,6 +537,7 @@ impl App {
     }
 
     pub fn toggle_selection(&mut self) {
+        self.years_confirmed = false;
         if self.input_focus_index == 0 {
             self.chain = self.chain.next();
         } else if self.input_focus_index == 1 {
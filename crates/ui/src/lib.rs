@@ -1,4 +1,4 @@
-use crate::app::{App, BitcoinType, Chain, TonVersion};
+use crate::app::{zero_byte_summary, App, BitcoinType, Chain, FoundKey, SearchEvent, TonVersion};
 use crate::view::ui;
 use anyhow::Result;
 use crossterm::{
@@ -17,9 +17,9 @@ use std::time::Instant;
 use std::{io, time::Duration};
 
 #[allow(clippy::too_many_arguments)]
-pub fn run_tui<F>(
+pub fn run_tui<F, M>(
     attempts: Arc<AtomicU64>,
-    result_rx: mpsc::Receiver<(String, String)>,
+    result_rx: mpsc::Receiver<SearchEvent>,
     prefix: String,
     suffix: String,
     case_sensitive: bool,
@@ -29,12 +29,19 @@ pub fn run_tui<F>(
     initial_btc_type: BitcoinType,
     initial_ton_version: TonVersion,
     initial_hrp: String,
+    contains: Vec<String>,
+    excludes: Vec<String>,
+    zero_bytes: Option<vanity_core::ZeroBytes>,
+    address_bits: Vec<vanity_core::AddressBits>,
+    count: usize,
     on_search_start: F,
-) -> Result<Option<(String, String, Chain)>>
+    measure_rate: M,
+) -> Result<(Vec<FoundKey>, Chain)>
 where
     F: Fn(String, String, bool, Chain, crate::app::Network, BitcoinType, TonVersion, String)
         + Send
         + 'static,
+    M: Fn(&App) -> (f64, Vec<FoundKey>),
 {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -54,8 +61,18 @@ where
         initial_ton_version,
         initial_hrp,
     );
+    app.contains = contains;
+    app.excludes = excludes;
+    app.zero_bytes = zero_bytes;
+    app.address_bits = address_bits;
+    app.count = count;
 
-    if start_immediately {
+    // A search likely to take years opens in the form, with the warning next to its odds
+    if start_immediately && app.takes_years(&measure_rate) {
+        app.state = crate::app::AppState::Config;
+        app.start_time = None;
+        app.years_confirmed = true;
+    } else if start_immediately {
         on_search_start(
             app.prefix.clone(),
             app.suffix.clone(),
@@ -73,10 +90,11 @@ where
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
 
-        if let crate::app::AppState::Searching = app.state {
-            if let Ok(res) = result_rx.try_recv() {
-                app.found_address = Some(res);
-                app.state = crate::app::AppState::Finished;
+        // Several matches can arrive between frames
+        while let crate::app::AppState::Searching = app.state {
+            match result_rx.try_recv() {
+                Ok(event) => app.on_search_event(event),
+                Err(_) => break,
             }
         }
 
@@ -103,19 +121,27 @@ where
                                 let is_ctrl = key.modifiers.contains(KeyModifiers::CONTROL)
                                     || key.modifiers.contains(KeyModifiers::SUPER);
                                 if app.input_focus_index == 6 || is_ctrl {
-                                    // Start button index is 6
-                                    app.state = crate::app::AppState::Searching;
-                                    app.start_time = Some(Instant::now());
-                                    on_search_start(
-                                        app.prefix.clone(),
-                                        app.suffix.clone(),
-                                        app.case_sensitive,
-                                        app.chain,
-                                        app.network,
-                                        app.btc_type,
-                                        app.ton_version,
-                                        app.hrp.clone(),
-                                    );
+                                    // Patterns that can never match stay in the form, highlighted
+                                    if app.pattern_error().is_none() {
+                                        if !app.years_confirmed && app.takes_years(&measure_rate) {
+                                            // The form now shows the warning; Start again runs it
+                                            app.years_confirmed = true;
+                                        } else {
+                                            // Start button index is 6
+                                            app.state = crate::app::AppState::Searching;
+                                            app.start_time = Some(Instant::now());
+                                            on_search_start(
+                                                app.prefix.clone(),
+                                                app.suffix.clone(),
+                                                app.case_sensitive,
+                                                app.chain,
+                                                app.network,
+                                                app.btc_type,
+                                                app.ton_version,
+                                                app.hrp.clone(),
+                                            );
+                                        }
+                                    }
                                 } else if app.input_focus_index < 3 || app.input_focus_index == 5 {
                                     app.toggle_selection();
                                 } else {
@@ -151,15 +177,33 @@ where
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
-    if let Some(res) = &app.found_address {
+    if !app.found.is_empty() {
         println!("{}", "=".repeat(50));
-        println!("SUCCESS! Result found:");
-        println!("Address: {}", res.0);
-        println!("Private Key: {}", res.1);
+        match app.found.len() {
+            1 => println!("SUCCESS! Result found:"),
+            n => println!("SUCCESS! {} results found:", n),
+        }
+        for (i, found) in app.found.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("Address: {}", found.address);
+            println!("{}: {}", found.kind.label(), found.private_key);
+            if let Some(pattern) = &found.pattern {
+                println!("Pattern: {}", pattern);
+            }
+            if app.active_zero_bytes().is_some() {
+                if let Some(summary) = zero_byte_summary(&found.address) {
+                    println!("Zero bytes: {}", summary);
+                }
+            }
+        }
         println!("{}", "=".repeat(50));
-        Ok(Some((res.0.clone(), res.1.clone(), app.chain)))
-    } else {
+    }
+    if let Some(reason) = &app.stop_reason {
+        println!("Vanity Crypto: {}", reason);
+    } else if app.found.is_empty() {
         println!("Vanity Crypto: aborted.");
-        Ok(None)
     }
+    Ok((app.found, app.chain))
 }
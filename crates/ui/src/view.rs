@@ -1,4 +1,4 @@
-use crate::app::{App, AppState};
+use crate::app::{zero_byte_summary, App, AppState};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
@@ -7,6 +7,8 @@ use ratatui::{
     Frame,
 };
 use std::sync::atomic::Ordering;
+use vanity_core::difficulty::{format_count, format_duration, CONFIDENCE_LEVELS};
+use vanity_core::PatternPart;
 
 pub fn ui(f: &mut Frame, app: &mut App) {
     // Common Layout: Header (3) | Content (Min) | Spacer (1) | Footer/Help (1)
@@ -89,7 +91,8 @@ fn render_config(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
             Constraint::Length(2), // Prefix (5)
             Constraint::Length(2), // Suffix (6)
             Constraint::Length(2), // Options (7)
-            Constraint::Length(2), // Button (8)
+            Constraint::Length(2), // Difficulty (8)
+            Constraint::Length(2), // Button (9)
         ])
         .split(content_area);
 
@@ -207,31 +210,30 @@ fn render_config(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     ];
     f.render_widget(Paragraph::new(Line::from(type_text)), chunks[4]);
 
-    // 3. Prefix
-    let prefix_val = if app.prefix.is_empty() {
-        "..."
-    } else {
-        &app.prefix
+    // Characters that make the pattern impossible are highlighted as they are typed
+    let pattern_error = app.pattern_error();
+    let error_in = |part| {
+        pattern_error
+            .as_ref()
+            .filter(|e| e.part == part)
+            .map(|e| e.position)
     };
-    let prefix_text = vec![
+
+    // 3. Prefix
+    let mut prefix_text = vec![
         Span::styled(cursor_symbol(3), style_for(3)),
         Span::styled("Prefix : ", style_for(3)),
-        Span::raw(prefix_val),
     ];
+    prefix_text.extend(pattern_spans(&app.prefix, error_in(PatternPart::Prefix)));
     let prefix_p = Paragraph::new(Line::from(prefix_text));
     f.render_widget(prefix_p, chunks[5]);
 
     // 4. Suffix
-    let suffix_val = if app.suffix.is_empty() {
-        "..."
-    } else {
-        &app.suffix
-    };
-    let suffix_text = vec![
+    let mut suffix_text = vec![
         Span::styled(cursor_symbol(4), style_for(4)),
         Span::styled("Suffix : ", style_for(4)),
-        Span::raw(suffix_val),
     ];
+    suffix_text.extend(pattern_spans(&app.suffix, error_in(PatternPart::Suffix)));
     let suffix_p = Paragraph::new(Line::from(suffix_text));
     f.render_widget(suffix_p, chunks[6]);
 
@@ -245,6 +247,30 @@ fn render_config(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     let opts_p = Paragraph::new(Line::from(opts_text));
     f.render_widget(opts_p, chunks[7]);
 
+    // Difficulty (read-only), or why the pattern cannot match
+    let odds = match (&pattern_error, app.difficulty()) {
+        (None, Some(difficulty)) => Span::styled(
+            format!("{} attempts", difficulty.odds()),
+            Style::default().fg(Color::Magenta),
+        ),
+        (error, _) => Span::styled(
+            error.as_ref().map(|e| e.to_string()).unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        ),
+    };
+    let mut odds_text = vec![Span::styled("  Odds   : ", inactive_style), odds];
+    let rate = app.config_rate().unwrap_or(0.0);
+    if pattern_error.is_none() && app.difficulty().is_some_and(|d| d.is_impractical(rate)) {
+        odds_text.push(Span::styled(
+            format!(
+                "  Warning: likely to take years at {} keys/s; Start again to search anyway",
+                format_count(rate)
+            ),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(odds_text)), chunks[8]);
+
     // 6. Button
     let btn_style = if app.input_focus_index == 6 {
         Style::default()
@@ -261,7 +287,7 @@ fn render_config(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
         "  [ START ENGINE ] (or Ctrl+Enter)".to_string()
     };
     let btn_p = Paragraph::new(btn_label).style(btn_style);
-    f.render_widget(btn_p, chunks[8]);
+    f.render_widget(btn_p, chunks[9]);
 }
 
 fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
@@ -273,7 +299,9 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
         0
     };
 
-    if let Some(found) = &app.found_address {
+    // A single requested match gets the full result view; several are listed as they arrive
+    let single = app.found.first().filter(|_| app.count <= 1);
+    if let Some(found) = single {
         // Success View - Split into Result and Safety Warning
         let result_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -285,7 +313,7 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
 
         // Explicitly format the private key to be copy-friendly
         // Split long lines
-        let pk_str = &found.1; // Private Key is index 1
+        let pk_str = &found.private_key;
 
         let pk_lines = if pk_str.len() > 60 {
             // Split in half essentially
@@ -298,22 +326,37 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
             vec![Line::from(pk_str.as_str())]
         };
 
-        let mut success_text = vec![
-            Line::from(vec![
-                Span::styled("Address: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    &found.0,
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
-                ), // Address is index 0
-            ]),
+        let mut success_text = vec![Line::from(vec![
+            Span::styled("Address: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                &found.address,
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ])];
+        if let Some(pattern) = &found.pattern {
+            success_text.push(Line::from(vec![
+                Span::styled("Pattern: ", Style::default().fg(Color::Gray)),
+                Span::styled(pattern, Style::default().fg(Color::Magenta)),
+            ]));
+        }
+        if let Some(summary) = app
+            .active_zero_bytes()
+            .and_then(|_| zero_byte_summary(&found.address))
+        {
+            success_text.push(Line::from(vec![
+                Span::styled("Zero bytes: ", Style::default().fg(Color::Gray)),
+                Span::styled(summary, Style::default().fg(Color::Magenta)),
+            ]));
+        }
+        success_text.extend([
             Line::from(""),
             Line::from(Span::styled(
-                "Private Key:",
+                format!("{}:", found.kind.label()),
                 Style::default().fg(Color::Gray),
             )),
-        ];
+        ]);
         success_text.extend(pk_lines);
 
         // Add footer note about finding it in stdout
@@ -358,15 +401,24 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
             );
         f.render_widget(warning_block, result_chunks[1]);
     } else {
-        // Search View
+        // Search View, with the matches found so far below when several are requested
+        let (stats_area, results_area) = if app.count > 1 {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(8), Constraint::Percentage(40)])
+                .split(area);
+            (chunks[0], Some(chunks[1]))
+        } else {
+            (area, None)
+        };
         let stats_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(area);
+            .split(stats_area);
 
         // Config Block
         let cs_text = if app.case_sensitive { "Yes" } else { "No" };
-        let config_text = vec![
+        let mut config_text = vec![
             Line::from(vec![
                 Span::raw("Prefix : "),
                 Span::styled(&app.prefix, Style::default().fg(Color::Magenta)),
@@ -375,18 +427,75 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
                 Span::raw("Suffix : "),
                 Span::styled(&app.suffix, Style::default().fg(Color::Magenta)),
             ]),
-            Line::from(vec![
-                Span::raw("Case   : "),
-                Span::styled(cs_text, Style::default().fg(Color::Magenta)),
-            ]),
-            Line::from(""),
-            Line::from(vec![Span::styled(
-                "Searching...",
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::RAPID_BLINK),
-            )]),
         ];
+        if let Some(count) = app.patterns().ok().map(|p| p.len()).filter(|&n| n > 1) {
+            config_text.push(Line::from(vec![
+                Span::raw("Any of : "),
+                Span::styled(
+                    format!("{count} patterns"),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]));
+        }
+        if let Some(zero_bytes) = app.active_zero_bytes() {
+            config_text.push(Line::from(vec![
+                Span::raw("Zeros  : "),
+                Span::styled(zero_bytes.to_string(), Style::default().fg(Color::Magenta)),
+            ]));
+        }
+        if !app.active_address_bits().is_empty() {
+            let rules: Vec<String> = app
+                .active_address_bits()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            config_text.push(Line::from(vec![
+                Span::raw("Bits   : "),
+                Span::styled(rules.join(", "), Style::default().fg(Color::Magenta)),
+            ]));
+        }
+        for (label, substrings) in [("Contains", &app.contains), ("Excludes", &app.excludes)] {
+            if !substrings.is_empty() {
+                config_text.push(Line::from(vec![
+                    Span::raw(format!("{label}: ")),
+                    Span::styled(substrings.join(", "), Style::default().fg(Color::Magenta)),
+                ]));
+            }
+        }
+        config_text.push(Line::from(vec![
+            Span::raw("Case   : "),
+            Span::styled(cs_text, Style::default().fg(Color::Magenta)),
+        ]));
+        if app.count > 1 {
+            config_text.push(Line::from(vec![
+                Span::raw("Found  : "),
+                Span::styled(
+                    format!("{} of {}", app.found.len(), app.count),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]));
+        }
+        config_text.extend([
+            Line::from(""),
+            match (&app.stop_reason, &app.state) {
+                (Some(reason), _) => Line::from(vec![Span::styled(
+                    reason.as_str(),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+                (None, AppState::Finished) => Line::from(vec![Span::styled(
+                    "Done. Press 'q' to print the keys.",
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+                (None, _) => Line::from(vec![Span::styled(
+                    "Searching...",
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::RAPID_BLINK),
+                )]),
+            },
+        ]);
 
         let config_block = Paragraph::new(config_text)
             .block(
@@ -398,7 +507,7 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
         f.render_widget(config_block, stats_chunks[0]);
 
         // Stats Block
-        let stats_text = vec![
+        let mut stats_text = vec![
             Line::from(vec![
                 Span::raw("Attempts : "),
                 Span::styled(
@@ -420,6 +529,7 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
                 Span::styled(format!("{}s", elapsed), Style::default().fg(Color::Gray)),
             ]),
         ];
+        stats_text.extend(estimate_lines(app, speed as f64));
 
         let stats = Paragraph::new(stats_text)
             .block(
@@ -430,5 +540,125 @@ fn render_searching_body(f: &mut Frame, app: &mut App, area: ratatui::layout::Re
             .alignment(Alignment::Left);
 
         f.render_widget(stats, stats_chunks[1]);
+
+        if let Some(results_area) = results_area {
+            render_results(f, app, results_area);
+        }
+    }
+}
+
+/// Matches found so far, newest last; keys are printed on exit rather than kept on screen.
+fn render_results(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let skipped = app.found.len().saturating_sub(visible);
+    let lines: Vec<Line> = app
+        .found
+        .iter()
+        .enumerate()
+        .skip(skipped)
+        .map(|(i, found)| {
+            let mut spans = vec![
+                Span::styled(format!("{:>3}. ", i + 1), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    found.address.as_str(),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ];
+            if let Some(pattern) = &found.pattern {
+                spans.push(Span::styled(
+                    format!("  {pattern}"),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            if let Some(summary) = app
+                .active_zero_bytes()
+                .and_then(|_| zero_byte_summary(&found.address))
+            {
+                spans.push(Span::styled(
+                    format!("  zero bytes: {summary}"),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let results = Paragraph::new(lines)
+        .style(Style::default().fg(Color::Green))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Matches ({} of {}) ", app.found.len(), app.count))
+                .border_style(Style::default().fg(Color::Green)),
+        );
+    f.render_widget(results, area);
+}
+
+/// Pattern text with the character at fault highlighted.
+/// `Some(None)` marks the whole pattern (e.g. a suffix that contradicts the prefix).
+fn pattern_spans(pattern: &str, error: Option<Option<usize>>) -> Vec<Span<'static>> {
+    if pattern.is_empty() {
+        return vec![Span::raw("...")];
+    }
+    let highlight = Style::default()
+        .fg(Color::White)
+        .bg(Color::Red)
+        .add_modifier(Modifier::BOLD);
+    match error {
+        None => vec![Span::raw(pattern.to_string())],
+        Some(None) => vec![Span::styled(pattern.to_string(), highlight)],
+        Some(Some(position)) => pattern
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if i == position {
+                    Span::styled(c.to_string(), highlight)
+                } else {
+                    Span::raw(c.to_string())
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Expected attempts, time to 50/90/99% at the measured speed, and a warning for searches
+/// that would take years.
+fn estimate_lines(app: &App, speed: f64) -> Vec<Line<'static>> {
+    let Some(difficulty) = app.difficulty().filter(|d| !d.is_impossible()) else {
+        return Vec::new();
+    };
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Expected : "),
+            Span::styled(
+                format!(
+                    "{}{} attempts",
+                    if difficulty.is_exact() { "" } else { "about " },
+                    format_count(difficulty.expected_attempts())
+                ),
+                Style::default().fg(Color::Magenta),
+            ),
+        ]),
+    ];
+    for confidence in CONFIDENCE_LEVELS {
+        let eta = match difficulty.time_for(confidence, speed) {
+            Some(time) => format_duration(time),
+            None if speed > 0.0 => "forever".to_string(),
+            None => "measuring...".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:>3}%     : ", (confidence * 100.0) as u32)),
+            Span::styled(eta, Style::default().fg(Color::Gray)),
+        ]));
+    }
+    if difficulty.is_impractical(speed) {
+        lines.push(Line::from(Span::styled(
+            "Warning: this search is likely to take years at this speed",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
     }
+    lines
 }
@@ -1,6 +1,7 @@
-use criterion::{criterion_group, criterion_main, Criterion};
-use vanity_core::VanityGenerator;
-use vanity_wallet::EthereumVanityGenerator;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use vanity_core::{AddressFormat, VanityGenerator};
+use vanity_wallet::secp::KeyWalker;
+use vanity_wallet::{EthereumMatcher, EthereumVanityGenerator};
 
 fn benchmark_search(c: &mut Criterion) {
     // Benchmark searching for a very simple prefix (1 char)
@@ -42,5 +43,64 @@ fn benchmark_key_generation(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_search, benchmark_key_generation);
+fn benchmark_batched_normalisation(c: &mut Criterion) {
+    // Ethereum address per candidate: one full scalar multiplication per key (per-key path)
+    // versus the incremental walker, whose blocks share one field inversion.
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use rand::rngs::OsRng;
+    use sha3::{Digest, Keccak256};
+
+    let mut group = c.benchmark_group("secp256k1_candidates");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("per_key_scalar_mul", |b| {
+        b.iter(|| {
+            let bytes: [u8; 32] = rand::random();
+            let signing_key = k256::ecdsa::SigningKey::from_bytes(&bytes.into()).unwrap();
+            let encoded_point = signing_key.verifying_key().to_encoded_point(false);
+            std::hint::black_box(Keccak256::digest(&encoded_point.as_bytes()[1..]));
+        })
+    });
+
+    for block_size in [1usize, 16, 64, 256, 1024] {
+        group.bench_with_input(
+            BenchmarkId::new("walker_block", block_size),
+            &block_size,
+            |b, &block_size| {
+                let mut walker = KeyWalker::random(&mut OsRng, block_size);
+                b.iter(|| {
+                    let encoded_point = walker.public_key().to_encoded_point(false);
+                    std::hint::black_box(Keccak256::digest(&encoded_point.as_bytes()[1..]));
+                    walker.advance(&mut OsRng);
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn benchmark_ethereum_matcher(c: &mut Criterion) {
+    // Pattern check only, on random addresses: lowercase vs EIP-55 checksum casing
+    let mut group = c.benchmark_group("ethereum_matcher");
+    for (name, case_sensitive) in [("lowercase", false), ("checksum", true)] {
+        let pattern = AddressFormat::Ethereum.compile("dEaD", "bEeF", case_sensitive);
+        let matcher = EthereumMatcher::new(&pattern.expect("literal pattern"));
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let address: [u8; 20] = rand::random();
+                std::hint::black_box(matcher.matches(&address))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_search,
+    benchmark_key_generation,
+    benchmark_batched_normalisation,
+    benchmark_ethereum_matcher
+);
 criterion_main!(benches);
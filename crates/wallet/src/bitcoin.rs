@@ -1,10 +1,18 @@
-use bitcoin::secp256k1::{All, Secp256k1};
-use bitcoin::{Address, CompressedPublicKey, Network, PublicKey, XOnlyPublicKey};
-use rayon::prelude::*;
-
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use vanity_core::{Address as CoreAddress, PrivateKey, VanityGenerator};
+use bitcoin::constants::{PUBKEY_ADDRESS_PREFIX_MAIN, PUBKEY_ADDRESS_PREFIX_TEST};
+use bitcoin::key::TapTweak;
+use bitcoin::secp256k1::{All, Secp256k1, SecretKey};
+use bitcoin::{Address, CompressedPublicKey, Network, NetworkKind, PublicKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::PublicKey as SplitKey;
+use rand::rngs::OsRng;
+use vanity_core::{
+    Address as CoreAddress, AddressFormat, Base58Prefix, Bech32Layout, Bech32Pattern, CoreError,
+    Leaderboard, Match, Pattern, PatternError, PatternSet, PrivateKey, Scored, Scorer,
+    SearchOptions, VanityGenerator,
+};
+
+use crate::search::{par_score, par_search};
+use crate::secp::{hash160, random_walker, KeyWalker, DEFAULT_BLOCK_SIZE};
 
 #[derive(Clone, Copy, Debug)]
 pub enum BitcoinAddressType {
@@ -14,12 +22,39 @@ pub enum BitcoinAddressType {
 }
 
 pub struct BitcoinVanityGenerator {
-    prefix: String,
-    suffix: String,
-    case_sensitive: bool,
+    /// Compiled patterns, or the syntax error the search reports.
+    patterns: Result<PatternSet, PatternError>,
     network: Network,
     addr_type: BitcoinAddressType,
+    format: AddressFormat,
     secp: Secp256k1<All>,
+    block_size: usize,
+    /// Version byte and payload ranges for Legacy prefixes, checked before building addresses.
+    legacy_prefix: Option<(u8, Base58Prefix)>,
+    /// SegWit/Taproot prefix and suffix as 5-bit constraints on the witness program.
+    bech32_pattern: Option<Bech32Pattern>,
+    /// Requester's public key in split-key mode.
+    split_key: Option<SplitKey>,
+}
+
+/// Address encoding produced for a network and address type.
+fn address_format(network: Network, addr_type: BitcoinAddressType) -> AddressFormat {
+    let hrp = match network {
+        Network::Bitcoin => "bc",
+        Network::Regtest => "bcrt",
+        _ => "tb",
+    }
+    .to_string();
+    match addr_type {
+        BitcoinAddressType::Legacy => AddressFormat::BitcoinLegacy {
+            version: match NetworkKind::from(network) {
+                NetworkKind::Main => PUBKEY_ADDRESS_PREFIX_MAIN,
+                NetworkKind::Test => PUBKEY_ADDRESS_PREFIX_TEST,
+            },
+        },
+        BitcoinAddressType::SegWit => AddressFormat::BitcoinSegWit { hrp },
+        BitcoinAddressType::Taproot => AddressFormat::BitcoinTaproot { hrp },
+    }
 }
 
 impl BitcoinVanityGenerator {
@@ -30,53 +65,87 @@ impl BitcoinVanityGenerator {
         network: Network,
         addr_type: BitcoinAddressType,
     ) -> Self {
-        let case_sensitive = match addr_type {
-            BitcoinAddressType::Legacy => true,
-            _ => case_sensitive,
-        };
+        let format = address_format(network, addr_type);
+        let pattern = format.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from), network, addr_type)
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    /// The pattern should be compiled for the same network and address type.
+    pub fn from_pattern(pattern: Pattern, network: Network, addr_type: BitcoinAddressType) -> Self {
+        Self::from_patterns(PatternSet::from(pattern), network, addr_type)
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(
+        patterns: PatternSet,
+        network: Network,
+        addr_type: BitcoinAddressType,
+    ) -> Self {
+        Self::compiled(Ok(patterns), network, addr_type)
+    }
 
-        // Preprocess prefix and suffix based on case sensitivity
-        let (prefix_processed, suffix_processed) = if case_sensitive {
-            (prefix.to_string(), suffix.to_string())
-        } else {
-            (prefix.to_lowercase(), suffix.to_lowercase())
+    fn compiled(
+        patterns: Result<PatternSet, PatternError>,
+        network: Network,
+        addr_type: BitcoinAddressType,
+    ) -> Self {
+        // Byte filters use the anchors every pattern shares
+        let envelope = patterns
+            .as_ref()
+            .map(PatternSet::envelope)
+            .unwrap_or_default();
+        let prefix = envelope.prefix_classes();
+        let suffix = envelope.suffix_classes();
+
+        let format = address_format(network, addr_type);
+        let legacy_prefix = match format {
+            AddressFormat::BitcoinLegacy { version } if !prefix.is_empty() => {
+                Some((version, Base58Prefix::new(&prefix, 25)))
+            }
+            _ => None,
         };
+        let bech32_layout = match &format {
+            AddressFormat::BitcoinSegWit { hrp } => Some(Bech32Layout::p2wpkh(hrp)),
+            AddressFormat::BitcoinTaproot { hrp } => Some(Bech32Layout::p2tr(hrp)),
+            _ => None,
+        };
+        let bech32_pattern =
+            bech32_layout.map(|layout| Bech32Pattern::new(&layout, &prefix, &suffix));
 
         Self {
-            prefix: prefix_processed,
-            suffix: suffix_processed,
-            case_sensitive,
+            patterns,
             network,
             addr_type,
+            format,
             secp: Secp256k1::new(),
+            block_size: DEFAULT_BLOCK_SIZE,
+            legacy_prefix,
+            bech32_pattern,
+            split_key: None,
         }
     }
 
-    #[inline(always)]
-    fn matches(&self, addr_str: &str) -> bool {
-        // Prefix and suffix are already preprocessed in new()
-        if self.case_sensitive {
-            let p_match = self.prefix.is_empty() || addr_str.starts_with(&self.prefix);
-            if !p_match {
-                return false;
-            }
-            self.suffix.is_empty() || addr_str.ends_with(&self.suffix)
-        } else {
-            let lower_addr = addr_str.to_lowercase();
-            let p_match = self.prefix.is_empty() || lower_addr.starts_with(&self.prefix);
-            if !p_match {
-                return false;
-            }
-            self.suffix.is_empty() || lower_addr.ends_with(&self.suffix)
-        }
+    /// Sets how many candidates share one field inversion during [`search`](Self::search).
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Split-key mode: matches the address of `public_key + k·G` and reports only the partial
+    /// key `k` (as WIF), which is useless without the requester's secret (see
+    /// [`combine_split_key`](crate::combine_split_key)).
+    pub fn with_split_key(mut self, public_key: SplitKey) -> Self {
+        self.split_key = Some(public_key);
+        self
     }
 
-    // New helper function
+    /// Derives the address string of a public key for the given network and address type.
+    /// Only the public key is needed, so keys from the incremental walker plug in directly.
     pub fn derive_address(
         secp: &Secp256k1<All>,
         network: Network,
         addr_type: BitcoinAddressType,
-        secret_key: bitcoin::secp256k1::SecretKey,
         public_key: bitcoin::secp256k1::PublicKey,
     ) -> String {
         match addr_type {
@@ -89,50 +158,131 @@ impl BitcoinVanityGenerator {
                 Address::p2wpkh(&pubkey, network).to_string()
             }
             BitcoinAddressType::Taproot => {
-                let keypair = bitcoin::secp256k1::Keypair::from_secret_key(secp, &secret_key);
-                let (x_only, _parity) = XOnlyPublicKey::from_keypair(&keypair);
+                let (x_only, _parity) = public_key.x_only_public_key();
                 Address::p2tr(secp, x_only, None, network).to_string()
             }
         }
     }
 
-    pub fn search(
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// Each worker walks consecutive keys from its own random start (see [`KeyWalker`]) and
+    /// jumps to a fresh one after a hit.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
         &self,
-        progress: Option<Arc<std::sync::atomic::AtomicU64>>,
-    ) -> (PrivateKey, CoreAddress) {
-        // Use cached Secp256k1 context (thread-safe)
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        // Rejects patterns no address can match (wrong version character, bc1p on SegWit, …)
+        let patterns = self.patterns.as_ref().map_err(|e| e.clone())?;
+        patterns.validate(&self.format)?;
 
-        rayon::iter::repeat(())
-            .map(|_| {
-                if let Some(p) = &progress {
-                    p.fetch_add(1, Ordering::Relaxed);
-                }
-
-                // Generate key using cached context
-                let (secret_key, public_key) = self.secp.generate_keypair(&mut rand::thread_rng());
-
-                // Create bitcoin::PrivateKey for WIF
-                let bitcoin_private_key = bitcoin::PrivateKey::new(secret_key, self.network);
-                let wif = bitcoin_private_key.to_string();
+        // Use cached Secp256k1 context (thread-safe)
+        par_search(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker| {
+                let hit = self.check_candidate(patterns, walker)?;
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            on_match,
+        )
+    }
 
-                let addr_str = Self::derive_address(
+    /// Keeps the best-scoring keys in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        par_score(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker, floor| {
+                let encoded = walker.public_key().to_encoded_point(false);
+                let public_key = bitcoin::secp256k1::PublicKey::from_slice(encoded.as_bytes())
+                    .map_err(|e| CoreError::CryptoError(e.to_string()))?;
+                let address = CoreAddress::Bitcoin(Self::derive_address(
                     &self.secp,
                     self.network,
                     self.addr_type,
-                    secret_key,
                     public_key,
-                );
+                ));
+                let score = scorer.score(&address);
+                let hit = (score >= floor).then(|| Scored {
+                    score,
+                    private_key: walker.private_key(|secret| self.wif(secret)),
+                    address,
+                });
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            board,
+        )
+    }
+
+    /// `secret` as a WIF private key for the generator's network.
+    fn wif(&self, secret: [u8; 32]) -> PrivateKey {
+        let secret_key = SecretKey::from_slice(&secret).expect("walks skip the zero key");
+        PrivateKey::Bitcoin(bitcoin::PrivateKey::new(secret_key, self.network).to_string())
+    }
 
-                let pk = PrivateKey::Bitcoin(wif);
-                let address = CoreAddress::Bitcoin(addr_str);
+    /// Tests the walker's current key, rejecting on raw bytes before any address string is built.
+    #[inline]
+    fn check_candidate(
+        &self,
+        patterns: &PatternSet,
+        walker: &KeyWalker,
+    ) -> Result<Option<Match>, CoreError> {
+        if let Some((version, ranges)) = &self.legacy_prefix {
+            // The P2PKH payload is version ‖ hash160 ‖ checksum: test it with the
+            // checksum left open so only candidates that can still match are encoded
+            let compressed = walker.public_key().to_encoded_point(true);
+            let mut lo = [0u8; 25];
+            lo[0] = *version;
+            lo[1..21].copy_from_slice(&hash160(compressed.as_bytes()));
+            let mut hi = lo;
+            hi[21..].fill(0xff);
+            if !ranges.intersects(&lo, &hi) {
+                return Ok(None);
+            }
+        }
+
+        if let (BitcoinAddressType::SegWit, Some(bits)) = (self.addr_type, &self.bech32_pattern) {
+            // P2WPKH witness program is the key hash
+            let compressed = walker.public_key().to_encoded_point(true);
+            if !bits.matches(&hash160(compressed.as_bytes())) {
+                return Ok(None);
+            }
+        }
 
-                (pk, address)
-            })
-            .find_any(|(_pk, addr)| match addr {
-                CoreAddress::Bitcoin(s) => self.matches(s),
-                _ => false,
-            })
-            .expect("Infinite iterator execution")
+        // Uncompressed parsing only checks the curve equation (no square root)
+        let encoded = walker.public_key().to_encoded_point(false);
+        let public_key = bitcoin::secp256k1::PublicKey::from_slice(encoded.as_bytes())
+            .map_err(|e| CoreError::CryptoError(e.to_string()))?;
+
+        if let (BitcoinAddressType::Taproot, Some(bits)) = (self.addr_type, &self.bech32_pattern) {
+            // P2TR witness program is the tweaked output key
+            let (internal_key, _parity) = public_key.x_only_public_key();
+            let (output_key, _parity) = internal_key.tap_tweak(&self.secp, None);
+            if !bits.matches(&output_key.to_x_only_public_key().serialize()) {
+                return Ok(None);
+            }
+        }
+
+        let addr_str = Self::derive_address(&self.secp, self.network, self.addr_type, public_key);
+        let Some(pattern) = patterns.find(&addr_str) else {
+            return Ok(None);
+        };
+
+        // The WIF is only built for the hit
+        Ok(Some(Match {
+            private_key: walker.private_key(|secret| self.wif(secret)),
+            address: CoreAddress::Bitcoin(addr_str),
+            pattern,
+        }))
     }
 }
 
@@ -140,21 +290,42 @@ impl VanityGenerator for BitcoinVanityGenerator {
     fn generate(&self) -> (PrivateKey, CoreAddress) {
         // Single-threaded generation for efficient batch processing
         // avoids rayon overhead when we just want one key
-        let (secret_key, public_key) = self.secp.generate_keypair(&mut rand::thread_rng());
-
-        // Create bitcoin::PrivateKey for WIF
-        let bitcoin_private_key = bitcoin::PrivateKey::new(secret_key, self.network);
-        let wif = bitcoin_private_key.to_string();
-
-        let addr_str = Self::derive_address(
-            &self.secp,
-            self.network,
-            self.addr_type,
-            secret_key,
-            public_key,
-        );
+        let (private_key, public_key) = match &self.split_key {
+            Some(base) => {
+                let walker = KeyWalker::random_split(base, &mut OsRng, 1);
+                let encoded = walker.public_key().to_encoded_point(false);
+                (
+                    walker.private_key(|secret| self.wif(secret)),
+                    bitcoin::secp256k1::PublicKey::from_slice(encoded.as_bytes())
+                        .expect("point on the curve"),
+                )
+            }
+            None => {
+                let (secret_key, public_key) = self.secp.generate_keypair(&mut rand::thread_rng());
+                (self.wif(secret_key.secret_bytes()), public_key)
+            }
+        };
 
-        (PrivateKey::Bitcoin(wif), CoreAddress::Bitcoin(addr_str))
+        let addr_str = Self::derive_address(&self.secp, self.network, self.addr_type, public_key);
+
+        (private_key, CoreAddress::Bitcoin(addr_str))
+    }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        BitcoinVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        BitcoinVanityGenerator::search_scored(self, options, scorer, board)
     }
 }
 
@@ -163,8 +334,154 @@ mod tests {
     use super::*;
     use bitcoin::hashes::Hash;
     use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bitcoin::XOnlyPublicKey;
     use std::str::FromStr;
 
+    #[test]
+    fn test_split_key_search() {
+        // Every address type matches the combined key, reported as a partial key
+        let secp = Secp256k1::new();
+        let secret = k256::SecretKey::random(&mut OsRng);
+        for addr_type in [
+            BitcoinAddressType::Legacy,
+            BitcoinAddressType::SegWit,
+            BitcoinAddressType::Taproot,
+        ] {
+            let gen = BitcoinVanityGenerator::new("", "", false, Network::Bitcoin, addr_type)
+                .with_split_key(secret.public_key());
+            for (pk, addr) in [
+                gen.search(&SearchOptions::default()).unwrap().unwrap(),
+                gen.generate(),
+            ] {
+                let PrivateKey::Secp256k1Partial(partial) = pk else {
+                    panic!("Expected a partial key");
+                };
+                let combined =
+                    crate::combine_split_key(&secret.to_bytes().into(), &partial).unwrap();
+                let public_key = SecretKey::from_slice(&combined).unwrap().public_key(&secp);
+                let derived = BitcoinVanityGenerator::derive_address(
+                    &secp,
+                    Network::Bitcoin,
+                    addr_type,
+                    public_key,
+                );
+                assert_eq!(addr, CoreAddress::Bitcoin(derived));
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_key_derives_address() {
+        let secp = Secp256k1::new();
+        let cases = [
+            (BitcoinAddressType::Legacy, "1A"),
+            (BitcoinAddressType::SegWit, "bc1qq"),
+            (BitcoinAddressType::Taproot, "bc1pq"),
+        ];
+
+        for (addr_type, prefix) in cases {
+            let generator =
+                BitcoinVanityGenerator::new(prefix, "", false, Network::Bitcoin, addr_type);
+            let (pk, addr) = generator
+                .search(&SearchOptions::default())
+                .unwrap()
+                .expect("unbounded search finds a match");
+
+            let PrivateKey::Bitcoin(wif) = pk else {
+                panic!("Expected Bitcoin private key");
+            };
+            let btc_priv_key = bitcoin::PrivateKey::from_str(&wif).expect("valid WIF");
+            let public_key = btc_priv_key.inner.public_key(&secp);
+            let derived = BitcoinVanityGenerator::derive_address(
+                &secp,
+                Network::Bitcoin,
+                addr_type,
+                public_key,
+            );
+
+            assert_eq!(addr, CoreAddress::Bitcoin(derived.clone()));
+            assert!(derived.to_lowercase().starts_with(&prefix.to_lowercase()));
+        }
+    }
+
+    #[test]
+    fn test_pattern_set_search() {
+        let cases = [
+            (BitcoinAddressType::Legacy, ["1A", "1b", "1?z"]),
+            (BitcoinAddressType::SegWit, ["bc1qq", "bc1qp", "bc1q?0"]),
+        ];
+        for (addr_type, prefixes) in cases {
+            let format = address_format(Network::Bitcoin, addr_type);
+            let words = prefixes.map(|p| format.compile(p, "", false).unwrap());
+            let generator = BitcoinVanityGenerator::from_patterns(
+                PatternSet::new(words.to_vec()),
+                Network::Bitcoin,
+                addr_type,
+            );
+            let found = generator
+                .search_match(&SearchOptions::default())
+                .unwrap()
+                .unwrap();
+            let address = found.address.to_string();
+            assert!(words[found.pattern].matches(&address), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_legacy_prefilter() {
+        // Testnet P2PKH (version 0x6f) starts with 'm' or 'n'
+        let generator = BitcoinVanityGenerator::new(
+            "mm",
+            "",
+            true,
+            Network::Testnet,
+            BitcoinAddressType::Legacy,
+        );
+        let (_, addr) = generator
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
+        assert!(addr.to_string().starts_with("mm"));
+
+        // Mainnet P2PKH always starts with '1'; '0' is not Base58
+        for prefix in ["1111111111111111111111111111", "A", "10"] {
+            let generator = BitcoinVanityGenerator::new(
+                prefix,
+                "",
+                true,
+                Network::Bitcoin,
+                BitcoinAddressType::Legacy,
+            );
+            assert!(generator.search(&SearchOptions::default()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_bech32_prefilter() {
+        // Suffixes inside the checksum are checked after the payload constraints
+        for addr_type in [BitcoinAddressType::SegWit, BitcoinAddressType::Taproot] {
+            let generator =
+                BitcoinVanityGenerator::new("", "QQ", false, Network::Bitcoin, addr_type);
+            let (_, addr) = generator
+                .search(&SearchOptions::default())
+                .unwrap()
+                .unwrap();
+            assert!(addr.to_string().ends_with("qq"), "{addr}");
+        }
+
+        // The witness version character is fixed by the address type
+        let cases = [
+            (BitcoinAddressType::SegWit, "bc1p"),
+            (BitcoinAddressType::Taproot, "bc1q"),
+            (BitcoinAddressType::SegWit, "tb1q"),
+        ];
+        for (addr_type, prefix) in cases {
+            let generator =
+                BitcoinVanityGenerator::new(prefix, "", false, Network::Bitcoin, addr_type);
+            assert!(generator.search(&SearchOptions::default()).is_err());
+        }
+    }
+
     // ... (test_bitcoin_key_usability remains the same)
     #[test]
     fn test_bitcoin_key_usability() {
@@ -242,7 +559,6 @@ mod tests {
                 &secp,
                 Network::Bitcoin,
                 addr_type,
-                secret_key,
                 public_key,
             );
             assert_eq!(address_str, expected, "Mismatch for {:?}", addr_type);
@@ -1,117 +1,193 @@
 use bech32::{Bech32, Hrp};
 use k256::ecdsa::SigningKey;
 use k256::elliptic_curve::rand_core::OsRng;
-use ripemd::Ripemd160;
-use sha2::{Digest, Sha256};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use vanity_core::{Address, PrivateKey, VanityGenerator};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::PublicKey;
+use vanity_core::{
+    Address, AddressFormat, Bech32Layout, Bech32Pattern, CharClass, CoreError, Leaderboard, Match,
+    Pattern, PatternError, PatternSet, PrivateKey, Scored, Scorer, SearchOptions, VanityGenerator,
+};
+
+use crate::search::{par_score, par_search};
+use crate::secp::{hash160, random_walker, KeyWalker, DEFAULT_BLOCK_SIZE};
 
 pub struct CosmosVanityGenerator {
     hrp: String,
-    prefix: String,
-    suffix: String,
-    case_sensitive: bool,
+    /// Compiled data-part patterns, or the syntax error the search reports.
+    patterns: Result<PatternSet, PatternError>,
+    block_size: usize,
+    /// Anchors shared by all patterns as 5-bit constraints on the key hash.
+    bits: Bech32Pattern,
+    /// Requester's public key in split-key mode.
+    split_key: Option<PublicKey>,
 }
 
 impl CosmosVanityGenerator {
     pub fn new(hrp: &str, prefix: &str, suffix: &str, case_sensitive: bool) -> Self {
-        Self {
+        let format = AddressFormat::Cosmos {
             hrp: hrp.to_string(),
-            prefix: prefix.to_string(),
-            suffix: suffix.to_string(),
-            case_sensitive,
-        }
+        };
+        let pattern = format.compile(prefix, suffix, case_sensitive);
+        Self::compiled(hrp, pattern.map(PatternSet::from))
     }
 
-    // ... (imports remain same)
+    /// Searches for a compiled data-part pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(hrp: &str, pattern: Pattern) -> Self {
+        Self::from_patterns(hrp, PatternSet::from(pattern))
+    }
 
-    pub fn search(&self, found_flag: Option<Arc<AtomicU64>>) -> (PrivateKey, Address) {
-        let mut csprng = OsRng;
-        let p_prefix = &self.prefix;
-        let p_suffix = &self.suffix;
-        let p_case = self.case_sensitive;
-        // Code Review: Fallback to "cosmos" is active, but we should potentially warn/error in new() if invalid.
-        // For search loop, we must have a valid HRP.
-        let hrp = Hrp::parse(&self.hrp).unwrap_or(Hrp::parse("cosmos").expect("valid default"));
+    /// Searches for any data-part pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(hrp: &str, patterns: PatternSet) -> Self {
+        Self::compiled(hrp, Ok(patterns))
+    }
 
-        loop {
-            if let Some(ref attempts) = found_flag {
-                attempts.fetch_add(1, Ordering::Relaxed);
-            }
+    fn compiled(hrp: &str, patterns: Result<PatternSet, PatternError>) -> Self {
+        let envelope = patterns
+            .as_ref()
+            .map(PatternSet::envelope)
+            .unwrap_or_default();
+        let layout = Bech32Layout::cosmos(hrp);
+        // The pattern starts after `hrp1`, the compiled constraints at the start of the address
+        let mut prefix: Vec<CharClass> = layout.head().bytes().map(CharClass::single).collect();
+        prefix.extend(envelope.prefix_classes());
+        let suffix = envelope.suffix_classes();
 
-            // 1. Generate Keypair (secp256k1 compressed)
-            let signing_key = SigningKey::random(&mut csprng);
-            let verifying_key = signing_key.verifying_key();
-            let pubkey_bytes = verifying_key.to_sec1_bytes(); // 33 bytes compressed
+        Self {
+            hrp: hrp.to_string(),
+            patterns,
+            block_size: DEFAULT_BLOCK_SIZE,
+            bits: Bech32Pattern::new(&layout, &prefix, &suffix),
+            split_key: None,
+        }
+    }
 
-            // 2. SHA256(pubkey)
-            let sha256_hash = Sha256::digest(&pubkey_bytes);
+    /// Sets how many candidates share one field inversion during [`search`](Self::search).
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
 
-            // 3. RIPEMD160(sha256_hash)
-            let mut ripemd_hasher = Ripemd160::new();
-            ripemd_hasher.update(sha256_hash);
-            let address_bytes = ripemd_hasher.finalize();
+    /// Split-key mode: matches the address of `public_key + k·G` and reports only the partial
+    /// key `k`, which is useless without the requester's secret (see
+    /// [`combine_split_key`](crate::combine_split_key)).
+    pub fn with_split_key(mut self, public_key: PublicKey) -> Self {
+        self.split_key = Some(public_key);
+        self
+    }
 
-            // 4. Encode Bech32
-            // Code Review: Handle potential encoding errors gracefully or expect success for valid inputs.
-            let address_str =
-                bech32::encode::<Bech32>(hrp, &address_bytes).expect("bech32 encoding failed");
+    /// Derives the address of a private key under `hrp`.
+    pub fn derive_address(hrp: &str, private_key: &[u8; 32]) -> Result<String, CoreError> {
+        let hrp = Hrp::parse(hrp)
+            .map_err(|e| CoreError::InvalidPattern(format!("invalid HRP '{}': {}", hrp, e)))?;
+        let signing_key = SigningKey::from_bytes(private_key.into())
+            .map_err(|e| CoreError::CryptoError(e.to_string()))?;
+        let address_bytes = hash160(&signing_key.verifying_key().to_sec1_bytes());
+        bech32::encode::<Bech32>(hrp, &address_bytes)
+            .map_err(|e| CoreError::CryptoError(format!("bech32 encoding failed: {}", e)))
+    }
 
-            // 5. Check Match
-            let mut is_match = true;
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        // For search loop, we must have a valid HRP.
+        let hrp = Hrp::parse(&self.hrp)
+            .map_err(|e| CoreError::InvalidPattern(format!("invalid HRP '{}': {}", self.hrp, e)))?;
 
-            if !p_prefix.is_empty() {
-                // Simplified Logic from Code Review
-                let target_start = format!("{}1{}", self.hrp, p_prefix);
+        let format = AddressFormat::Cosmos {
+            hrp: self.hrp.clone(),
+        };
+        let patterns = self.patterns.as_ref().map_err(|e| e.clone())?;
+        patterns.validate(&format)?;
+        let data_start = self.hrp.len() + 1;
 
-                let matches = if p_case {
-                    address_str.starts_with(&target_start)
-                } else {
-                    address_str.starts_with(&target_start.to_lowercase())
-                };
+        par_search(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker| {
+                // 1. Next key on this worker's walk (secp256k1 compressed, 33 bytes)
+                let pubkey_bytes = walker.public_key().to_encoded_point(true);
 
-                if !matches {
-                    is_match = false;
-                }
-            }
+                // 2 & 3. RIPEMD160(SHA256(pubkey))
+                let address_bytes = hash160(pubkey_bytes.as_bytes());
 
-            if is_match && !p_suffix.is_empty() {
-                let matches = if p_case {
-                    address_str.ends_with(p_suffix)
+                // 4. Check the 5-bit groups, then encode Bech32 for hits only
+                let hit = if self.bits.matches(&address_bytes) {
+                    let address_str =
+                        bech32::encode::<Bech32>(hrp, &address_bytes).map_err(|e| {
+                            CoreError::CryptoError(format!("bech32 encoding failed: {}", e))
+                        })?;
+                    patterns
+                        .find(&address_str[data_start..])
+                        .map(|pattern| Match {
+                            private_key: walker.private_key(PrivateKey::Cosmos),
+                            address: Address::Cosmos(address_str),
+                            pattern,
+                        })
                 } else {
-                    address_str.ends_with(&p_suffix.to_lowercase())
+                    None
                 };
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            on_match,
+        )
+    }
 
-                if !matches {
-                    is_match = false;
-                }
-            }
-
-            if is_match {
-                let secret_bytes = signing_key.to_bytes();
-                return (
-                    PrivateKey::Cosmos(secret_bytes.into()),
-                    Address::Cosmos(address_str),
-                );
-            }
-        }
+    /// Keeps the best-scoring keys in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        let hrp = Hrp::parse(&self.hrp)
+            .map_err(|e| CoreError::InvalidPattern(format!("invalid HRP '{}': {}", self.hrp, e)))?;
+
+        par_score(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker, floor| {
+                let pubkey_bytes = walker.public_key().to_encoded_point(true);
+                let address_bytes = hash160(pubkey_bytes.as_bytes());
+                let address_str = bech32::encode::<Bech32>(hrp, &address_bytes).map_err(|e| {
+                    CoreError::CryptoError(format!("bech32 encoding failed: {}", e))
+                })?;
+                let address = Address::Cosmos(address_str);
+                let score = scorer.score(&address);
+                let hit = (score >= floor).then(|| Scored {
+                    score,
+                    private_key: walker.private_key(PrivateKey::Cosmos),
+                    address,
+                });
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            board,
+        )
     }
 }
 
 impl VanityGenerator for CosmosVanityGenerator {
     fn generate(&self) -> (PrivateKey, Address) {
+        let hrp = Hrp::parse(&self.hrp).unwrap_or(Hrp::parse("cosmos").unwrap());
+        if let Some(base) = &self.split_key {
+            let walker = KeyWalker::random_split(base, &mut OsRng, 1);
+            let address_bytes = hash160(walker.public_key().to_encoded_point(true).as_bytes());
+            return (
+                walker.private_key(PrivateKey::Cosmos),
+                Address::Cosmos(bech32::encode::<Bech32>(hrp, &address_bytes).unwrap()),
+            );
+        }
         let mut csprng = OsRng;
         let signing_key = SigningKey::random(&mut csprng);
         let verifying_key = signing_key.verifying_key();
         let pubkey_bytes = verifying_key.to_sec1_bytes();
+        let address_bytes = hash160(&pubkey_bytes);
 
-        let sha256_hash = Sha256::digest(&pubkey_bytes);
-        let mut ripemd_hasher = Ripemd160::new();
-        ripemd_hasher.update(sha256_hash);
-        let address_bytes = ripemd_hasher.finalize();
-
-        let hrp = Hrp::parse(&self.hrp).unwrap_or(Hrp::parse("cosmos").unwrap());
         let address_str = bech32::encode::<Bech32>(hrp, &address_bytes).unwrap();
 
         let secret_bytes = signing_key.to_bytes();
@@ -120,4 +196,113 @@ impl VanityGenerator for CosmosVanityGenerator {
             Address::Cosmos(address_str),
         )
     }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        CosmosVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        CosmosVanityGenerator::search_scored(self, options, scorer, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_vector_scalar_one() {
+        // Compressed pubkey of scalar 1 -> hash160 751e76e8199196d454941c45d1b3a323f1433bd6
+        let pubkey =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        let address_bytes = hash160(&pubkey);
+        let hrp = Hrp::parse("cosmos").unwrap();
+        assert_eq!(
+            bech32::encode::<Bech32>(hrp, &address_bytes).unwrap(),
+            "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c"
+        );
+    }
+
+    #[test]
+    fn test_split_key_search() {
+        let secret = SigningKey::random(&mut OsRng);
+        let public_key = PublicKey::from(secret.verifying_key());
+        let gen = CosmosVanityGenerator::new("osmo", "q", "", false).with_split_key(public_key);
+        let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Secp256k1Partial(partial) = pk else {
+            panic!("Expected a partial key");
+        };
+        let combined = crate::combine_split_key(&secret.to_bytes().into(), &partial).unwrap();
+        let derived = CosmosVanityGenerator::derive_address("osmo", &combined).unwrap();
+        assert_eq!(addr, Address::Cosmos(derived.clone()));
+        assert!(derived.starts_with("osmo1q"));
+    }
+
+    #[test]
+    fn test_search_key_derives_address() {
+        let gen = CosmosVanityGenerator::new("osmo", "q", "", false);
+        let (pk, addr) = gen
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
+
+        let PrivateKey::Cosmos(secret) = pk else {
+            panic!("Expected Cosmos private key");
+        };
+        let signing_key = SigningKey::from_bytes(&secret.into()).expect("valid key");
+        let address_bytes = hash160(&signing_key.verifying_key().to_sec1_bytes());
+        let hrp = Hrp::parse("osmo").unwrap();
+        let derived = bech32::encode::<Bech32>(hrp, &address_bytes).unwrap();
+
+        assert_eq!(addr, Address::Cosmos(derived.clone()));
+        assert!(derived.starts_with("osmo1q"));
+    }
+
+    #[test]
+    fn test_checksum_suffix_and_invalid_pattern() {
+        // The last six characters are the checksum, computed only after the payload matches
+        let gen = CosmosVanityGenerator::new("cosmos", "", "qq", false);
+        let (_, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        assert!(addr.to_string().ends_with("qq"));
+
+        // 'b', 'i', 'o' and '1' are not in the Bech32 data alphabet
+        let gen = CosmosVanityGenerator::new("cosmos", "b", "", false);
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_pattern_set_search() {
+        let format = AddressFormat::Cosmos {
+            hrp: "cosmos".into(),
+        };
+        let words =
+            [("x", ""), ("", "qq"), ("z", "")].map(|(p, s)| format.compile(p, s, false).unwrap());
+        let gen = CosmosVanityGenerator::from_patterns("cosmos", PatternSet::new(words.to_vec()));
+        let found = gen
+            .search_match(&SearchOptions::default())
+            .unwrap()
+            .unwrap();
+
+        let address = found.address.to_string();
+        assert!(
+            words[found.pattern].matches(&address["cosmos1".len()..]),
+            "{address}"
+        );
+    }
+
+    #[test]
+    fn test_invalid_hrp_is_an_error() {
+        let gen = CosmosVanityGenerator::new("bad hrp", "q", "", false);
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
 }
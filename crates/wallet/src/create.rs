@@ -0,0 +1,75 @@
+use sha3::{Digest, Keccak256};
+
+/// Address of the contract `sender` creates with CREATE at `nonce`:
+/// `keccak256(rlp([sender, nonce]))[12..]`.
+pub fn create_address(sender: &[u8; 20], nonce: u64) -> [u8; 20] {
+    // RLP of the nonce: 0x80 for zero, the byte itself below 0x80, else length-prefixed
+    let be = nonce.to_be_bytes();
+    let significant = &be[be.iter().take_while(|&&b| b == 0).count()..];
+    let mut encoded_nonce = [0u8; 9];
+    let nonce_len = match significant {
+        [] => {
+            encoded_nonce[0] = 0x80;
+            1
+        }
+        [byte] if *byte < 0x80 => {
+            encoded_nonce[0] = *byte;
+            1
+        }
+        bytes => {
+            encoded_nonce[0] = 0x80 + bytes.len() as u8;
+            encoded_nonce[1..=bytes.len()].copy_from_slice(bytes);
+            bytes.len() + 1
+        }
+    };
+
+    let mut hasher = Keccak256::new();
+    // List header (payload is under 56 bytes), then the 20-byte string header
+    hasher.update([0xc0 + 21 + nonce_len as u8, 0x94]);
+    hasher.update(sender);
+    hasher.update(&encoded_nonce[..nonce_len]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hasher.finalize()[12..]);
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::bytes;
+
+    #[test]
+    fn test_create_addresses() {
+        let sender = bytes("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let expected = [
+            "cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d",
+            "343c43a37d37dff08ae8c4a11544c718abb4fcf8",
+            "f778b86fa74e846c4f0a1fbd1335fe81c00a0c91",
+            "fffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c",
+        ];
+        for (nonce, expected) in expected.into_iter().enumerate() {
+            assert_eq!(create_address(&sender, nonce as u64), bytes::<20>(expected));
+        }
+    }
+
+    #[test]
+    fn test_long_nonces() {
+        // 0x80 and up are length-prefixed strings
+        let sender = [0x11; 20];
+        let rlp = |nonce: &[u8]| -> [u8; 20] {
+            let mut list = vec![0xc0 + 21 + nonce.len() as u8, 0x94];
+            list.extend_from_slice(&sender);
+            list.extend_from_slice(nonce);
+            let mut address = [0u8; 20];
+            address.copy_from_slice(&Keccak256::digest(&list)[12..]);
+            address
+        };
+        assert_eq!(create_address(&sender, 0x7f), rlp(&[0x7f]));
+        assert_eq!(create_address(&sender, 0x80), rlp(&[0x81, 0x80]));
+        assert_eq!(create_address(&sender, 0x0400), rlp(&[0x82, 0x04, 0x00]));
+        assert_eq!(
+            create_address(&sender, u64::MAX),
+            rlp(&[0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use sha3::{Digest, Keccak256};
+use vanity_core::{
+    Address, AddressBits, AddressFormat, CoreError, Leaderboard, Match, Pattern, PatternError,
+    PatternSet, PrivateKey, Scorer, SearchOptions, VanityGenerator, ZeroBytes,
+};
+
+use crate::ethereum::EthereumTarget;
+use crate::salt::{SaltLayout, SaltSearch};
+
+/// Address a CREATE2 factory at `deployer` deploys init code hashing to `init_code_hash`
+/// to with `salt` (EIP-1014): `keccak256(0xff ‖ deployer ‖ salt ‖ initCodeHash)[12..]`.
+#[inline]
+pub fn create2_address(
+    deployer: &[u8; 20],
+    salt: &[u8; 32],
+    init_code_hash: &[u8; 32],
+) -> [u8; 20] {
+    let mut preimage = [0u8; 85];
+    preimage[0] = 0xff;
+    preimage[1..21].copy_from_slice(deployer);
+    preimage[21..53].copy_from_slice(salt);
+    preimage[53..].copy_from_slice(init_code_hash);
+    let hash = Keccak256::digest(preimage);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Searches CREATE2 salts for a vanity contract address; no private key is involved.
+///
+/// Matches carry the salt as [`PrivateKey::Salt`]. Each worker starts from a random salt
+/// (after the fixed [`salt prefix`](Self::with_salt_prefix)) and counts upwards, so a candidate
+/// costs one Keccak-256 of 85 bytes.
+pub struct Create2VanityGenerator {
+    target: EthereumTarget,
+    deployer: [u8; 20],
+    init_code_hash: [u8; 32],
+    /// Leading salt bytes the factory requires, e.g. the caller's address.
+    salt_prefix: Vec<u8>,
+}
+
+impl Create2VanityGenerator {
+    pub fn new(
+        prefix: &str,
+        suffix: &str,
+        case_sensitive: bool,
+        deployer: [u8; 20],
+        init_code_hash: [u8; 32],
+    ) -> Self {
+        let pattern = AddressFormat::Ethereum.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from), deployer, init_code_hash)
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern, deployer: [u8; 20], init_code_hash: [u8; 32]) -> Self {
+        Self::from_patterns(PatternSet::from(pattern), deployer, init_code_hash)
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(
+        patterns: PatternSet,
+        deployer: [u8; 20],
+        init_code_hash: [u8; 32],
+    ) -> Self {
+        Self::compiled(Ok(patterns), deployer, init_code_hash)
+    }
+
+    fn compiled(
+        patterns: Result<PatternSet, PatternError>,
+        deployer: [u8; 20],
+        init_code_hash: [u8; 32],
+    ) -> Self {
+        Self {
+            target: EthereumTarget::new(patterns),
+            deployer,
+            init_code_hash,
+            salt_prefix: Vec::new(),
+        }
+    }
+
+    /// Fixes the first salt bytes, for factories that require e.g. the caller's address in
+    /// the first 20 bytes. At least one byte must be left to search.
+    pub fn with_salt_prefix(mut self, salt_prefix: &[u8]) -> Self {
+        self.salt_prefix = salt_prefix.to_vec();
+        self
+    }
+
+    /// Also requires zero bytes in the contract address (see [`ZeroBytes`]).
+    pub fn with_zero_bytes(mut self, zero_bytes: ZeroBytes) -> Self {
+        self.target = self.target.with_zero_bytes(zero_bytes);
+        self
+    }
+
+    /// Also requires a bit mask or numeric bound on the contract address (see
+    /// [`AddressBits`]), e.g. Uniswap v4 hook flags; repeat to combine several.
+    pub fn with_bits(mut self, bits: AddressBits) -> Self {
+        self.target = self.target.with_bits(bits);
+        self
+    }
+
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        self.check_salt_prefix()?;
+        self.salts().search_each(options, on_match)
+    }
+
+    /// Keeps the best-scoring salts in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        self.check_salt_prefix()?;
+        self.salts().search_scored(options, scorer, board)
+    }
+
+    fn check_salt_prefix(&self) -> Result<(), CoreError> {
+        if self.salt_prefix.len() >= 32 {
+            return Err(CoreError::InvalidPattern(format!(
+                "salt prefix is {} bytes; a salt has 32 and at least one must be left to search",
+                self.salt_prefix.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Salts after the salt prefix, and the contract address each deploys to.
+    fn salts(&self) -> SaltSearch<'_, impl Fn(&[u8; 32]) -> [u8; 20] + Sync + '_> {
+        SaltSearch {
+            target: &self.target,
+            layout: SaltLayout::after(&self.salt_prefix),
+            address: |salt: &[u8; 32]| create2_address(&self.deployer, salt, &self.init_code_hash),
+        }
+    }
+}
+
+impl VanityGenerator for Create2VanityGenerator {
+    /// A random salt (after the salt prefix) and the address it deploys to.
+    fn generate(&self) -> (PrivateKey, Address) {
+        self.salts().generate()
+    }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        Create2VanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        Create2VanityGenerator::search_scored(self, options, scorer, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::bytes;
+
+    #[test]
+    fn test_eip1014_examples() {
+        let hash =
+            |code: &str| -> [u8; 32] { Keccak256::digest(hex::decode(code).unwrap()).into() };
+        let cases = [
+            (
+                "0000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "00",
+                "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38",
+            ),
+            (
+                "deadbeef00000000000000000000000000000000",
+                "000000000000000000000000feed000000000000000000000000000000000000",
+                "00",
+                "0xD04116cDd17beBE565EB2422F2497E06cC1C9833",
+            ),
+            (
+                "00000000000000000000000000000000deadbeef",
+                "00000000000000000000000000000000000000000000000000000000cafebabe",
+                "deadbeef",
+                "0x60f3f640a8508fC6a86d45DF051962668E1e8AC7",
+            ),
+        ];
+        for (deployer, salt, code, expected) in cases {
+            let address = create2_address(&bytes(deployer), &bytes(salt), &hash(code));
+            assert_eq!(Address::Ethereum(address).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_salt_search() {
+        let deployer = bytes("4e59b44847b379578588920ca78fbf26c0b4956c");
+        let init_code_hash = [0x42; 32];
+        let caller = bytes::<20>("00000000000000000000000000000000deadbeef");
+        let gen = Create2VanityGenerator::new("ab", "", false, deployer, init_code_hash)
+            .with_salt_prefix(&caller);
+        let (salt, address) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+
+        let PrivateKey::Salt(salt) = salt else {
+            panic!("Wrong key type");
+        };
+        assert_eq!(salt[..20], caller);
+        assert_eq!(
+            Address::Ethereum(create2_address(&deployer, &salt, &init_code_hash)),
+            address
+        );
+        assert!(address.to_match_string().starts_with("ab"));
+
+        let gen = gen.with_salt_prefix(&[0; 32]);
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use sha3::{Digest, Keccak256};
+use vanity_core::{
+    Address, AddressBits, AddressFormat, CoreError, Leaderboard, Match, Pattern, PatternError,
+    PatternSet, PrivateKey, Scorer, SearchOptions, VanityGenerator, ZeroBytes,
+};
+
+use crate::create::create_address;
+use crate::create2::create2_address;
+use crate::ethereum::EthereumTarget;
+use crate::salt::{SaltLayout, SaltSearch};
+
+/// The CreateX factory, deployed at the same address on most EVM chains.
+pub const CREATEX: [u8; 20] = [
+    0xba, 0x5e, 0xd0, 0x99, 0x63, 0x3d, 0x3b, 0x31, 0x3e, 0x4d, 0x5f, 0x7b, 0xdc, 0x13, 0x05, 0xd3,
+    0xc2, 0x8b, 0xa5, 0xed,
+];
+
+/// keccak256 of the proxy init code `0x67363d3d37363d34f03d5260086018f3` (CreateX and Solady).
+/// A CREATE3 factory deploys the proxy with CREATE2; it then deploys the real contract with
+/// CREATE at nonce 1.
+const PROXY_INIT_CODE_HASH: [u8; 32] = [
+    0x21, 0xc3, 0x5d, 0xbe, 0x1b, 0x34, 0x4a, 0x24, 0x88, 0xcf, 0x33, 0x21, 0xd6, 0xce, 0x54, 0x2f,
+    0x8e, 0x9f, 0x30, 0x55, 0x44, 0xff, 0x09, 0xe4, 0x99, 0x3a, 0x62, 0x31, 0x9a, 0x49, 0x7c, 0x1f,
+];
+
+/// Address of a contract deployed through a CREATE3 factory: the CREATE2 address of the proxy
+/// for `guarded_salt`, then the address that proxy creates at nonce 1. The init code plays no
+/// part, so the address is the same on every chain with the factory at `factory`.
+pub fn create3_address(factory: &[u8; 20], guarded_salt: &[u8; 32]) -> [u8; 20] {
+    let proxy = create2_address(factory, guarded_salt, &PROXY_INIT_CODE_HASH);
+    create_address(&proxy, 1)
+}
+
+/// How CreateX guards a salt before deploying with it, chosen by the salt's first 21 bytes.
+///
+/// The first 20 bytes name who may deploy (zero for anyone) and byte 20 says whether the chain
+/// id is mixed in, which makes the address differ per chain. The last 11 bytes are mined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaltGuard {
+    /// Anyone may deploy; the same address on every chain.
+    None,
+    /// Only this sender may deploy; the same address on every chain.
+    Sender([u8; 20]),
+    /// Anyone may deploy, on this chain only.
+    ChainId(u64),
+    /// Only this sender, on this chain only.
+    SenderAndChainId([u8; 20], u64),
+}
+
+impl SaltGuard {
+    /// Salt prefix that selects this guard: the sender (or zeros) and the chain-id flag.
+    pub fn salt_head(&self) -> [u8; 21] {
+        let mut head = [0u8; 21];
+        match *self {
+            SaltGuard::None => {}
+            SaltGuard::Sender(sender) => head[..20].copy_from_slice(&sender),
+            SaltGuard::ChainId(_) => head[20] = 0x01,
+            SaltGuard::SenderAndChainId(sender, _) => {
+                head[..20].copy_from_slice(&sender);
+                head[20] = 0x01;
+            }
+        }
+        head
+    }
+
+    /// Salt the factory actually deploys with for `salt`: `keccak256` of the sender and chain
+    /// id (each padded to 32 bytes, when guarded) followed by `salt`.
+    pub fn guard(&self, salt: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        if let SaltGuard::Sender(sender) | SaltGuard::SenderAndChainId(sender, _) = self {
+            hasher.update([0u8; 12]);
+            hasher.update(sender);
+        }
+        if let SaltGuard::ChainId(chain_id) | SaltGuard::SenderAndChainId(_, chain_id) = self {
+            hasher.update([0u8; 24]);
+            hasher.update(chain_id.to_be_bytes());
+        }
+        hasher.update(salt);
+        hasher.finalize().into()
+    }
+}
+
+/// Searches CreateX CREATE3 salts for a vanity contract address.
+///
+/// Matches carry the salt to pass to the factory as [`PrivateKey::Salt`]. A candidate costs
+/// three Keccak-256 hashes: the guard, the proxy's CREATE2 address and its CREATE child.
+pub struct Create3VanityGenerator {
+    target: EthereumTarget,
+    factory: [u8; 20],
+    guard: SaltGuard,
+}
+
+impl Create3VanityGenerator {
+    pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Self {
+        let pattern = AddressFormat::Ethereum.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from))
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern) -> Self {
+        Self::from_patterns(PatternSet::from(pattern))
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(patterns: PatternSet) -> Self {
+        Self::compiled(Ok(patterns))
+    }
+
+    fn compiled(patterns: Result<PatternSet, PatternError>) -> Self {
+        Self {
+            target: EthereumTarget::new(patterns),
+            factory: CREATEX,
+            guard: SaltGuard::None,
+        }
+    }
+
+    /// Deploys through a CreateX instance at another address than [`CREATEX`].
+    pub fn with_factory(mut self, factory: [u8; 20]) -> Self {
+        self.factory = factory;
+        self
+    }
+
+    /// Guards the salt (see [`SaltGuard`]); unguarded by default.
+    pub fn with_guard(mut self, guard: SaltGuard) -> Self {
+        self.guard = guard;
+        self
+    }
+
+    /// Also requires zero bytes in the contract address (see [`ZeroBytes`]).
+    pub fn with_zero_bytes(mut self, zero_bytes: ZeroBytes) -> Self {
+        self.target = self.target.with_zero_bytes(zero_bytes);
+        self
+    }
+
+    /// Also requires a bit mask or numeric bound on the contract address (see
+    /// [`AddressBits`]), e.g. Uniswap v4 hook flags; repeat to combine several.
+    pub fn with_bits(mut self, bits: AddressBits) -> Self {
+        self.target = self.target.with_bits(bits);
+        self
+    }
+
+    /// Contract address the factory deploys to for `salt`.
+    pub fn address(&self, salt: &[u8; 32]) -> [u8; 20] {
+        create3_address(&self.factory, &self.guard.guard(salt))
+    }
+
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        self.salts().search_each(options, on_match)
+    }
+
+    /// Keeps the best-scoring salts in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        self.salts().search_scored(options, scorer, board)
+    }
+
+    /// Salts after the guard's salt head (the last 11 bytes are mined), and the contract
+    /// address each deploys to.
+    fn salts(&self) -> SaltSearch<'_, impl Fn(&[u8; 32]) -> [u8; 20] + Sync + '_> {
+        SaltSearch {
+            target: &self.target,
+            layout: SaltLayout::after(&self.guard.salt_head()),
+            address: |salt: &[u8; 32]| self.address(salt),
+        }
+    }
+}
+
+impl VanityGenerator for Create3VanityGenerator {
+    /// A random salt for the guard and the address it deploys to.
+    fn generate(&self) -> (PrivateKey, Address) {
+        self.salts().generate()
+    }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        Create3VanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        Create3VanityGenerator::search_scored(self, options, scorer, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::bytes;
+
+    #[test]
+    fn test_constants() {
+        let proxy_init_code = bytes::<16>("67363d3d37363d34f03d5260086018f3");
+        assert_eq!(Keccak256::digest(proxy_init_code)[..], PROXY_INIT_CODE_HASH);
+        assert_eq!(
+            Address::Ethereum(CREATEX).to_string(),
+            "0xba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed"
+        );
+    }
+
+    #[test]
+    fn test_guards() {
+        let sender = bytes::<20>("00000000000000000000000000000000deadbeef");
+        let salt = [0x07; 32];
+        let hash = |parts: &[&[u8]]| -> [u8; 32] { Keccak256::digest(parts.concat()).into() };
+        let padded_sender = [&[0u8; 12][..], &sender].concat();
+        let chain_id = {
+            let mut word = [0u8; 32];
+            word[24..].copy_from_slice(&10u64.to_be_bytes());
+            word
+        };
+
+        assert_eq!(SaltGuard::None.guard(&salt), hash(&[&salt]));
+        assert_eq!(
+            SaltGuard::Sender(sender).guard(&salt),
+            hash(&[&padded_sender, &salt])
+        );
+        assert_eq!(
+            SaltGuard::ChainId(10).guard(&salt),
+            hash(&[&chain_id, &salt])
+        );
+        assert_eq!(
+            SaltGuard::SenderAndChainId(sender, 10).guard(&salt),
+            hash(&[&padded_sender, &chain_id, &salt])
+        );
+
+        let head = SaltGuard::SenderAndChainId(sender, 10).salt_head();
+        assert_eq!(head[..20], sender);
+        assert_eq!(head[20], 0x01);
+        assert_eq!(SaltGuard::Sender(sender).salt_head()[20], 0x00);
+    }
+
+    #[test]
+    fn test_guarded_search() {
+        let sender = bytes::<20>("00000000000000000000000000000000deadbeef");
+        let gen =
+            Create3VanityGenerator::new("ab", "", false).with_guard(SaltGuard::Sender(sender));
+        let (salt, address) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+
+        let PrivateKey::Salt(salt) = salt else {
+            panic!("Wrong key type");
+        };
+        assert_eq!(salt[..20], sender);
+        assert_eq!(salt[20], 0x00);
+        let guarded = SaltGuard::Sender(sender).guard(&salt);
+        assert_eq!(
+            Address::Ethereum(create3_address(&CREATEX, &guarded)),
+            address
+        );
+        assert!(address.to_match_string().starts_with("ab"));
+    }
+}
@@ -0,0 +1,162 @@
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use ed25519_dalek::hazmat::{raw_sign, ExpandedSecretKey};
+use ed25519_dalek::VerifyingKey;
+use rand::RngCore;
+use sha2::{Digest, Sha512};
+use vanity_core::CoreError;
+
+/// Message [`verify_expanded_key`] signs to prove an expanded key works.
+const CHECK_MESSAGE: &[u8] = b"vanity_crypto split-key check";
+
+/// Incremental ed25519 split-key walker.
+///
+/// Visits `A + k·B, A + (k+1)·B, …` for the requester's public point `A` from a random `k`, so
+/// each candidate costs one point addition and a compression. The walker only ever knows `k`:
+/// the candidate's secret scalar is `a + k`, where `a` is the requester's.
+pub struct SplitWalker {
+    partial: Scalar,
+    point: EdwardsPoint,
+}
+
+impl SplitWalker {
+    /// Starts a walk for `base` at a uniformly random `k`.
+    pub fn random(base: &VerifyingKey, rng: &mut impl RngCore) -> Self {
+        let mut wide = [0u8; 64];
+        rng.fill_bytes(&mut wide);
+        let partial = Scalar::from_bytes_mod_order_wide(&wide);
+        Self {
+            partial,
+            point: base.to_edwards() + EdwardsPoint::mul_base(&partial),
+        }
+    }
+
+    /// Compressed public key of the current candidate, as Solana and TON use it.
+    #[inline]
+    pub fn public_key(&self) -> [u8; 32] {
+        self.point.compress().to_bytes()
+    }
+
+    /// The current `k`, little-endian: what the worker reports instead of a key.
+    pub fn partial(&self) -> [u8; 32] {
+        self.partial.to_bytes()
+    }
+
+    /// Moves to the next candidate (`P + B`).
+    #[inline]
+    pub fn advance(&mut self) {
+        self.partial += Scalar::ONE;
+        self.point += ED25519_BASEPOINT_POINT;
+    }
+}
+
+/// Expands a 32-byte ed25519 seed into the 64-byte form split keys are combined in: the
+/// signing scalar (clamped from `SHA-512(seed)`, reduced, little-endian) followed by the nonce
+/// prefix (the hash's second half).
+pub fn expand_seed(seed: &[u8; 32]) -> [u8; 64] {
+    let hash = Sha512::digest(seed);
+    let mut scalar = [0u8; 32];
+    scalar.copy_from_slice(&hash[..32]);
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+
+    let mut expanded = [0u8; 64];
+    expanded[..32].copy_from_slice(&Scalar::from_bytes_mod_order(scalar).to_bytes());
+    expanded[32..].copy_from_slice(&hash[32..]);
+    expanded
+}
+
+/// Final key of an ed25519 split-key search: the requester's `secret` (a 32-byte seed, or a
+/// 64-byte expanded key as [`expand_seed`] returns) with the worker's `partial` scalar added.
+///
+/// The sum has no seed, so the result is an expanded key; it keeps the requester's nonce
+/// prefix, which the worker never sees.
+pub fn combine_ed25519_split_key(secret: &[u8], partial: &[u8; 32]) -> Result<[u8; 64], CoreError> {
+    let mut expanded = match secret.len() {
+        32 => expand_seed(secret.try_into().expect("32 bytes")),
+        64 => secret.try_into().expect("64 bytes"),
+        n => {
+            return Err(CoreError::CryptoError(format!(
+                "an ed25519 secret is a 32-byte seed or a 64-byte expanded key, not {} bytes",
+                n
+            )))
+        }
+    };
+    let scalar = canonical_scalar(&expanded[..32], "secret scalar")?;
+    let partial = canonical_scalar(partial, "partial key")?;
+    expanded[..32].copy_from_slice(&(scalar + partial).to_bytes());
+    Ok(expanded)
+}
+
+/// Signs and strictly verifies a fixed message with an expanded key, returning its public key.
+/// This exercises the key the way a wallet would rather than only recomputing the point.
+pub fn verify_expanded_key(expanded: &[u8; 64]) -> Result<VerifyingKey, CoreError> {
+    let mut hash_prefix = [0u8; 32];
+    hash_prefix.copy_from_slice(&expanded[32..]);
+    let secret = ExpandedSecretKey {
+        scalar: canonical_scalar(&expanded[..32], "secret scalar")?,
+        hash_prefix,
+    };
+    let public_key = VerifyingKey::from(&secret);
+    let signature = raw_sign::<Sha512>(&secret, CHECK_MESSAGE, &public_key);
+    public_key
+        .verify_strict(CHECK_MESSAGE, &signature)
+        .map_err(|e| CoreError::CryptoError(format!("signature check failed: {}", e)))?;
+    Ok(public_key)
+}
+
+fn canonical_scalar(bytes: &[u8], name: &str) -> Result<Scalar, CoreError> {
+    let bytes: [u8; 32] = bytes.try_into().expect("32 bytes");
+    Option::from(Scalar::from_canonical_bytes(bytes))
+        .ok_or_else(|| CoreError::CryptoError(format!("{} is not below the group order", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_expanded_seed_signs_like_the_seed() {
+        // Ed25519 signatures are deterministic, so both forms must agree exactly
+        let seed = [0x42; 32];
+        let signing_key = SigningKey::from_bytes(&seed);
+        let expanded = expand_seed(&seed);
+        assert_eq!(
+            verify_expanded_key(&expanded).unwrap(),
+            signing_key.verifying_key()
+        );
+
+        let mut hash_prefix = [0u8; 32];
+        hash_prefix.copy_from_slice(&expanded[32..]);
+        let secret = ExpandedSecretKey {
+            scalar: canonical_scalar(&expanded[..32], "").unwrap(),
+            hash_prefix,
+        };
+        let signature = raw_sign::<Sha512>(&secret, b"hi", &signing_key.verifying_key());
+        assert_eq!(signature, signing_key.sign(b"hi"));
+    }
+
+    #[test]
+    fn test_split_walk_combines() {
+        let seed = [0x07; 32];
+        let base = SigningKey::from_bytes(&seed).verifying_key();
+        let mut walker = SplitWalker::random(&base, &mut OsRng);
+        for _ in 0..5 {
+            let combined = combine_ed25519_split_key(&seed, &walker.partial()).unwrap();
+            let public_key = verify_expanded_key(&combined).unwrap();
+            assert_eq!(public_key.to_bytes(), walker.public_key());
+            // Combining again from the expanded form gives the same key
+            assert_eq!(
+                combine_ed25519_split_key(&expand_seed(&seed), &walker.partial()).unwrap(),
+                combined
+            );
+            walker.advance();
+        }
+
+        assert!(combine_ed25519_split_key(&[0; 31], &[0; 32]).is_err());
+        assert!(combine_ed25519_split_key(&seed, &[0xff; 32]).is_err());
+    }
+}
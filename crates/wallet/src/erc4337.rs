@@ -0,0 +1,123 @@
+use sha3::{Digest, Keccak256};
+use vanity_core::CoreError;
+
+use crate::create2::create2_address;
+
+/// Marks where the owner's 20 bytes go in an init-code template.
+pub const OWNER_PLACEHOLDER: &str = "{owner}";
+
+/// Factory entry point the first UserOperation calls, as in eth-infinitism's
+/// `SimpleAccountFactory` and the factories modelled on it.
+const CREATE_ACCOUNT: &str = "createAccount(address,uint256)";
+
+/// An ERC-4337 account a factory deploys with CREATE2 for `owner` and a `uint256 salt`,
+/// which it uses as the CREATE2 salt unchanged.
+///
+/// The salt search itself is a plain CREATE2 search: pass [`factory`](Self::factory) and
+/// [`init_code_hash`](Self::init_code_hash) to
+/// [`Create2VanityGenerator`](crate::Create2VanityGenerator).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountDeployment {
+    /// The account factory, e.g. `SimpleAccountFactory`.
+    pub factory: [u8; 20],
+    /// Owner the account is created for.
+    pub owner: [u8; 20],
+    /// Init code the factory deploys for this owner, e.g. `ERC1967Proxy`'s creation code
+    /// followed by `abi.encode(implementation, initialize(owner) calldata)`.
+    pub init_code: Vec<u8>,
+}
+
+impl AccountDeployment {
+    /// Fills in `owner` for each [`OWNER_PLACEHOLDER`] of a hex init-code template, e.g.
+    /// `0x6080…c4d66de8000000000000000000000000{owner}…`.
+    pub fn from_template(
+        factory: [u8; 20],
+        owner: [u8; 20],
+        template: &str,
+    ) -> Result<Self, CoreError> {
+        let template = template.strip_prefix("0x").unwrap_or(template);
+        if !template.contains(OWNER_PLACEHOLDER) {
+            return Err(CoreError::InvalidPattern(format!(
+                "the init code template has no {} placeholder, so the owner would not affect \
+                 the address",
+                OWNER_PLACEHOLDER
+            )));
+        }
+        let init_code = hex::decode(template.replace(OWNER_PLACEHOLDER, &hex::encode(owner)))?;
+        Ok(Self {
+            factory,
+            owner,
+            init_code,
+        })
+    }
+
+    pub fn init_code_hash(&self) -> [u8; 32] {
+        Keccak256::digest(&self.init_code).into()
+    }
+
+    /// Account address for `salt`, as the factory's `getAddress(owner, salt)` predicts it.
+    pub fn address(&self, salt: &[u8; 32]) -> [u8; 20] {
+        create2_address(&self.factory, salt, &self.init_code_hash())
+    }
+
+    /// `initCode` of the account's first UserOperation: the factory followed by the calldata
+    /// of `createAccount(owner, salt)`.
+    pub fn user_op_init_code(&self, salt: &[u8; 32]) -> Vec<u8> {
+        let mut init_code = Vec::with_capacity(20 + 4 + 64);
+        init_code.extend_from_slice(&self.factory);
+        init_code.extend_from_slice(&Keccak256::digest(CREATE_ACCOUNT)[..4]);
+        init_code.extend_from_slice(&[0u8; 12]);
+        init_code.extend_from_slice(&self.owner);
+        init_code.extend_from_slice(salt);
+        init_code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Create2VanityGenerator;
+    use vanity_core::{Address, PrivateKey, SearchOptions, VanityGenerator};
+
+    const OWNER: [u8; 20] = [0x0e; 20];
+
+    #[test]
+    fn test_template() {
+        let account =
+            AccountDeployment::from_template([0xfa; 20], OWNER, "0x6080{owner}00{owner}").unwrap();
+        let mut expected = vec![0x60, 0x80];
+        expected.extend_from_slice(&OWNER);
+        expected.push(0x00);
+        expected.extend_from_slice(&OWNER);
+        assert_eq!(account.init_code, expected);
+
+        assert!(AccountDeployment::from_template([0xfa; 20], OWNER, "6080").is_err());
+        assert!(AccountDeployment::from_template([0xfa; 20], OWNER, "60{owner}8").is_err());
+    }
+
+    #[test]
+    fn test_user_op_init_code() {
+        let account = AccountDeployment::from_template([0xfa; 20], OWNER, "{owner}").unwrap();
+        let salt = [0x05; 32];
+        let init_code = account.user_op_init_code(&salt);
+        assert_eq!(init_code.len(), 20 + 4 + 64);
+        assert_eq!(init_code[..20], [0xfa; 20]);
+        // createAccount(address,uint256)
+        assert_eq!(init_code[20..24], [0x5f, 0xbf, 0xb9, 0xcf]);
+        assert_eq!(init_code[24..36], [0; 12]);
+        assert_eq!(init_code[36..56], OWNER);
+        assert_eq!(init_code[56..], salt);
+    }
+
+    #[test]
+    fn test_salt_search() {
+        let account = AccountDeployment::from_template([0xfa; 20], OWNER, "6080{owner}").unwrap();
+        let gen =
+            Create2VanityGenerator::new("ab", "", false, account.factory, account.init_code_hash());
+        let (salt, address) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Salt(salt) = salt else {
+            panic!("Wrong key type");
+        };
+        assert_eq!(Address::Ethereum(account.address(&salt)), address);
+    }
+}
@@ -1,157 +1,523 @@
-// use hex; // Removed unused import
 use k256::ecdsa::{SigningKey, VerifyingKey};
-
-use rayon::prelude::*;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{AffinePoint, PublicKey};
+use rand::rngs::OsRng;
 use sha3::{Digest, Keccak256};
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use vanity_core::{Address, PrivateKey, VanityGenerator};
+use vanity_core::{
+    eip55_hash, eip55_is_upper, Address, AddressBits, AddressFormat, ByteMask, CharClass,
+    CoreError, Leaderboard, Match, Pattern, PatternError, PatternSet, PrivateKey, Scored, Scorer,
+    SearchOptions, VanityGenerator, ZeroBytes,
+};
+
+use crate::create::create_address;
+use crate::search::{par_score, par_search};
+use crate::secp::{random_walker, KeyWalker, DEFAULT_BLOCK_SIZE};
+
+/// Prefix/suffix pattern compiled into nibble constraints on the raw 20-byte address.
+///
+/// Candidates are compared byte-wise without building any string. Positions whose class pins
+/// the case of its letters (case-sensitive letters, `\u`, `\l`) only compute the EIP-55
+/// checksum for candidates that already match case-insensitively, so checksum searches cost
+/// about the same as lowercase ones. Classes the nibble mask cannot express exactly and
+/// substrings are confirmed on the checksummed string.
+#[derive(Clone, Debug)]
+pub struct EthereumMatcher {
+    nibbles: ByteMask,
+    /// (hex character index, must be uppercase) for positions whose letters have one case.
+    case_checks: Vec<(usize, bool)>,
+    /// Pattern to confirm hits with, when the mask and case checks are not exact.
+    confirm: Option<Pattern>,
+    /// False when the pattern can never match (non-hex characters, too long, or self-contradicting).
+    satisfiable: bool,
+}
+
+impl EthereumMatcher {
+    pub fn new(pattern: &Pattern) -> Self {
+        let mut matcher = Self {
+            nibbles: ByteMask::new(20),
+            case_checks: Vec::new(),
+            confirm: pattern.has_substrings().then(|| pattern.clone()),
+            satisfiable: true,
+        };
+        let positions: Vec<(Option<usize>, CharClass)> = {
+            let prefix = pattern.prefix().iter().enumerate();
+            let suffix_start = 40usize.checked_sub(pattern.suffix().len());
+            let suffix = pattern
+                .suffix()
+                .iter()
+                .enumerate()
+                .map(|(i, slot)| (suffix_start.map(|start| start + i), slot));
+            prefix
+                .map(|(i, slot)| (Some(i), slot))
+                .chain(suffix)
+                .map(|(index, slot)| (index, slot.class))
+                .collect()
+        };
+        for (index, class) in positions {
+            if !matcher.require(index, class) {
+                matcher.confirm = Some(pattern.clone());
+            }
+        }
+        matcher
+    }
+
+    /// Constrains one hex character; returns `false` when only the string check is exact.
+    fn require(&mut self, index: Option<usize>, class: CharClass) -> bool {
+        let Some(index) = index.filter(|&i| i < 40) else {
+            self.satisfiable = false;
+            return true;
+        };
+        let cases = |nibble: u64| {
+            let lower = char::from_digit(nibble as u32, 16).expect("nibble");
+            (
+                class.contains(lower),
+                class.contains(lower.to_ascii_uppercase()),
+            )
+        };
+        let nibbles: Vec<u64> = (0..16)
+            .filter(|&nibble| {
+                let (lower, upper) = cases(nibble);
+                lower || upper
+            })
+            .collect();
+        let Some(&first) = nibbles.first() else {
+            self.satisfiable = false;
+            return true;
+        };
+
+        // Keep the bits every allowed nibble shares
+        let mut shared = 0;
+        for k in 0..4 {
+            let bit = first >> (3 - k) & 1;
+            if nibbles.iter().all(|&nibble| nibble >> (3 - k) & 1 == bit) {
+                shared += 1;
+                if !self.nibbles.require_bits(index * 4 + k, 1, bit) {
+                    self.satisfiable = false;
+                }
+            }
+        }
+        let exact_nibbles = nibbles.len() == 1 << (4 - shared);
+
+        // Letters allowed in one case only fix an EIP-55 bit
+        let letter_cases: Vec<(bool, bool)> = nibbles
+            .iter()
+            .filter(|&&nibble| nibble >= 10)
+            .map(|&nibble| cases(nibble))
+            .collect();
+        if letter_cases.iter().all(|&(lower, upper)| lower && upper) {
+            return exact_nibbles;
+        }
+        match letter_cases[0] {
+            (lower, upper)
+                if lower != upper && letter_cases.iter().all(|&c| c == (lower, upper)) =>
+            {
+                self.case_checks.push((index, upper));
+                exact_nibbles
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks the raw address bytes against the pattern.
+    #[inline(always)]
+    pub fn matches(&self, address: &[u8; 20]) -> bool {
+        self.satisfiable
+            && self.nibbles.matches(address)
+            && (self.case_checks.is_empty() || self.matches_checksum(address))
+            && self
+                .confirm
+                .as_ref()
+                .is_none_or(|pattern| confirm(pattern, address))
+    }
+
+    /// Checks the nibble mask only, a necessary condition for [`matches`](Self::matches).
+    #[inline(always)]
+    pub(crate) fn matches_nibbles(&self, address: &[u8; 20]) -> bool {
+        self.satisfiable && self.nibbles.matches(address)
+    }
+
+    #[cold]
+    fn matches_checksum(&self, address: &[u8; 20]) -> bool {
+        let checksum_hash = eip55_hash(address);
+        self.case_checks.iter().all(|&(index, upper)| {
+            // Digits have no case
+            let byte = address[index / 2];
+            let nibble = if index % 2 == 0 {
+                byte >> 4
+            } else {
+                byte & 0x0f
+            };
+            nibble < 10 || eip55_is_upper(&checksum_hash, index) == upper
+        })
+    }
+}
+
+/// Matches the checksummed address without its `0x`.
+#[cold]
+fn confirm(pattern: &Pattern, address: &[u8; 20]) -> bool {
+    pattern.matches(&Address::Ethereum(*address).to_string()[2..])
+}
+
+/// What a 20-byte address must look like: patterns with their compiled matcher, an optional
+/// zero-byte rule and bit rules. Shared by the EOA and contract-address generators.
+#[derive(Clone, Debug)]
+pub(crate) struct EthereumTarget {
+    /// Compiled patterns, or the syntax error the search reports.
+    patterns: Result<PatternSet, PatternError>,
+    /// Exact matcher of a single pattern, or the shared anchors of a set.
+    matcher: EthereumMatcher,
+    /// Zero bytes every match must also have.
+    zero_bytes: Option<ZeroBytes>,
+    /// Bit masks and numeric bounds every match must also meet.
+    bits: Vec<AddressBits>,
+}
+
+impl EthereumTarget {
+    pub(crate) fn new(patterns: Result<PatternSet, PatternError>) -> Self {
+        let matcher = match patterns.as_ref().map(PatternSet::patterns) {
+            Ok([pattern]) => EthereumMatcher::new(pattern),
+            Ok(patterns) => EthereumMatcher::new(&Pattern::envelope(patterns)),
+            Err(_) => EthereumMatcher::new(&Pattern::default()),
+        };
+        Self {
+            patterns,
+            matcher,
+            zero_bytes: None,
+            bits: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_zero_bytes(mut self, zero_bytes: ZeroBytes) -> Self {
+        self.zero_bytes = Some(zero_bytes);
+        self
+    }
+
+    pub(crate) fn with_bits(mut self, bits: AddressBits) -> Self {
+        self.bits.push(bits);
+        self
+    }
+
+    /// The patterns, once they and the address rules are known to be satisfiable.
+    pub(crate) fn validated(&self) -> Result<&PatternSet, CoreError> {
+        let patterns = self.patterns.as_ref().map_err(|e| e.clone())?;
+        patterns.validate(&AddressFormat::Ethereum)?;
+        if let Some(zero_bytes) = &self.zero_bytes {
+            zero_bytes.validate()?;
+        }
+        for bits in &self.bits {
+            bits.validate()?;
+        }
+        Ok(patterns)
+    }
+
+    /// Index of the pattern an address meeting the zero-byte and bit rules matches. A single
+    /// pattern is decided by the matcher; sets walk the lowercase hex and confirm candidates on
+    /// the checksummed form.
+    #[inline]
+    pub(crate) fn find(&self, patterns: &PatternSet, address: &[u8; 20]) -> Option<usize> {
+        if self
+            .zero_bytes
+            .is_some_and(|zero_bytes| !zero_bytes.matches(address))
+            || !self.bits.iter().all(|bits| bits.matches(address))
+        {
+            return None;
+        }
+        if patterns.len() == 1 {
+            return self.matcher.matches(address).then_some(0);
+        }
+        if !self.matcher.matches_nibbles(address) {
+            return None;
+        }
+        let mut checksummed = None;
+        patterns.find_with(&hex::encode(address), |pattern| {
+            let text = checksummed.get_or_insert_with(|| Address::Ethereum(*address).to_string());
+            pattern.matches(&text[2..])
+        })
+    }
+}
 
 pub struct EthereumVanityGenerator {
-    prefix: String,
-    suffix: String,
-    case_sensitive: bool,
+    target: EthereumTarget,
+    block_size: usize,
+    /// Match the contract the account creates at this nonce instead of the account.
+    deploy_nonce: Option<u64>,
+    /// Requester's public key in split-key mode.
+    split_key: Option<PublicKey>,
 }
 
 impl EthereumVanityGenerator {
     pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Self {
-        // Validation happens before this, usually in CLI parsing
+        let pattern = AddressFormat::Ethereum.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from))
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern) -> Self {
+        Self::from_patterns(PatternSet::from(pattern))
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(patterns: PatternSet) -> Self {
+        Self::compiled(Ok(patterns))
+    }
+
+    fn compiled(patterns: Result<PatternSet, PatternError>) -> Self {
         Self {
-            prefix: prefix.to_string(),
-            suffix: suffix.to_string(),
-            case_sensitive,
+            target: EthereumTarget::new(patterns),
+            block_size: DEFAULT_BLOCK_SIZE,
+            deploy_nonce: None,
+            split_key: None,
         }
     }
 
-    /// Checks if a given address string matches the criteria
-    #[inline(always)]
-    fn matches(&self, addr_str: &str) -> bool {
-        // Compare normalized strings.
+    /// Also requires zero bytes in the raw address, checked before any pattern. Use empty
+    /// patterns to search for zero bytes alone.
+    pub fn with_zero_bytes(mut self, zero_bytes: ZeroBytes) -> Self {
+        self.target = self.target.with_zero_bytes(zero_bytes);
+        self
+    }
 
-        let target = if self.case_sensitive {
-            addr_str.to_string()
-        } else {
-            addr_str.to_lowercase()
-        };
-        // Strip 0x if present (important for checksummed addresses from to_string())
-        let target = target.trim_start_matches("0x");
+    /// Also requires a bit mask or numeric bound on the raw address (see [`AddressBits`]);
+    /// repeat to combine several.
+    pub fn with_bits(mut self, bits: AddressBits) -> Self {
+        self.target = self.target.with_bits(bits);
+        self
+    }
 
-        let p_match = if !self.prefix.is_empty() {
-            let p = if self.case_sensitive {
-                self.prefix.clone()
-            } else {
-                self.prefix.to_lowercase()
-            };
-            // Strip 0x from prefix if present
-            let p = p.trim_start_matches("0x");
-            target.starts_with(p)
-        } else {
-            true
-        };
+    /// Sets how many candidates share one field inversion during [`search`](Self::search).
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
 
-        let s_match = if !self.suffix.is_empty() {
-            let s = if self.case_sensitive {
-                self.suffix.clone()
-            } else {
-                self.suffix.to_lowercase()
-            };
-            target.ends_with(&s)
-        } else {
-            true
-        };
+    /// Matches the address of the contract each key's account deploys with CREATE at `nonce`
+    /// (see [`create_address`]) instead of the account's own. Results carry the contract
+    /// address; [`derive_address`](Self::derive_address) recovers the deployer.
+    pub fn with_deploy_nonce(mut self, nonce: u64) -> Self {
+        self.deploy_nonce = Some(nonce);
+        self
+    }
+
+    /// Split-key mode: matches the address of `public_key + k·G` and reports only the partial
+    /// key `k`, which is useless without the requester's secret (see
+    /// [`combine_split_key`](crate::combine_split_key)).
+    pub fn with_split_key(mut self, public_key: PublicKey) -> Self {
+        self.split_key = Some(public_key);
+        self
+    }
 
-        p_match && s_match
+    /// Derives the account address of a private key, or `None` if it is not a valid scalar.
+    pub fn derive_address(private_key: &[u8; 32]) -> Option<[u8; 20]> {
+        let signing_key = SigningKey::from_bytes(private_key.into()).ok()?;
+        Some(address_from_point(
+            VerifyingKey::from(&signing_key).as_affine(),
+        ))
     }
 
-    /// Run the search using multiple threads.
-    /// This uses rayon to parallelize.
-    /// Takes an optional progress counter to track attempts.
-    pub fn search(
+    /// Address matched for a public key: the account's, or its contract at the deploy nonce.
+    #[inline]
+    fn matched_address(&self, point: &AffinePoint) -> [u8; 20] {
+        let account = address_from_point(point);
+        match self.deploy_nonce {
+            Some(nonce) => create_address(&account, nonce),
+            None => account,
+        }
+    }
+
+    /// Run the search using multiple threads; each hit is tagged with the pattern it matched.
+    /// This uses rayon to parallelize; each worker walks consecutive keys from its own
+    /// random start (see [`KeyWalker`]) and jumps to a fresh one after a hit.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
         &self,
-        progress: Option<Arc<std::sync::atomic::AtomicU64>>,
-    ) -> (PrivateKey, Address) {
-        rayon::iter::repeat(())
-            .map(|_| {
-                if let Some(p) = &progress {
-                    p.fetch_add(1, Ordering::Relaxed);
-                }
-                Self::generate_keypair()
-            })
-            .find_any(|(_pk, addr)| {
-                if self.case_sensitive {
-                    self.matches(&addr.to_string())
-                } else {
-                    self.matches(&addr.to_match_string())
-                }
-            })
-            .expect("Infinite iterator execution")
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        let patterns = self.target.validated()?;
+
+        par_search(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker| {
+                let address_bytes = self.matched_address(walker.public_key());
+                let hit = self
+                    .target
+                    .find(patterns, &address_bytes)
+                    .map(|pattern| Match {
+                        private_key: walker.private_key(PrivateKey::Ethereum),
+                        address: Address::Ethereum(address_bytes),
+                        pattern,
+                    });
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            on_match,
+        )
+    }
+
+    /// Keeps the best-scoring keys in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        par_score(
+            options,
+            || random_walker(self.split_key.as_ref(), self.block_size),
+            |walker, floor| {
+                let address = Address::Ethereum(self.matched_address(walker.public_key()));
+                let score = scorer.score(&address);
+                let hit = (score >= floor).then(|| Scored {
+                    score,
+                    private_key: walker.private_key(PrivateKey::Ethereum),
+                    address,
+                });
+                walker.advance(&mut OsRng);
+                Ok(hit)
+            },
+            board,
+        )
     }
 
     /// Shared logical core for key generation
-    fn generate_keypair() -> (PrivateKey, Address) {
+    fn generate_keypair(&self) -> (PrivateKey, Address) {
+        if let Some(base) = &self.split_key {
+            let walker = KeyWalker::random_split(base, &mut OsRng, 1);
+            return (
+                walker.private_key(PrivateKey::Ethereum),
+                Address::Ethereum(self.matched_address(walker.public_key())),
+            );
+        }
         let bytes: [u8; 32] = rand::random();
 
         let signing_key = SigningKey::from_bytes(&bytes.into()).expect("valid key from random");
         let verifying_key = VerifyingKey::from(&signing_key);
-        let encoded_point = verifying_key.to_encoded_point(false);
-        let encoded = encoded_point.as_bytes();
-        // Skip the uncompressed prefix (0x04)
-        let public_key_bytes = &encoded[1..];
-
-        let mut hasher = Keccak256::new();
-        hasher.update(public_key_bytes);
-        let hash = hasher.finalize();
-
-        let mut address_bytes = [0u8; 20];
-        address_bytes.copy_from_slice(&hash[12..]);
 
-        let address = Address::Ethereum(address_bytes);
+        let address = Address::Ethereum(self.matched_address(verifying_key.as_affine()));
         let pk = PrivateKey::Ethereum(bytes);
 
         (pk, address)
     }
 }
 
+/// Derives the 20-byte Ethereum address of a public key:
+/// the last 20 bytes of Keccak-256 over the uncompressed point (without the 0x04 prefix).
+#[inline]
+pub(crate) fn address_from_point(point: &AffinePoint) -> [u8; 20] {
+    let encoded_point = point.to_encoded_point(false);
+    // Skip the uncompressed prefix (0x04)
+    let public_key_bytes = &encoded_point.as_bytes()[1..];
+
+    let hash = Keccak256::digest(public_key_bytes);
+
+    let mut address_bytes = [0u8; 20];
+    address_bytes.copy_from_slice(&hash[12..]);
+    address_bytes
+}
+
 impl VanityGenerator for EthereumVanityGenerator {
     fn generate(&self) -> (PrivateKey, Address) {
         // Single-threaded optimization for batch generation
-        Self::generate_keypair()
+        self.generate_keypair()
+    }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        EthereumVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        EthereumVanityGenerator::search_scored(self, options, scorer, board)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vanity_core::{MatchLimits, MatchSieve, Score};
+
+    fn matcher(prefix: &str, suffix: &str, case_sensitive: bool) -> EthereumMatcher {
+        let pattern = AddressFormat::Ethereum.compile(prefix, suffix, case_sensitive);
+        EthereumMatcher::new(&pattern.unwrap())
+    }
 
     #[test]
     fn test_case_sensitivity_logic() {
-        let gen_sens = EthereumVanityGenerator::new("DeaD", "", true);
-        let gen_insens = EthereumVanityGenerator::new("dead", "", false);
+        // EIP-55 reference vector: 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", &mut bytes).unwrap();
 
-        // Checksummed address (starts with 0x)
-        let checksum = "0xDeaD000000000000000000000000000000000000";
-        // Lowercase address (starts with 0x)
-        let lower = "0xdead000000000000000000000000000000000000";
+        let gen_sens = matcher("0x5aAe", "BeAed", true);
+        let gen_sens_wrong = matcher("5aae", "", true);
+        let gen_insens = matcher("5AAE", "beaed", false);
 
-        // Sensitive: Must match exact prefix "DeaD" (after 0x strip)
+        // Sensitive: Must match exact checksum casing (after 0x strip)
         assert!(
-            gen_sens.matches(checksum),
-            "Sensitive: Should match 0xDeaD..."
+            gen_sens.matches(&bytes),
+            "Sensitive: Should match 0x5aAe...BeAed"
         );
         assert!(
-            !gen_sens.matches(lower),
-            "Sensitive: Should NOT match 0xdead..."
+            !gen_sens_wrong.matches(&bytes),
+            "Sensitive: Should NOT match 0x5aae..."
         );
 
-        // Insensitive: Should match both "dead" and "DeaD" (normalized to "dead")
-        assert!(
-            gen_insens.matches(checksum),
-            "Insensitive: Should match 0xDeaD..."
-        );
+        // Insensitive: casing of the pattern is irrelevant
         assert!(
-            gen_insens.matches(lower),
-            "Insensitive: Should match 0xdead..."
+            gen_insens.matches(&bytes),
+            "Insensitive: Should match 5AAE...beaed"
         );
+        assert!(!matcher("5aaf", "", false).matches(&bytes));
+        assert!(!matcher("", "beaee", false).matches(&bytes));
+    }
+
+    #[test]
+    fn test_classes() {
+        // 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", &mut bytes).unwrap();
+
+        assert!(matcher("[0-7]\\l", "", true).matches(&bytes));
+        assert!(matcher("?{3}e", "[a-f]{2}", false).matches(&bytes));
+        assert!(matcher("5a\\u", "\\u\\l{2}", true).matches(&bytes));
+        assert!(!matcher("[6-9]", "", false).matches(&bytes));
+        assert!(!matcher("5\\u", "", true).matches(&bytes));
+        assert!(!matcher("", "[^d]", false).matches(&bytes));
+    }
+
+    #[test]
+    fn test_unsatisfiable_patterns() {
+        let bytes = [0u8; 20];
+        assert!(matcher("", "", true).matches(&bytes));
+        assert!(matcher("00", "00", false).matches(&bytes));
+        // Non-hex, too long, and prefix/suffix that disagree where they overlap
+        assert!(!matcher("0g", "", false).matches(&bytes));
+        assert!(!matcher(&"0".repeat(41), "", false).matches(&bytes));
+        assert!(!matcher(&"0".repeat(40), "1", false).matches(&bytes));
+
+        // The search reports them instead of running forever
+        let error = EthereumVanityGenerator::new("0g", "", false)
+            .search(&SearchOptions::default())
+            .unwrap_err();
+        assert!(error.to_string().contains("'g' at position 1"), "{error}");
+    }
+
+    #[test]
+    fn test_case_sensitive_search_matches_display() {
+        let gen = EthereumVanityGenerator::new("", "aB", true);
+        let (_, addr) = gen
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
+        assert!(addr.to_string().ends_with("aB"));
     }
 
     #[test]
@@ -216,6 +582,210 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_search_returns_matching_key() {
+        let gen = EthereumVanityGenerator::new("a", "", false);
+        let (pk, addr) = gen
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
+
+        let PrivateKey::Ethereum(pk_bytes) = pk else {
+            panic!("Expected Ethereum private key");
+        };
+        let signing_key = SigningKey::from_bytes(&pk_bytes.into()).expect("valid key");
+        let derived = address_from_point(VerifyingKey::from(&signing_key).as_affine());
+        assert_eq!(addr, Address::Ethereum(derived));
+        assert!(addr.to_match_string().starts_with('a'));
+    }
+
+    #[test]
+    fn test_search_with_substrings() {
+        let pattern = AddressFormat::Ethereum
+            .compile("[0-7]", "", false)
+            .and_then(|pattern| pattern.with_contains("ab"))
+            .and_then(|pattern| pattern.with_exclude("0"))
+            .unwrap();
+        let (_, addr) = EthereumVanityGenerator::from_pattern(pattern)
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
+
+        let text = addr.to_match_string();
+        assert!(matches!(text.as_bytes()[0], b'1'..=b'7'), "{text}");
+        assert!(text.contains("ab") && !text.contains('0'), "{text}");
+    }
+
+    #[test]
+    fn test_pattern_set_search() {
+        let words = ["0xAb", "cD", "\\u\\u", "[0-3]{2}"]
+            .map(|p| AddressFormat::Ethereum.compile(p, "", true).unwrap());
+        let gen = EthereumVanityGenerator::from_patterns(PatternSet::new(words.to_vec()));
+        for _ in 0..4 {
+            let found = gen
+                .search_match(&SearchOptions::default())
+                .unwrap()
+                .unwrap();
+            let address = found.address.to_string();
+            assert!(words[found.pattern].matches(&address[2..]), "{address}");
+            assert!(words[..found.pattern]
+                .iter()
+                .all(|p| !p.matches(&address[2..])));
+        }
+    }
+
+    #[test]
+    fn test_search_each_streams_distinct_matches() {
+        let words = ["a", "b"].map(|p| AddressFormat::Ethereum.compile(p, "", false).unwrap());
+        let gen = EthereumVanityGenerator::from_patterns(PatternSet::new(words.to_vec()));
+        let limits = MatchLimits {
+            count: 6,
+            per_pattern: Some(3),
+        };
+        let mut sieve = MatchSieve::new(limits, words.len());
+        let mut found = Vec::new();
+        gen.search_each(&SearchOptions::default(), &mut |hit| {
+            if sieve.admit(&hit) {
+                found.push(hit);
+            }
+            !sieve.is_done()
+        })
+        .unwrap();
+
+        assert_eq!(found.len(), 6);
+        for pattern in 0..2 {
+            assert_eq!(found.iter().filter(|m| m.pattern == pattern).count(), 3);
+        }
+        for hit in &found {
+            let address = hit.address.to_string();
+            assert!(words[hit.pattern].matches(&address[2..]), "{address}");
+            let PrivateKey::Ethereum(secret) = hit.private_key else {
+                panic!("Wrong key type");
+            };
+            let signing_key = SigningKey::from_bytes(&secret.into()).expect("valid key");
+            let point = VerifyingKey::from(&signing_key).as_affine().to_owned();
+            assert_eq!(
+                Address::Ethereum(address_from_point(&point)).to_string(),
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_scored_keeps_best() {
+        let gen = EthereumVanityGenerator::new("", "", false);
+        let options = SearchOptions {
+            max_attempts: Some(20_000),
+            ..SearchOptions::default()
+        };
+        let mut board = Leaderboard::new(3);
+        gen.search_scored(&options, &Score::Leading('0'), &mut board)
+            .unwrap();
+
+        let entries = board.entries();
+        assert_eq!(entries.len(), 3);
+        // 20k keys all but guarantee two leading zero nibbles
+        assert!(entries[0].score >= 2);
+        for (entry, next) in entries.iter().zip(&entries[1..]) {
+            assert!(entry.score >= next.score);
+        }
+        for entry in entries {
+            assert_eq!(Score::Leading('0').score(&entry.address), entry.score);
+            let PrivateKey::Ethereum(secret) = entry.private_key else {
+                panic!("Wrong key type");
+            };
+            let signing_key = SigningKey::from_bytes(&secret.into()).expect("valid key");
+            let point = VerifyingKey::from(&signing_key).as_affine().to_owned();
+            assert_eq!(Address::Ethereum(address_from_point(&point)), entry.address);
+        }
+    }
+
+    #[test]
+    fn test_zero_bytes_search() {
+        let gen =
+            EthereumVanityGenerator::new("", "", false).with_zero_bytes(ZeroBytes::Leading(1));
+        let (_, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let Address::Ethereum(bytes) = addr else {
+            panic!("Wrong address type");
+        };
+        assert_eq!(bytes[0], 0);
+
+        // Zero bytes anywhere, together with a suffix
+        let gen =
+            EthereumVanityGenerator::new("", "F", false).with_zero_bytes(ZeroBytes::Anywhere(2));
+        let (_, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let Address::Ethereum(bytes) = addr else {
+            panic!("Wrong address type");
+        };
+        assert!(bytes.iter().filter(|&&b| b == 0).count() >= 2);
+        assert_eq!(bytes[19] & 0x0f, 0x0f);
+
+        let gen =
+            EthereumVanityGenerator::new("", "", false).with_zero_bytes(ZeroBytes::Leading(21));
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_address_bits_search() {
+        // A Uniswap v4 hook with only the afterSwap flag (bit 6) of the low 14 bits
+        let mut mask = [0u8; 20];
+        mask[18..].copy_from_slice(&[0x3f, 0xff]);
+        let mut value = [0u8; 20];
+        value[19] = 0x40;
+        let hook = AddressBits::Mask { mask, value };
+        let gen = EthereumVanityGenerator::new("", "", false).with_bits(hook);
+        let (_, address) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let Address::Ethereum(bytes) = address else {
+            panic!("Wrong address type");
+        };
+        assert!(hook.matches(&bytes));
+        assert_eq!(bytes[18] & 0x3f, 0);
+        assert_eq!(bytes[19], 0x40);
+
+        let gen = gen.with_bits(AddressBits::Below([0; 20]));
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_deploy_nonce_search() {
+        // The contract address matches; the key's account deploys it at the nonce
+        let gen = EthereumVanityGenerator::new("ab", "", false).with_deploy_nonce(3);
+        let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Ethereum(secret) = pk else {
+            panic!("Wrong key type");
+        };
+        let account = EthereumVanityGenerator::derive_address(&secret).unwrap();
+        assert_eq!(addr, Address::Ethereum(create_address(&account, 3)));
+        assert!(addr.to_match_string().starts_with("ab"));
+
+        let (pk, addr) = gen.generate();
+        let PrivateKey::Ethereum(secret) = pk else {
+            panic!("Wrong key type");
+        };
+        let account = EthereumVanityGenerator::derive_address(&secret).unwrap();
+        assert_eq!(addr, Address::Ethereum(create_address(&account, 3)));
+    }
+
+    #[test]
+    fn test_split_key_search() {
+        // The worker only sees the requester's public key; secret + partial is the real key
+        let secret = SigningKey::random(&mut OsRng);
+        let public_key = PublicKey::from(secret.verifying_key());
+        let gen = EthereumVanityGenerator::new("ab", "", false).with_split_key(public_key);
+        let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Secp256k1Partial(partial) = pk else {
+            panic!("Expected a partial key");
+        };
+        assert_ne!(
+            Address::Ethereum(EthereumVanityGenerator::derive_address(&partial).unwrap()),
+            addr
+        );
+        let combined = crate::combine_split_key(&secret.to_bytes().into(), &partial).unwrap();
+        let account = EthereumVanityGenerator::derive_address(&combined).unwrap();
+        assert_eq!(Address::Ethereum(account), addr);
+        assert!(addr.to_match_string().starts_with("ab"));
+    }
+
     use proptest::prelude::*;
 
     proptest! {
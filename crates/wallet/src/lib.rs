@@ -1,12 +1,31 @@
 pub mod bitcoin;
+pub mod create;
+pub mod create2;
+pub mod create3;
+pub mod ed25519;
+pub mod erc4337;
 pub mod ethereum;
 
 pub use bitcoin::{BitcoinAddressType, BitcoinVanityGenerator};
 pub use cosmos::CosmosVanityGenerator;
-pub use ethereum::EthereumVanityGenerator;
+pub use create::create_address;
+pub use create2::{create2_address, Create2VanityGenerator};
+pub use create3::{create3_address, Create3VanityGenerator, SaltGuard, CREATEX};
+pub use ed25519::{combine_ed25519_split_key, verify_expanded_key};
+pub use erc4337::AccountDeployment;
+pub use ethereum::{EthereumMatcher, EthereumVanityGenerator};
+pub use safe::{SafeDeployment, SafeVanityGenerator};
+pub use secp::combine_split_key;
 pub use solana::SolanaVanityGenerator;
 pub use ton::TonVanityGenerator;
 
 pub mod cosmos;
+pub mod safe;
+pub mod secp;
 pub mod solana;
 pub mod ton;
+
+mod salt;
+mod search;
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,211 @@
+use sha3::{Digest, Keccak256};
+use vanity_core::{
+    Address, AddressBits, AddressFormat, CoreError, Leaderboard, Match, Pattern, PatternError,
+    PatternSet, PrivateKey, Scorer, SearchOptions, VanityGenerator, ZeroBytes,
+};
+
+use crate::create2::create2_address;
+use crate::ethereum::EthereumTarget;
+use crate::salt::{SaltLayout, SaltSearch};
+
+/// Everything a Safe proxy's address depends on besides `saltNonce`, as passed to
+/// `SafeProxyFactory.createProxyWithNonce(singleton, initializer, saltNonce)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafeDeployment {
+    /// The `SafeProxyFactory` that deploys the proxy.
+    pub factory: [u8; 20],
+    /// The Safe implementation (`_singleton`) the proxy delegates to.
+    pub singleton: [u8; 20],
+    /// The factory's `proxyCreationCode()`, which differs between Safe versions.
+    pub proxy_creation_code: Vec<u8>,
+    /// Calldata of the `setup` call (owners, threshold, ...).
+    pub initializer: Vec<u8>,
+}
+
+impl SafeDeployment {
+    /// Address of the proxy `createProxyWithNonce` deploys for `salt_nonce` (a big-endian
+    /// uint256): CREATE2 with salt `keccak256(keccak256(initializer) ‖ saltNonce)` and init
+    /// code `proxyCreationCode ‖ uint256(singleton)`.
+    pub fn address(&self, salt_nonce: &[u8; 32]) -> [u8; 20] {
+        self.hashes().address(salt_nonce)
+    }
+
+    fn hashes(&self) -> SafeHashes {
+        let mut deployment = Keccak256::new();
+        deployment.update(&self.proxy_creation_code);
+        deployment.update([0u8; 12]);
+        deployment.update(self.singleton);
+        SafeHashes {
+            factory: self.factory,
+            initializer_hash: Keccak256::digest(&self.initializer).into(),
+            deployment_hash: deployment.finalize().into(),
+        }
+    }
+}
+
+/// The parts of a [`SafeDeployment`] that stay fixed while `saltNonce` varies.
+#[derive(Clone, Copy, Debug)]
+struct SafeHashes {
+    factory: [u8; 20],
+    initializer_hash: [u8; 32],
+    deployment_hash: [u8; 32],
+}
+
+impl SafeHashes {
+    #[inline]
+    fn address(&self, salt_nonce: &[u8; 32]) -> [u8; 20] {
+        let mut salt = Keccak256::new();
+        salt.update(self.initializer_hash);
+        salt.update(salt_nonce);
+        create2_address(
+            &self.factory,
+            &salt.finalize().into(),
+            &self.deployment_hash,
+        )
+    }
+}
+
+/// Searches `saltNonce` values for a vanity Safe address.
+///
+/// Matches carry the salt nonce as a big-endian uint256 in [`PrivateKey::Salt`]. Workers start
+/// from random nonces below 2^64, which keeps them short to type, and count upwards; a
+/// candidate costs two Keccak-256 hashes.
+pub struct SafeVanityGenerator {
+    target: EthereumTarget,
+    hashes: SafeHashes,
+}
+
+impl SafeVanityGenerator {
+    pub fn new(prefix: &str, suffix: &str, case_sensitive: bool, safe: &SafeDeployment) -> Self {
+        let pattern = AddressFormat::Ethereum.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from), safe)
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern, safe: &SafeDeployment) -> Self {
+        Self::from_patterns(PatternSet::from(pattern), safe)
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(patterns: PatternSet, safe: &SafeDeployment) -> Self {
+        Self::compiled(Ok(patterns), safe)
+    }
+
+    fn compiled(patterns: Result<PatternSet, PatternError>, safe: &SafeDeployment) -> Self {
+        Self {
+            target: EthereumTarget::new(patterns),
+            hashes: safe.hashes(),
+        }
+    }
+
+    /// Also requires zero bytes in the Safe address (see [`ZeroBytes`]).
+    pub fn with_zero_bytes(mut self, zero_bytes: ZeroBytes) -> Self {
+        self.target = self.target.with_zero_bytes(zero_bytes);
+        self
+    }
+
+    /// Also requires a bit mask or numeric bound on the Safe address (see [`AddressBits`]);
+    /// repeat to combine several.
+    pub fn with_bits(mut self, bits: AddressBits) -> Self {
+        self.target = self.target.with_bits(bits);
+        self
+    }
+
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        self.nonces().search_each(options, on_match)
+    }
+
+    /// Keeps the best-scoring nonces in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        self.nonces().search_scored(options, scorer, board)
+    }
+
+    /// Nonces below 2^64, counting within 64 bits, and the Safe address each deploys to.
+    fn nonces(&self) -> SaltSearch<'_, impl Fn(&[u8; 32]) -> [u8; 20] + Sync + '_> {
+        SaltSearch {
+            target: &self.target,
+            layout: SaltLayout::after(&[0; 24]),
+            address: |nonce: &[u8; 32]| self.hashes.address(nonce),
+        }
+    }
+}
+
+impl VanityGenerator for SafeVanityGenerator {
+    /// A random salt nonce and the Safe address it deploys to.
+    fn generate(&self) -> (PrivateKey, Address) {
+        self.nonces().generate()
+    }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        SafeVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        SafeVanityGenerator::search_scored(self, options, scorer, board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment() -> SafeDeployment {
+        SafeDeployment {
+            factory: [0x4e; 20],
+            singleton: [0x29; 20],
+            proxy_creation_code: vec![0x60, 0x80, 0x60, 0x40, 0x52],
+            initializer: vec![0xb6, 0x3e, 0x80, 0x0d, 0x01],
+        }
+    }
+
+    #[test]
+    fn test_address_follows_factory() {
+        // Spelled out as SafeProxyFactory computes it
+        let safe = deployment();
+        let mut nonce = [0u8; 32];
+        nonce[31] = 7;
+        let initializer_hash = Keccak256::digest(&safe.initializer);
+        let salt: [u8; 32] = Keccak256::digest([&initializer_hash[..], &nonce].concat()).into();
+        let mut init_code = safe.proxy_creation_code.clone();
+        init_code.extend_from_slice(&[0u8; 12]);
+        init_code.extend_from_slice(&safe.singleton);
+        let init_code_hash: [u8; 32] = Keccak256::digest(&init_code).into();
+        assert_eq!(
+            safe.address(&nonce),
+            create2_address(&safe.factory, &salt, &init_code_hash)
+        );
+    }
+
+    #[test]
+    fn test_salt_nonce_search() {
+        let safe = deployment();
+        let gen = SafeVanityGenerator::new("5afe", "", false, &safe);
+        let (nonce, address) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Salt(nonce) = nonce else {
+            panic!("Wrong key type");
+        };
+        assert_eq!(nonce[..24], [0; 24]);
+        assert_eq!(Address::Ethereum(safe.address(&nonce)), address);
+        assert!(address.to_match_string().starts_with("5afe"));
+    }
+}
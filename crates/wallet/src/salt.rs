@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use rand::{rngs::OsRng, RngCore};
+use vanity_core::{
+    Address, CoreError, Leaderboard, Match, PrivateKey, Scored, Scorer, SearchOptions,
+};
+
+use crate::ethereum::EthereumTarget;
+use crate::search::{par_score, par_search};
+
+/// Which bytes of a 32-byte salt are fixed and which a search mines.
+#[derive(Clone, Debug)]
+pub(crate) struct SaltLayout {
+    /// The salt with its fixed bytes in place; the mined bytes are overwritten.
+    fixed: [u8; 32],
+    mined: Range<usize>,
+}
+
+impl SaltLayout {
+    /// Salts starting with `head` and mined after it; 32 bytes or more leave nothing to mine.
+    pub(crate) fn after(head: &[u8]) -> Self {
+        let len = head.len().min(32);
+        let mut fixed = [0u8; 32];
+        fixed[..len].copy_from_slice(&head[..len]);
+        Self {
+            fixed,
+            mined: len..32,
+        }
+    }
+
+    /// The fixed bytes followed by random mined ones.
+    fn random(&self) -> [u8; 32] {
+        let mut salt = self.fixed;
+        OsRng.fill_bytes(&mut salt[self.mined.clone()]);
+        salt
+    }
+
+    /// Counts the mined bytes up by one, wrapping within them.
+    #[inline]
+    fn next(&self, salt: &mut [u8; 32]) {
+        for byte in salt[self.mined.clone()].iter_mut().rev() {
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+    }
+}
+
+/// Mines the salts of a [`SaltLayout`] for contract addresses `target` accepts, where
+/// `address` is the address a salt deploys to. Each worker starts from a random salt and
+/// counts upwards; matches carry the salt as [`PrivateKey::Salt`].
+pub(crate) struct SaltSearch<'a, F> {
+    pub(crate) target: &'a EthereumTarget,
+    pub(crate) layout: SaltLayout,
+    pub(crate) address: F,
+}
+
+impl<F> SaltSearch<'_, F>
+where
+    F: Fn(&[u8; 32]) -> [u8; 20] + Sync,
+{
+    pub(crate) fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        let patterns = self.target.validated()?;
+
+        par_search(
+            options,
+            || self.layout.random(),
+            |salt| {
+                let address = (self.address)(salt);
+                let hit = self.target.find(patterns, &address).map(|pattern| Match {
+                    private_key: PrivateKey::Salt(*salt),
+                    address: Address::Ethereum(address),
+                    pattern,
+                });
+                self.layout.next(salt);
+                Ok(hit)
+            },
+            on_match,
+        )
+    }
+
+    pub(crate) fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        par_score(
+            options,
+            || self.layout.random(),
+            |salt, floor| {
+                let address = Address::Ethereum((self.address)(salt));
+                let score = scorer.score(&address);
+                let hit = (score >= floor).then_some(Scored {
+                    score,
+                    private_key: PrivateKey::Salt(*salt),
+                    address,
+                });
+                self.layout.next(salt);
+                Ok(hit)
+            },
+            board,
+        )
+    }
+
+    /// A random salt and the address it deploys to.
+    pub(crate) fn generate(&self) -> (PrivateKey, Address) {
+        let salt = self.layout.random();
+        (
+            PrivateKey::Salt(salt),
+            Address::Ethereum((self.address)(&salt)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let layout = SaltLayout::after(&[0xaa; 30]);
+        let mut salt = layout.random();
+        assert_eq!(salt[..30], [0xaa; 30]);
+        salt[30..].copy_from_slice(&[0x01, 0xff]);
+        layout.next(&mut salt);
+        assert_eq!(salt[29..], [0xaa, 0x02, 0x00]);
+        // Wraps within the mined bytes, leaving the fixed ones alone
+        salt[30..].copy_from_slice(&[0xff, 0xff]);
+        layout.next(&mut salt);
+        assert_eq!(salt[29..], [0xaa, 0x00, 0x00]);
+
+        assert!(SaltLayout::after(&[0; 40]).mined.is_empty());
+    }
+}
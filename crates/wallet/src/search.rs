@@ -0,0 +1,206 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use vanity_core::{CoreError, Leaderboard, Scored, SearchBudget, SearchOptions};
+
+/// Attempts a worker counts locally before recording them in the shared budget, which is
+/// also when it checks the budget's limits. Matches the key walkers' block size.
+const FLUSH_INTERVAL: u64 = 256;
+
+/// Runs `attempt` on every thread of the current rayon pool (the global one unless called
+/// inside [`rayon::ThreadPool::install`]) and hands each hit to `on_hit`, until `on_hit`
+/// returns `false`, an error occurs, or the search budget runs out.
+///
+/// `init` builds per-worker state (RNG handles, scratch buffers) once per thread, and again
+/// after each of its hits: walkers step from one key to the next, so the keys after a hit are
+/// its neighbours, and starting over from fresh random state keeps matches independent.
+/// All workers share one stop flag and the progress counter in `options`; hits are passed
+/// to `on_hit` one at a time, and hits racing a stop are dropped. Attempts are recorded every
+/// [`FLUSH_INTERVAL`], so cancellation, the deadline and `max_attempts` take effect at that
+/// granularity.
+pub(crate) fn par_search<S, T, I, F>(
+    options: &SearchOptions,
+    init: I,
+    attempt: F,
+    on_hit: &mut (dyn FnMut(T) -> bool + Send),
+) -> Result<(), CoreError>
+where
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S) -> Result<Option<T>, CoreError> + Sync,
+    T: Send,
+{
+    let budget = SearchBudget::new(options);
+    if budget.is_exhausted() {
+        return Ok(());
+    }
+
+    let stop = AtomicBool::new(false);
+    let on_hit = Mutex::new(on_hit);
+    let error: Mutex<Option<CoreError>> = Mutex::new(None);
+
+    rayon::broadcast(|_| {
+        let mut state = init();
+        let mut pending = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let result = attempt(&mut state);
+            pending += 1;
+            let keep_going =
+                pending < FLUSH_INTERVAL || budget.record(std::mem::take(&mut pending));
+
+            match result {
+                Ok(None) => {}
+                Ok(Some(hit)) => {
+                    let mut on_hit = on_hit.lock().expect("search callback poisoned");
+                    if !stop.load(Ordering::Relaxed) && !on_hit(hit) {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    drop(on_hit);
+                    state = init();
+                }
+                Err(e) => {
+                    // First error wins and stops every worker
+                    error
+                        .lock()
+                        .expect("search error poisoned")
+                        .get_or_insert(e);
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+            if !keep_going {
+                break;
+            }
+        }
+        if pending > 0 {
+            budget.record(pending);
+        }
+    });
+
+    match error.into_inner().expect("search error poisoned") {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Runs a best-of search with [`par_search`]: `attempt` gets the board's current floor and
+/// returns a candidate only when it scores at least that much, so the board's lock is taken
+/// for the rare keys that can still rank.
+pub(crate) fn par_score<S, I, F>(
+    options: &SearchOptions,
+    init: I,
+    attempt: F,
+    board: &mut Leaderboard,
+) -> Result<(), CoreError>
+where
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S, u32) -> Result<Option<Scored>, CoreError> + Sync,
+{
+    let floor = AtomicU32::new(board.floor());
+    par_search(
+        options,
+        init,
+        |state| attempt(state, floor.load(Ordering::Relaxed)),
+        &mut |scored| {
+            board.offer(scored);
+            floor.store(board.floor(), Ordering::Relaxed);
+            true
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_first_hit_stops_all_workers() {
+        let progress = Arc::new(AtomicU64::new(0));
+        let options = SearchOptions::with_progress(progress.clone());
+        let mut hits = Vec::new();
+        par_search(
+            &options,
+            || 0u32,
+            |n| {
+                *n += 1;
+                Ok((*n == 100).then_some(*n))
+            },
+            &mut |hit| {
+                hits.push(hit);
+                false
+            },
+        )
+        .unwrap();
+
+        assert_eq!(hits, [100]);
+        assert!(progress.load(Ordering::Relaxed) >= 100);
+    }
+
+    #[test]
+    fn test_state_restarts_after_hit() {
+        // A walker that hits on its third step is rebuilt, so every hit is a third step
+        let options = SearchOptions::default();
+        let mut hits = Vec::new();
+        par_search(
+            &options,
+            || 0u32,
+            |n| {
+                *n += 1;
+                Ok((*n >= 3).then_some(*n))
+            },
+            &mut |hit| {
+                hits.push(hit);
+                hits.len() < 10
+            },
+        )
+        .unwrap();
+        assert!(hits.iter().all(|&hit| hit == 3));
+    }
+
+    #[test]
+    fn test_budget_exhaustion_returns_none() {
+        let options = SearchOptions {
+            max_attempts: Some(1_000),
+            ..SearchOptions::default()
+        };
+        let mut hits = 0;
+        par_search(&options, || (), |_| Ok(None::<()>), &mut |_| {
+            hits += 1;
+            true
+        })
+        .unwrap();
+        assert_eq!(hits, 0);
+    }
+
+    #[test]
+    fn test_error_is_propagated() {
+        let options = SearchOptions::default();
+        let result = par_search(
+            &options,
+            || (),
+            |_| Err::<Option<()>, _>(CoreError::CryptoError("boom".to_string())),
+            &mut |_| true,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_hits_stream_until_callback_stops() {
+        // Every worker hits on each 10th attempt; the callback takes five hits
+        let options = SearchOptions::default();
+        let mut hits = Vec::new();
+        par_search(
+            &options,
+            || 0u32,
+            |n| {
+                *n += 1;
+                Ok((*n % 10 == 0).then_some(*n))
+            },
+            &mut |hit| {
+                hits.push(hit);
+                hits.len() < 5
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 5);
+    }
+}
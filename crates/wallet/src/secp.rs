@@ -0,0 +1,263 @@
+use k256::elliptic_curve::group::Group;
+use k256::elliptic_curve::PrimeField;
+use k256::elliptic_curve::{BatchNormalize, Field};
+use k256::{AffinePoint, ProjectivePoint, PublicKey, Scalar};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use vanity_core::{CoreError, PrivateKey};
+
+/// Default number of candidates normalised per shared field inversion.
+pub const DEFAULT_BLOCK_SIZE: usize = 256;
+
+/// Incremental secp256k1 key walker.
+///
+/// Starts from one random secret `k` and visits `k, k+1, k+2, …` by adding `G` to the previous
+/// public key, so each candidate costs one point addition instead of a full scalar multiplication.
+/// The secret of the current candidate is recovered as `k + offset`.
+///
+/// Points are produced in blocks of `block_size` projective points and converted to affine form
+/// together, so the block shares a single field inversion (Montgomery's trick).
+/// A block size of 1 normalises every candidate on its own.
+///
+/// Keys on one walk are related: start a new walk after a hit so two results never share a
+/// start key, as the parallel searches do.
+///
+/// A split-key walk ([`KeyWalker::random_split`]) visits `B + k·G` for someone else's public key
+/// `B` instead; the secrets it reports are only the `k` part.
+pub struct KeyWalker {
+    /// Point every candidate is offset by: the identity, or the split-key requester's key.
+    base: ProjectivePoint,
+    start: Scalar,
+    /// Offset (from `start`) of the first point in `block`.
+    block_offset: u64,
+    cursor: usize,
+    block: Vec<AffinePoint>,
+    scratch: Vec<ProjectivePoint>,
+    /// Slot of `block` holding a placeholder for the point at infinity, if any.
+    identity: Option<usize>,
+    /// Point for the key right after the current block.
+    next: ProjectivePoint,
+}
+
+impl KeyWalker {
+    /// Starts a walk at a uniformly random secret.
+    pub fn random(rng: &mut impl RngCore, block_size: usize) -> Self {
+        Self::from_scalar(Scalar::random(rng), block_size)
+    }
+
+    /// Starts a walk at the given secret.
+    pub fn from_scalar(start: Scalar, block_size: usize) -> Self {
+        Self::offset(ProjectivePoint::IDENTITY, start, block_size)
+    }
+
+    /// Starts a split-key walk over `base + k·G` at a uniformly random `k`. Whoever holds the
+    /// secret of `base` adds it to a result's `k` (see [`combine_split_key`]); the walk itself
+    /// never sees a usable key.
+    pub fn random_split(base: &PublicKey, rng: &mut impl RngCore, block_size: usize) -> Self {
+        Self::offset(base.to_projective(), Scalar::random(rng), block_size)
+    }
+
+    fn offset(base: ProjectivePoint, start: Scalar, block_size: usize) -> Self {
+        let block_size = block_size.max(1);
+        let mut walker = Self {
+            base,
+            start,
+            block_offset: 0,
+            cursor: 0,
+            block: Vec::new(),
+            scratch: vec![ProjectivePoint::IDENTITY; block_size],
+            identity: None,
+            next: base + ProjectivePoint::GENERATOR * start,
+        };
+        walker.fill_block();
+        walker.skip_identity();
+        walker
+    }
+
+    /// Affine public key of the current candidate.
+    #[inline]
+    pub fn public_key(&self) -> &AffinePoint {
+        &self.block[self.cursor]
+    }
+
+    /// Secret key of the current candidate: `start + offset` (only the `k` part on a split-key
+    /// walk).
+    pub fn secret(&self) -> Scalar {
+        self.start + Scalar::from(self.block_offset + self.cursor as u64)
+    }
+
+    /// The current candidate's key as a search result: `key` of the full secret on a plain
+    /// walk, or [`PrivateKey::Secp256k1Partial`] of the `k` part on a split-key walk.
+    pub fn private_key(&self, key: impl FnOnce([u8; 32]) -> PrivateKey) -> PrivateKey {
+        let secret = self.secret().to_bytes().into();
+        if bool::from(self.base.is_identity()) {
+            key(secret)
+        } else {
+            PrivateKey::Secp256k1Partial(secret)
+        }
+    }
+
+    /// Moves to the next key (`P + G`), normalising a new block when the current one is used up.
+    #[inline]
+    pub fn advance(&mut self, rng: &mut impl RngCore) {
+        self.cursor += 1;
+        if self.cursor == self.block.len() {
+            if self.block_offset > u64::MAX / 2 {
+                self.reseed(rng);
+                return;
+            }
+            self.block_offset += self.block.len() as u64;
+            self.cursor = 0;
+            self.fill_block();
+        }
+        self.skip_identity();
+    }
+
+    /// Restarts the walk from a fresh random secret, keeping a split-key base.
+    pub fn reseed(&mut self, rng: &mut impl RngCore) {
+        *self = Self::offset(self.base, Scalar::random(rng), self.scratch.len());
+    }
+
+    /// Computes `next, next+G, …` for one block and normalises them with one inversion.
+    fn fill_block(&mut self) {
+        self.identity = None;
+        let mut point = self.next;
+        for (i, slot) in self.scratch.iter_mut().enumerate() {
+            if bool::from(point.is_identity()) {
+                // A zero Z would poison the shared inversion; park a placeholder and skip the slot
+                self.identity = Some(i);
+                *slot = ProjectivePoint::GENERATOR;
+            } else {
+                *slot = point;
+            }
+            point += AffinePoint::GENERATOR;
+        }
+        self.next = point;
+        self.block = ProjectivePoint::batch_normalize(self.scratch.as_slice());
+    }
+
+    /// `k + offset == 0 (mod n)` only happens with negligible probability,
+    /// but the identity has no address, so step over it rather than emit it.
+    fn skip_identity(&mut self) {
+        while self.identity == Some(self.cursor) {
+            self.cursor += 1;
+            if self.cursor == self.block.len() {
+                self.block_offset += self.block.len() as u64;
+                self.cursor = 0;
+                self.fill_block();
+            }
+        }
+    }
+}
+
+/// Random walker for a search: over plain keys, or over `split_key + k·G` in split-key mode.
+pub(crate) fn random_walker(split_key: Option<&PublicKey>, block_size: usize) -> KeyWalker {
+    match split_key {
+        Some(base) => KeyWalker::random_split(base, &mut OsRng, block_size),
+        None => KeyWalker::random(&mut OsRng, block_size),
+    }
+}
+
+/// Final private key of a split-key search: the requester's `secret` plus the worker's
+/// `partial` key, modulo the curve order. Both are 32-byte big-endian scalars.
+pub fn combine_split_key(secret: &[u8; 32], partial: &[u8; 32]) -> Result<[u8; 32], CoreError> {
+    let scalar = |bytes: &[u8; 32], name: &str| {
+        Option::<Scalar>::from(Scalar::from_repr((*bytes).into()))
+            .ok_or_else(|| CoreError::CryptoError(format!("{} is not below the curve order", name)))
+    };
+    let sum = scalar(secret, "secret")? + scalar(partial, "partial key")?;
+    if bool::from(sum.is_zero()) {
+        return Err(CoreError::CryptoError(
+            "the partial key cancels the secret".to_string(),
+        ));
+    }
+    Ok(sum.to_bytes().into())
+}
+
+/// RIPEMD160(SHA256(data)), the key hash behind P2PKH, P2WPKH and Cosmos addresses.
+#[inline]
+pub(crate) fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::elliptic_curve::group::prime::PrimeCurveAffine;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_walk_matches_scalar_multiplication() {
+        // Cross several block boundaries, including a block size of 1 (per-key path)
+        for block_size in [1, 7, DEFAULT_BLOCK_SIZE] {
+            let mut walker = KeyWalker::random(&mut OsRng, block_size);
+            for _ in 0..300 {
+                let expected = (ProjectivePoint::GENERATOR * walker.secret()).to_affine();
+                assert_eq!(*walker.public_key(), expected);
+                walker.advance(&mut OsRng);
+            }
+        }
+    }
+
+    #[test]
+    fn test_walk_from_one() {
+        // 1·G, 2·G, 3·G are well-known points
+        let mut walker = KeyWalker::from_scalar(Scalar::ONE, 2);
+        walker.advance(&mut OsRng);
+        walker.advance(&mut OsRng);
+
+        assert_eq!(walker.secret(), Scalar::from(3u64));
+        let encoded = walker.public_key().to_encoded_point(true);
+        assert_eq!(
+            hex::encode(encoded.as_bytes()),
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+    }
+
+    #[test]
+    fn test_split_walk_combines() {
+        // Each candidate is base + k·G, so secret + k is its private key
+        let secret = Scalar::random(&mut OsRng);
+        let base =
+            PublicKey::from_affine((ProjectivePoint::GENERATOR * secret).to_affine()).unwrap();
+        let mut walker = KeyWalker::random_split(&base, &mut OsRng, 7);
+        for _ in 0..20 {
+            let partial: [u8; 32] = walker.secret().to_bytes().into();
+            let combined = combine_split_key(&secret.to_bytes().into(), &partial).unwrap();
+            let combined = Option::<Scalar>::from(Scalar::from_repr(combined.into())).unwrap();
+            assert_eq!(
+                *walker.public_key(),
+                (ProjectivePoint::GENERATOR * combined).to_affine()
+            );
+            walker.advance(&mut OsRng);
+        }
+        walker.reseed(&mut OsRng);
+        let combined = secret + walker.secret();
+        assert_eq!(
+            *walker.public_key(),
+            (ProjectivePoint::GENERATOR * combined).to_affine()
+        );
+
+        let one: [u8; 32] = Scalar::ONE.to_bytes().into();
+        let minus_one: [u8; 32] = (-Scalar::ONE).to_bytes().into();
+        assert!(combine_split_key(&one, &minus_one).is_err());
+        assert!(combine_split_key(&[0xff; 32], &one).is_err());
+    }
+
+    #[test]
+    fn test_walk_skips_identity() {
+        // Start at n - 2: the walk passes through the point at infinity inside a block
+        let mut walker = KeyWalker::from_scalar(-Scalar::from(2u64), 4);
+        for _ in 0..4 {
+            assert!(!bool::from(walker.public_key().is_identity()));
+            assert_eq!(
+                *walker.public_key(),
+                (ProjectivePoint::GENERATOR * walker.secret()).to_affine()
+            );
+            walker.advance(&mut OsRng);
+        }
+    }
+}
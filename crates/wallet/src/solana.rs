@@ -1,77 +1,119 @@
 use bs58;
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use rand::{rngs::OsRng, RngCore};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use vanity_core::{Address, PrivateKey, VanityGenerator};
+use vanity_core::{
+    Address, AddressFormat, Base58Prefix, Base58Suffix, CoreError, Leaderboard, Match, Pattern,
+    PatternError, PatternSet, PrivateKey, Scored, Scorer, SearchOptions, VanityGenerator,
+};
+
+use crate::ed25519::SplitWalker;
+use crate::search::{par_score, par_search};
 
 pub struct SolanaVanityGenerator {
-    prefix: String,
-    suffix: String,
-    case_sensitive: bool,
+    /// Compiled patterns, or the syntax error the search reports.
+    patterns: Result<PatternSet, PatternError>,
+    /// Byte-level prefilters (from the anchors all patterns share) so only likely hits are
+    /// Base58-encoded.
+    prefix_ranges: Base58Prefix,
+    suffix_residues: Base58Suffix,
+    /// Requester's public key in split-key mode.
+    split_key: Option<VerifyingKey>,
 }
 
 impl SolanaVanityGenerator {
     pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Self {
+        let pattern = AddressFormat::Solana.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from))
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern) -> Self {
+        Self::from_patterns(PatternSet::from(pattern))
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist.
+    pub fn from_patterns(patterns: PatternSet) -> Self {
+        Self::compiled(Ok(patterns))
+    }
+
+    fn compiled(patterns: Result<PatternSet, PatternError>) -> Self {
+        let envelope = patterns
+            .as_ref()
+            .map(PatternSet::envelope)
+            .unwrap_or_default();
         Self {
-            prefix: prefix.to_string(),
-            suffix: suffix.to_string(),
-            case_sensitive,
+            prefix_ranges: Base58Prefix::new(&envelope.prefix_classes(), 32),
+            suffix_residues: Base58Suffix::new(&envelope.suffix_classes()),
+            patterns,
+            split_key: None,
         }
     }
 
-    pub fn search(&self, found_flag: Option<Arc<AtomicU64>>) -> (PrivateKey, Address) {
-        let mut csprng = OsRng;
+    /// Split-key mode: matches the address of `public_key + k·B` and reports only the partial
+    /// scalar `k` as [`PrivateKey::Ed25519Partial`] (see
+    /// [`combine_ed25519_split_key`](crate::combine_ed25519_split_key)).
+    pub fn with_split_key(mut self, public_key: VerifyingKey) -> Self {
+        self.split_key = Some(public_key);
+        self
+    }
 
-        // Convert input prefix/suffix ref for loop to avoid borrow checker issues
-        let p_prefix = &self.prefix;
-        let p_suffix = &self.suffix;
-        let p_case = self.case_sensitive;
-
-        loop {
-            // Update attempts counter if provided
-            if let Some(ref attempts) = found_flag {
-                // Relaxed ordering is sufficient for stats
-                attempts.fetch_add(1, Ordering::Relaxed);
-            }
-
-            // 1. Generate Keypair
-            let (signing_key, verifying_key, address) = generate_one(&mut csprng);
-
-            // 2. Check Match
-            let mut is_match = true;
-
-            if !p_prefix.is_empty() {
-                if p_case {
-                    if !address.starts_with(p_prefix) {
-                        is_match = false;
-                    }
-                } else {
-                    // Optimized lowercase check for prefix
-                    if address.len() < p_prefix.len()
-                        || !address[..p_prefix.len()].eq_ignore_ascii_case(p_prefix)
-                    {
-                        is_match = false;
-                    }
-                }
-            }
-
-            if is_match && !p_suffix.is_empty() {
-                if p_case {
-                    if !address.ends_with(p_suffix) {
-                        is_match = false;
-                    }
-                } else {
-                    // Optimized lowercase check for suffix
-                    if address.len() < p_suffix.len()
-                        || !address[address.len() - p_suffix.len()..].eq_ignore_ascii_case(p_suffix)
-                    {
-                        is_match = false;
-                    }
-                }
-            }
+    /// Address of a public key: its Base58 encoding.
+    pub fn derive_address(public_key: &[u8; 32]) -> String {
+        bs58::encode(public_key).into_string()
+    }
+
+    /// Address and pattern index of a public key that matches, rejecting on the raw bytes
+    /// before any Base58 encoding.
+    #[inline]
+    fn find(&self, patterns: &PatternSet, pubkey: &[u8; 32]) -> Option<(String, usize)> {
+        if !self.prefix_ranges.contains(pubkey) || !self.suffix_residues.matches(pubkey) {
+            return None;
+        }
+        let address = Self::derive_address(pubkey);
+        patterns.find(&address).map(|pattern| (address, pattern))
+    }
+
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        let patterns = self.patterns.as_ref().map_err(|e| e.clone())?;
+        patterns.validate(&AddressFormat::Solana)?;
+
+        if let Some(base) = &self.split_key {
+            return par_search(
+                options,
+                || SplitWalker::random(base, &mut OsRng),
+                |walker| {
+                    let hit =
+                        self.find(patterns, &walker.public_key())
+                            .map(|(address, pattern)| Match {
+                                private_key: PrivateKey::Ed25519Partial(walker.partial()),
+                                address: Address::Solana(address),
+                                pattern,
+                            });
+                    walker.advance();
+                    Ok(hit)
+                },
+                on_match,
+            );
+        }
+
+        par_search(
+            options,
+            || OsRng,
+            |csprng| {
+                // 1. Generate Keypair
+                let (signing_key, verifying_key) = generate_keypair(csprng);
+
+                // 2. Reject on the raw public key, encode only candidates that pass
+                let Some((address, pattern)) = self.find(patterns, verifying_key.as_bytes()) else {
+                    return Ok(None);
+                };
 
-            if is_match {
                 // Get 32-byte seed
                 let secret_bytes = signing_key.to_bytes();
 
@@ -80,15 +122,78 @@ impl SolanaVanityGenerator {
                 full_keypair[..32].copy_from_slice(&secret_bytes);
                 full_keypair[32..].copy_from_slice(verifying_key.as_bytes());
 
-                return (PrivateKey::Solana(full_keypair), Address::Solana(address));
-            }
+                Ok(Some(Match {
+                    private_key: PrivateKey::Solana(full_keypair),
+                    address: Address::Solana(address),
+                    pattern,
+                }))
+            },
+            on_match,
+        )
+    }
+
+    /// Keeps the best-scoring keys in `board`; see [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        if let Some(base) = &self.split_key {
+            return par_score(
+                options,
+                || SplitWalker::random(base, &mut OsRng),
+                |walker, floor| {
+                    let address = Address::Solana(Self::derive_address(&walker.public_key()));
+                    let score = scorer.score(&address);
+                    let hit = (score >= floor).then(|| Scored {
+                        score,
+                        private_key: PrivateKey::Ed25519Partial(walker.partial()),
+                        address,
+                    });
+                    walker.advance();
+                    Ok(hit)
+                },
+                board,
+            );
         }
+
+        par_score(
+            options,
+            || OsRng,
+            |csprng, floor| {
+                let (signing_key, verifying_key) = generate_keypair(csprng);
+                let address = Address::Solana(bs58::encode(verifying_key.as_bytes()).into_string());
+                let score = scorer.score(&address);
+                if score < floor {
+                    return Ok(None);
+                }
+
+                // Standard 64-byte Solana private key format (seed + pubkey)
+                let mut full_keypair = [0u8; 64];
+                full_keypair[..32].copy_from_slice(&signing_key.to_bytes());
+                full_keypair[32..].copy_from_slice(verifying_key.as_bytes());
+                Ok(Some(Scored {
+                    score,
+                    private_key: PrivateKey::Solana(full_keypair),
+                    address,
+                }))
+            },
+            board,
+        )
     }
 }
 
 impl VanityGenerator for SolanaVanityGenerator {
     fn generate(&self) -> (PrivateKey, Address) {
         let mut csprng = OsRng;
+        if let Some(base) = &self.split_key {
+            let walker = SplitWalker::random(base, &mut csprng);
+            return (
+                PrivateKey::Ed25519Partial(walker.partial()),
+                Address::Solana(Self::derive_address(&walker.public_key())),
+            );
+        }
         let (signing_key, verifying_key, address) = generate_one(&mut csprng);
 
         let mut full_keypair = [0u8; 64];
@@ -98,14 +203,37 @@ impl VanityGenerator for SolanaVanityGenerator {
 
         (PrivateKey::Solana(full_keypair), Address::Solana(address))
     }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        SolanaVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        SolanaVanityGenerator::search_scored(self, options, scorer, board)
+    }
 }
 
-/// Generates a single internal Solana keypair + address.
-fn generate_one(csprng: &mut OsRng) -> (SigningKey, VerifyingKey, String) {
+/// Generates a single internal Solana keypair.
+fn generate_keypair(csprng: &mut OsRng) -> (SigningKey, VerifyingKey) {
     let mut secret_bytes = [0u8; 32];
     csprng.fill_bytes(&mut secret_bytes);
     let signing_key = SigningKey::from_bytes(&secret_bytes);
     let verifying_key: VerifyingKey = signing_key.verifying_key();
+    (signing_key, verifying_key)
+}
+
+/// Generates a single internal Solana keypair + address.
+fn generate_one(csprng: &mut OsRng) -> (SigningKey, VerifyingKey, String) {
+    let (signing_key, verifying_key) = generate_keypair(csprng);
     let address = bs58::encode(verifying_key.as_bytes()).into_string();
     (signing_key, verifying_key, address)
 }
@@ -134,11 +262,120 @@ mod tests {
     #[test]
     fn test_vanity_search() {
         let gen = SolanaVanityGenerator::new("A", "", true);
-        let (_, addr) = gen.search(None);
+        let (_, addr) = gen
+            .search(&SearchOptions::default())
+            .unwrap()
+            .expect("unbounded search finds a match");
 
         match addr {
             Address::Solana(s) => assert!(s.starts_with("A")),
             _ => panic!("Wrong address type"),
         }
     }
+
+    #[test]
+    fn test_pattern_set_search() {
+        let words = ["Zz", "yY", "?x"].map(|p| AddressFormat::Solana.compile(p, "", true).unwrap());
+        let gen = SolanaVanityGenerator::from_patterns(PatternSet::new(words.to_vec()));
+        let found = gen
+            .search_match(&SearchOptions::default())
+            .unwrap()
+            .unwrap();
+
+        let address = found.address.to_string();
+        assert!(words[found.pattern].matches(&address), "{address}");
+        assert!(words[..found.pattern].iter().all(|p| !p.matches(&address)));
+    }
+
+    #[test]
+    fn test_search_scored_with_custom_scorer() {
+        // Count of uppercase letters, a rule the built-in scores do not cover
+        let uppercase = |address: &Address| {
+            address
+                .to_string()
+                .chars()
+                .filter(char::is_ascii_uppercase)
+                .count() as u32
+        };
+        let options = SearchOptions {
+            max_attempts: Some(2_000),
+            ..SearchOptions::default()
+        };
+        let mut board = Leaderboard::new(2);
+        SolanaVanityGenerator::new("", "", false)
+            .search_scored(&options, &uppercase, &mut board)
+            .unwrap();
+
+        let entries = board.into_entries();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].score >= entries[1].score);
+        assert!(entries.iter().all(|e| uppercase(&e.address) == e.score));
+    }
+
+    #[test]
+    fn test_split_key_search() {
+        // The worker only sees the requester's public key; seed + partial is the real key
+        let seed = [0x5e; 32];
+        let public_key = SigningKey::from_bytes(&seed).verifying_key();
+        let gen = SolanaVanityGenerator::new("A", "", true).with_split_key(public_key);
+        let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Ed25519Partial(partial) = pk else {
+            panic!("Wrong key type");
+        };
+        let combined = crate::combine_ed25519_split_key(&seed, &partial).unwrap();
+        let combined_key = crate::verify_expanded_key(&combined).unwrap();
+        assert_eq!(
+            Address::Solana(SolanaVanityGenerator::derive_address(
+                combined_key.as_bytes()
+            )),
+            addr
+        );
+        assert!(addr.to_string().starts_with('A'));
+    }
+
+    #[test]
+    fn test_search_respects_limits() {
+        // 8 leading '1's is practically unreachable in a handful of attempts
+        let gen = SolanaVanityGenerator::new("11111111", "", true);
+        let options = SearchOptions {
+            max_attempts: Some(16),
+            ..SearchOptions::default()
+        };
+        assert!(gen.search(&options).unwrap().is_none());
+
+        let options = SearchOptions::default();
+        options.cancel.cancel();
+        assert!(gen.search(&options).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prefilter_search() {
+        // Leading '1' (a zero byte), case-insensitive prefix, a suffix and classes through the
+        // byte filters
+        let cases = [
+            ("1", "", true),
+            ("ab", "", false),
+            ("", "Zz", true),
+            ("[xyz]?\\d", "\\u", true),
+        ];
+        for (prefix, suffix, case_sensitive) in cases {
+            let gen = SolanaVanityGenerator::new(prefix, suffix, case_sensitive);
+            let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+            let (PrivateKey::Solana(keypair), Address::Solana(addr)) = (pk, addr) else {
+                panic!("Wrong key or address type");
+            };
+
+            assert_eq!(bs58::encode(&keypair[32..]).into_string(), addr);
+            assert!(
+                gen.patterns.as_ref().unwrap().find(&addr).is_some(),
+                "{addr} does not match {prefix}/{suffix}"
+            );
+        }
+
+        // '0' is not a Base58 digit
+        let gen = SolanaVanityGenerator::new("0x", "", true);
+        assert!(gen.search(&SearchOptions::default()).is_err());
+        let gen = SolanaVanityGenerator::new("", "a0", false);
+        assert!(gen.search(&SearchOptions::default()).is_err());
+    }
 }
@@ -0,0 +1,6 @@
+/// Decodes `text`, with or without `0x`, into exactly `N` bytes.
+pub(crate) fn bytes<const N: usize>(text: &str) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    hex::decode_to_slice(text.trim_start_matches("0x"), &mut bytes).unwrap();
+    bytes
+}
@@ -2,9 +2,14 @@ use base64::{engine::general_purpose, Engine as _};
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use rand::{rngs::OsRng, RngCore};
 use sha2::{Digest, Sha256};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use vanity_core::{Address, PrivateKey, TonWalletVersion, VanityGenerator};
+use vanity_core::{
+    ton_tag, Address, AddressFormat, Base64Pattern, CoreError, Leaderboard, Match, Pattern,
+    PatternError, PatternSet, PrivateKey, Scored, Scorer, SearchOptions, TonWalletVersion,
+    VanityGenerator, TON_TAG_BOUNCEABLE, TON_TAG_NON_BOUNCEABLE,
+};
+
+use crate::ed25519::SplitWalker;
+use crate::search::{par_score, par_search};
 
 // --- CONSTANTS FOR WALLET V4R2 ---
 
@@ -35,11 +40,19 @@ const DATA_HEAD_V5R1: [u8; 10] = [0x00, 0x51, 0x80, 0x00, 0x00, 0x00, 0x3F, 0xFF
 // Code Depth: 6. Data Depth: 0.
 const STATE_INIT_HEAD_V5R1: [u8; 7] = [0x02, 0x01, 0x34, 0x00, 0x06, 0x00, 0x00];
 
+// --- USER-FRIENDLY ADDRESS ---
+
+const CRC16: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
+
 pub struct TonVanityGenerator {
-    prefix: String,
-    suffix: String,
-    case_sensitive: bool,
+    /// Compiled patterns, or the syntax error the search reports.
+    patterns: Result<PatternSet, PatternError>,
     version: TonWalletVersion,
+    /// Each tag the patterns ask for, with the anchors its patterns share as bit constraints
+    /// on the state-init hash and the CRC.
+    forms: Vec<(u8, Base64Pattern)>,
+    /// Requester's public key in split-key mode.
+    split_key: Option<VerifyingKey>,
 }
 
 impl TonVanityGenerator {
@@ -49,111 +62,230 @@ impl TonVanityGenerator {
         case_sensitive: bool,
         version: TonWalletVersion,
     ) -> Self {
+        let pattern = AddressFormat::Ton.compile(prefix, suffix, case_sensitive);
+        Self::compiled(pattern.map(PatternSet::from), version)
+    }
+
+    /// Searches for a compiled pattern, e.g. one with `contains`/`exclude` substrings.
+    pub fn from_pattern(pattern: Pattern, version: TonWalletVersion) -> Self {
+        Self::from_patterns(PatternSet::from(pattern), version)
+    }
+
+    /// Searches for any pattern of a set, e.g. a wordlist; `EQ` and `UQ` patterns may be mixed.
+    pub fn from_patterns(patterns: PatternSet, version: TonWalletVersion) -> Self {
+        Self::compiled(Ok(patterns), version)
+    }
+
+    fn compiled(patterns: Result<PatternSet, PatternError>, version: TonWalletVersion) -> Self {
+        let compiled = patterns
+            .as_ref()
+            .map(PatternSet::patterns)
+            .unwrap_or_default();
+        let mut forms = Vec::new();
+        // Bounceable (EQ) addresses are requested by an 'E' prefix
+        for tag in [TON_TAG_NON_BOUNCEABLE, TON_TAG_BOUNCEABLE] {
+            let group: Vec<Pattern> = compiled
+                .iter()
+                .filter(|pattern| ton_tag(pattern) == tag)
+                .cloned()
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            let envelope = Pattern::envelope(&group);
+            // Tag and workchain 0 fix the first characters; the CRC covers the last 16 bits
+            let (prefix, suffix) = (envelope.prefix_classes(), envelope.suffix_classes());
+            forms.push((
+                tag,
+                Base64Pattern::new(&[tag, 0x00], 32, 2, &prefix, &suffix),
+            ));
+        }
+
         Self {
-            prefix: prefix.to_string(),
-            suffix: suffix.to_string(),
-            case_sensitive,
+            patterns,
             version,
+            forms,
+            split_key: None,
         }
     }
 
-    pub fn search(&self, found_flag: Option<Arc<AtomicU64>>) -> (PrivateKey, Address) {
-        let mut csprng = OsRng;
-        let p_prefix = &self.prefix;
-        let p_suffix = &self.suffix;
-        let p_case = self.case_sensitive;
-
-        loop {
-            // Update stats
-            if let Some(ref attempts) = found_flag {
-                attempts.fetch_add(1, Ordering::Relaxed);
-            }
+    /// Split-key mode: matches the wallet of `public_key + k·B` and reports only the partial
+    /// scalar `k` as [`PrivateKey::Ed25519Partial`] (see
+    /// [`combine_ed25519_split_key`](crate::combine_ed25519_split_key)).
+    pub fn with_split_key(mut self, public_key: VerifyingKey) -> Self {
+        self.split_key = Some(public_key);
+        self
+    }
 
-            // 1. Generate Keypair
-            let (signing_key, verifying_key) = generate_ed25519(&mut csprng);
-            let pubkey_bytes = verifying_key.as_bytes();
-
-            // 2 & 3. Compute StateInit Hash (via helper)
-            let state_init_hash = self.compute_state_init_hash(pubkey_bytes);
-
-            // 4. Encode Address (Base64 URL Safe) to check match
-            let tag = if !p_prefix.is_empty() && p_prefix.starts_with('E') {
-                0x11
-            } else {
-                0x51
-            };
-            let address_str = encode_ton_address(&state_init_hash, tag);
-
-            // 5. Check Match
-            let mut is_match = true;
-            if !p_prefix.is_empty() {
-                if p_case {
-                    if !address_str.starts_with(p_prefix) {
-                        is_match = false;
-                    }
-                } else if address_str.len() < p_prefix.len()
-                    || !address_str[..p_prefix.len()].eq_ignore_ascii_case(p_prefix)
-                {
-                    is_match = false;
-                }
-            }
+    /// Non-bounceable (`UQ…`) address of the `version` wallet for a public key.
+    pub fn derive_address(version: TonWalletVersion, public_key: &[u8; 32]) -> String {
+        encode_ton_address(
+            &state_init_hash(version, public_key),
+            TON_TAG_NON_BOUNCEABLE,
+        )
+    }
+
+    /// Bounceable (`EQ…`) address of the `version` wallet for a public key.
+    pub fn derive_bounceable_address(version: TonWalletVersion, public_key: &[u8; 32]) -> String {
+        encode_ton_address(&state_init_hash(version, public_key), TON_TAG_BOUNCEABLE)
+    }
+
+    /// Run the search on all cores; each hit is tagged with the pattern it matched.
+    /// See [`VanityGenerator::search_each`].
+    pub fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        let patterns = self.patterns.as_ref().map_err(|e| e.clone())?;
+        patterns.validate(&AddressFormat::Ton)?;
+
+        if let Some(base) = &self.split_key {
+            return par_search(
+                options,
+                || SplitWalker::random(base, &mut OsRng),
+                |walker| {
+                    let hit =
+                        self.find(patterns, &walker.public_key())
+                            .map(|(address, pattern)| Match {
+                                private_key: PrivateKey::Ed25519Partial(walker.partial()),
+                                address: Address::Ton(address),
+                                pattern,
+                            });
+                    walker.advance();
+                    Ok(hit)
+                },
+                on_match,
+            );
+        }
 
-            if is_match && !p_suffix.is_empty() {
-                if p_case {
-                    if !address_str.ends_with(p_suffix) {
-                        is_match = false;
-                    }
-                } else if address_str.len() < p_suffix.len()
-                    || !address_str[address_str.len() - p_suffix.len()..]
-                        .eq_ignore_ascii_case(p_suffix)
-                {
-                    is_match = false;
+        par_search(
+            options,
+            || OsRng,
+            |csprng| {
+                // 1. Generate Keypair
+                let (signing_key, verifying_key) = generate_ed25519(csprng);
+
+                // 2-5. Hash the state init, check it per tag and encode the candidates
+                Ok(self
+                    .find(patterns, verifying_key.as_bytes())
+                    .map(|(address, pattern)| Match {
+                        // Store as 32-byte secret seed (standard Ed25519 persistence)
+                        private_key: PrivateKey::Ton(signing_key.to_bytes()),
+                        address: Address::Ton(address),
+                        pattern,
+                    }))
+            },
+            on_match,
+        )
+    }
+
+    /// Keeps the best-scoring keys in `board`, scoring the non-bounceable (`UQ…`) form; see
+    /// [`VanityGenerator::search_scored`].
+    pub fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        if let Some(base) = &self.split_key {
+            return par_score(
+                options,
+                || SplitWalker::random(base, &mut OsRng),
+                |walker, floor| {
+                    let address =
+                        Address::Ton(Self::derive_address(self.version, &walker.public_key()));
+                    let score = scorer.score(&address);
+                    let hit = (score >= floor).then(|| Scored {
+                        score,
+                        private_key: PrivateKey::Ed25519Partial(walker.partial()),
+                        address,
+                    });
+                    walker.advance();
+                    Ok(hit)
+                },
+                board,
+            );
+        }
+
+        par_score(
+            options,
+            || OsRng,
+            |csprng, floor| {
+                let (signing_key, verifying_key) = generate_ed25519(csprng);
+                let address =
+                    Address::Ton(Self::derive_address(self.version, verifying_key.as_bytes()));
+                let score = scorer.score(&address);
+                Ok((score >= floor).then(|| Scored {
+                    score,
+                    private_key: PrivateKey::Ton(signing_key.to_bytes()),
+                    address,
+                }))
+            },
+            board,
+        )
+    }
+
+    /// Address and pattern index of a public key that matches. Each tag's hash bits are
+    /// checked first, and the CRC only if a suffix reaches into it.
+    fn find(&self, patterns: &PatternSet, pubkey: &[u8; 32]) -> Option<(String, usize)> {
+        let state_init_hash = state_init_hash(self.version, pubkey);
+        for &(tag, ref bits) in &self.forms {
+            if !bits.matches_body(&state_init_hash) {
+                continue;
+            }
+            if bits.needs_checksum() {
+                let checksum = address_checksum(&state_init_hash, tag);
+                if !bits.matches_checksum(&checksum) {
+                    continue;
                 }
             }
 
-            if is_match {
-                // Return result
-                let secret_bytes = signing_key.to_bytes();
-                // Store as 32-byte secret seed (standard Ed25519 persistence)
-                return (PrivateKey::Ton(secret_bytes), Address::Ton(address_str));
+            // Encode Address (Base64 URL Safe) and confirm the match
+            let address = encode_ton_address(&state_init_hash, tag);
+            if let Some(pattern) = patterns.find(&address) {
+                return Some((address, pattern));
             }
         }
+        None
     }
-    fn compute_state_init_hash(&self, pubkey_bytes: &[u8]) -> [u8; 32] {
-        match self.version {
-            TonWalletVersion::V5R1 => {
-                // 2. Compute Data Hash for V5R1
-                let mut hasher = Sha256::new();
-                hasher.update(DATA_HEAD_V5R1);
-                hasher.update([0x80 | (pubkey_bytes[0] >> 1)]);
-                for i in 0..31 {
-                    hasher.update([(pubkey_bytes[i] << 7) | (pubkey_bytes[i + 1] >> 1)]);
-                }
-                hasher.update([(pubkey_bytes[31] << 7) | 0x20]);
-                let data_hash = hasher.finalize();
-
-                // 3. Compute StateInit Hash for V5R1
-                let mut hasher = Sha256::new();
-                hasher.update(STATE_INIT_HEAD_V5R1);
-                hasher.update(CODE_HASH_V5R1);
-                hasher.update(data_hash);
-                hasher.finalize().into()
-            }
-            TonWalletVersion::V4R2 => {
-                // 2. Compute Data Hash for V4R2
-                let mut hasher = Sha256::new();
-                hasher.update(DATA_HEAD);
-                hasher.update(pubkey_bytes);
-                hasher.update(DATA_TAIL);
-                let data_hash = hasher.finalize();
-
-                // 3. Compute StateInit Hash for V4R2
-                let mut hasher = Sha256::new();
-                hasher.update(STATE_INIT_HEAD);
-                hasher.update(CODE_HASH_V4R2);
-                hasher.update(data_hash);
-                hasher.finalize().into()
+}
+
+/// Hash of the wallet's state init (code and data cells), which is the account id.
+fn state_init_hash(version: TonWalletVersion, pubkey_bytes: &[u8]) -> [u8; 32] {
+    match version {
+        TonWalletVersion::V5R1 => {
+            // 2. Compute Data Hash for V5R1
+            let mut hasher = Sha256::new();
+            hasher.update(DATA_HEAD_V5R1);
+            hasher.update([0x80 | (pubkey_bytes[0] >> 1)]);
+            for i in 0..31 {
+                hasher.update([(pubkey_bytes[i] << 7) | (pubkey_bytes[i + 1] >> 1)]);
             }
+            hasher.update([(pubkey_bytes[31] << 7) | 0x20]);
+            let data_hash = hasher.finalize();
+
+            // 3. Compute StateInit Hash for V5R1
+            let mut hasher = Sha256::new();
+            hasher.update(STATE_INIT_HEAD_V5R1);
+            hasher.update(CODE_HASH_V5R1);
+            hasher.update(data_hash);
+            hasher.finalize().into()
+        }
+        TonWalletVersion::V4R2 => {
+            // 2. Compute Data Hash for V4R2
+            let mut hasher = Sha256::new();
+            hasher.update(DATA_HEAD);
+            hasher.update(pubkey_bytes);
+            hasher.update(DATA_TAIL);
+            let data_hash = hasher.finalize();
+
+            // 3. Compute StateInit Hash for V4R2
+            let mut hasher = Sha256::new();
+            hasher.update(STATE_INIT_HEAD);
+            hasher.update(CODE_HASH_V4R2);
+            hasher.update(data_hash);
+            hasher.finalize().into()
         }
     }
 }
@@ -166,17 +298,20 @@ fn generate_ed25519(csprng: &mut OsRng) -> (SigningKey, VerifyingKey) {
     (signing_key, verifying_key)
 }
 
+/// CRC16-XMODEM over tag, workchain 0 and the hash: the last two bytes of the address.
+fn address_checksum(hash: &[u8], tag: u8) -> [u8; 2] {
+    let mut digest = CRC16.digest();
+    digest.update(&[tag, 0x00]);
+    digest.update(hash);
+    digest.finalize().to_be_bytes()
+}
+
 fn encode_ton_address(hash: &[u8], tag: u8) -> String {
     let mut bytes = Vec::with_capacity(36);
     bytes.push(tag);
     bytes.push(0x00); // Workchain 0
     bytes.extend_from_slice(hash);
-
-    let crc = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
-    let checksum = crc.checksum(&bytes);
-
-    bytes.push((checksum >> 8) as u8);
-    bytes.push((checksum & 0xFF) as u8);
+    bytes.extend_from_slice(&address_checksum(hash, tag));
 
     general_purpose::URL_SAFE.encode(&bytes)
 }
@@ -184,16 +319,36 @@ fn encode_ton_address(hash: &[u8], tag: u8) -> String {
 impl VanityGenerator for TonVanityGenerator {
     fn generate(&self) -> (PrivateKey, Address) {
         let mut csprng = OsRng;
+        if let Some(base) = &self.split_key {
+            let walker = SplitWalker::random(base, &mut csprng);
+            return (
+                PrivateKey::Ed25519Partial(walker.partial()),
+                Address::Ton(Self::derive_address(self.version, &walker.public_key())),
+            );
+        }
         let (signing_key, verifying_key) = generate_ed25519(&mut csprng);
-        let pubkey_bytes = verifying_key.as_bytes();
-
-        let state_init_hash = self.compute_state_init_hash(pubkey_bytes);
-
-        let address = encode_ton_address(&state_init_hash, 0x51);
+        let address = Self::derive_address(self.version, verifying_key.as_bytes());
         let secret_bytes = signing_key.to_bytes();
         // Return 32-byte seed
         (PrivateKey::Ton(secret_bytes), Address::Ton(address))
     }
+
+    fn search_each(
+        &self,
+        options: &SearchOptions,
+        on_match: &mut (dyn FnMut(Match) -> bool + Send),
+    ) -> Result<(), CoreError> {
+        TonVanityGenerator::search_each(self, options, on_match)
+    }
+
+    fn search_scored(
+        &self,
+        options: &SearchOptions,
+        scorer: &dyn Scorer,
+        board: &mut Leaderboard,
+    ) -> Result<(), CoreError> {
+        TonVanityGenerator::search_scored(self, options, scorer, board)
+    }
 }
 
 #[cfg(test)]
@@ -323,7 +478,7 @@ mod tests {
     #[test]
     fn test_ton_vanity_search() {
         let gen = TonVanityGenerator::new("UQA", "", true, TonWalletVersion::V4R2);
-        let (__pk, addr) = gen.search(None);
+        let (__pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
         let addr_str = addr.to_string();
 
         assert!(addr_str.starts_with("UQA"));
@@ -332,18 +487,36 @@ mod tests {
     #[test]
     fn test_ton_vanity_search_bounceable() {
         let gen = TonVanityGenerator::new("EQA", "", true, TonWalletVersion::V4R2);
-        let (__pk, addr) = gen.search(None);
+        let (__pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
         let addr_str = addr.to_string();
 
         assert!(addr_str.starts_with("EQA"));
     }
 
+    #[test]
+    fn test_ton_pattern_set_mixes_tags() {
+        let words =
+            ["UQAx", "EQB", "UQ?{2}y"].map(|p| AddressFormat::Ton.compile(p, "", true).unwrap());
+        let gen = TonVanityGenerator::from_patterns(
+            PatternSet::new(words.to_vec()),
+            TonWalletVersion::V4R2,
+        );
+        assert_eq!(gen.forms.len(), 2);
+
+        let found = gen
+            .search_match(&SearchOptions::default())
+            .unwrap()
+            .unwrap();
+        let address = found.address.to_string();
+        assert!(words[found.pattern].matches(&address), "{address}");
+    }
+
     #[test]
     fn test_ton_suffix_search() {
         // Search for a suffix, e.g., "A" (very fast)
         // With case sensitivity FALSE
         let gen = TonVanityGenerator::new("", "A", false, TonWalletVersion::V4R2);
-        let (__pk, addr) = gen.search(None);
+        let (__pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
         let addr_str = addr.to_string();
 
         assert!(addr_str.to_uppercase().ends_with('A'));
@@ -355,9 +528,43 @@ mod tests {
         // Search for a suffix match which is valid and tests case sensitivity.
         // Searching for "x" (lowercase) at the end.
         let gen = TonVanityGenerator::new("UQ", "x", true, TonWalletVersion::V5R1);
-        let (__pk, addr) = gen.search(None);
+        let (__pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
         let addr_str = addr.to_string();
 
         assert!(addr_str.ends_with('x'));
     }
+
+    #[test]
+    fn test_split_key_search() {
+        // The worker only sees the requester's public key; seed + partial is the real key
+        let seed = [0x70; 32];
+        let public_key = SigningKey::from_bytes(&seed).verifying_key();
+        let gen = TonVanityGenerator::new("UQA", "", true, TonWalletVersion::V5R1)
+            .with_split_key(public_key);
+        let (pk, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        let PrivateKey::Ed25519Partial(partial) = pk else {
+            panic!("Wrong key type");
+        };
+        let combined = crate::combine_ed25519_split_key(&seed, &partial).unwrap();
+        let combined_key = crate::verify_expanded_key(&combined).unwrap();
+        assert_eq!(
+            TonVanityGenerator::derive_address(TonWalletVersion::V5R1, combined_key.as_bytes()),
+            addr.to_string()
+        );
+        assert!(addr.to_string().starts_with("UQA"));
+    }
+
+    #[test]
+    fn test_ton_checksum_suffix_and_impossible_prefix() {
+        // The last two characters come from the CRC only
+        let gen = TonVanityGenerator::new("", "Ab", true, TonWalletVersion::V5R1);
+        let (_, addr) = gen.search(&SearchOptions::default()).unwrap().unwrap();
+        assert!(addr.to_string().ends_with("Ab"));
+
+        // Workchain 0 only allows 'A'-'D' after "UQ"/"EQ"
+        for prefix in ["UQE", "EQa", "Q"] {
+            let gen = TonVanityGenerator::new(prefix, "", true, TonWalletVersion::V4R2);
+            assert!(gen.search(&SearchOptions::default()).is_err(), "{prefix}");
+        }
+    }
 }